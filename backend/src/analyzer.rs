@@ -27,6 +27,71 @@ pub struct AnalysisResult {
     pub type_inference: HashMap<String, String>,
 }
 
+/// A field as seen from a particular class: declared there or inherited.
+#[derive(Debug, Clone, Copy)]
+pub struct EffectiveField<'a> {
+    /// Class that declares the field
+    pub declaring_class: &'a str,
+    /// The declaration itself
+    pub field: &'a JavaField,
+    /// A subclass further down the chain redeclares the same name
+    pub hidden: bool,
+}
+
+impl AnalysisResult {
+    /// Look up a class by name.
+    pub fn class(&self, name: &str) -> Option<&JavaClass> {
+        self.classes.iter().find(|c| c.name == name)
+    }
+
+    /// `class_name` followed by its superclasses, as far as they are
+    /// declared in the analyzed source.
+    pub fn superclass_chain(&self, class_name: &str) -> Vec<&JavaClass> {
+        let mut chain: Vec<&JavaClass> = Vec::new();
+        let mut current = self.class(class_name);
+        while let Some(class) = current {
+            // Stop on a cyclic hierarchy instead of looping forever
+            if chain.iter().any(|c| c.name == class.name) {
+                break;
+            }
+            chain.push(class);
+            current = class.extends.as_deref().and_then(|name| self.class(name));
+        }
+        chain
+    }
+
+    /// Every field an instance of `class_name` carries: those inherited from
+    /// the topmost superclass first, then each subclass down to
+    /// `class_name`, each group in declaration order. A superclass field whose
+    /// name is redeclared further down is kept but marked `hidden`.
+    pub fn effective_fields(&self, class_name: &str) -> Vec<EffectiveField<'_>> {
+        let chain = self.superclass_chain(class_name);
+        let mut fields = Vec::new();
+        for (depth, class) in chain.iter().enumerate().rev() {
+            for field in &class.fields {
+                let hidden = chain[..depth]
+                    .iter()
+                    .any(|sub| sub.fields.iter().any(|f| f.name == field.name));
+                fields.push(EffectiveField {
+                    declaring_class: &class.name,
+                    field,
+                    hidden,
+                });
+            }
+        }
+        fields
+    }
+
+    /// Class declaring the field that `field_name` names when used from
+    /// `class_name`: the nearest declaration walking up the superclass chain.
+    pub fn field_declaring_class(&self, class_name: &str, field_name: &str) -> Option<&str> {
+        self.superclass_chain(class_name)
+            .into_iter()
+            .find(|class| class.fields.iter().any(|f| f.name == field_name))
+            .map(|class| class.name.as_str())
+    }
+}
+
 impl Default for JavaAnalyzer {
     fn default() -> Self {
         Self::new()
@@ -389,7 +454,15 @@ impl JavaAnalyzer {
         let mut interfaces = Vec::new();
         let mut cursor = interfaces_node.walk();
         for child in interfaces_node.children(&mut cursor).skip(1) {
-            interfaces.push(node_text(&child, source).to_string());
+            // `implements A, B` lists the interfaces in a `type_list`
+            if child.kind() == "type_list" {
+                let mut list_cursor = child.walk();
+                for interface in child.named_children(&mut list_cursor) {
+                    interfaces.push(node_text(&interface, source).to_string());
+                }
+            } else {
+                interfaces.push(node_text(&child, source).to_string());
+            }
         }
         interfaces
     }
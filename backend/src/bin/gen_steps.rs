@@ -16,7 +16,7 @@ fn main() {
         })
         .expect("no work concat");
 
-    let steps = execution_flow_gen(&java_code);
+    let steps = execution_flow_gen(&java_code, None);
     for (i, graph) in steps.into_iter().enumerate() {
         let mut file =
             std::fs::File::create_new(format!("graph_{}.dot", i)).expect("unable to create file");
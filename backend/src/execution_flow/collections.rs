@@ -0,0 +1,452 @@
+use super::evaluator::Value;
+
+/// A JDK collection class modeled on the heap instead of being stepped
+/// into. Lists and sets keep their elements in an `elementData` array and
+/// maps their keys and values in `keys` and `values` arrays, so the diagram
+/// shows the contents as element arrays. The arrays are sized to the
+/// contents rather than to a capacity, and `HashSet` and `HashMap` keep
+/// insertion order instead of hash order.
+///
+/// A class is modeled by implementing this trait and listing it in
+/// [`COLLECTIONS`].
+pub trait Collection: Sync {
+    /// Simple name of the class.
+    fn name(&self) -> &'static str;
+
+    /// Whether the class maps keys to values rather than holding elements.
+    fn is_map(&self) -> bool {
+        false
+    }
+
+    /// Fields of an instance that refer to its arrays, in layout order.
+    fn array_fields(&self) -> &'static [&'static str] {
+        if self.is_map() {
+            &["keys", "values"]
+        } else {
+            &["elementData"]
+        }
+    }
+
+    /// Result of the class's own method `method`, as
+    /// [`call_collection_method`] gives it; `size`, `isEmpty` and `clear`
+    /// are handled there for every class.
+    fn call(
+        &self,
+        contents: &mut Contents,
+        method: &str,
+        args: &[Value],
+        equals: &dyn Fn(&Value, &Value) -> Option<bool>,
+    ) -> Option<Result<Option<Value>, CollectionFault>>;
+}
+
+/// The modeled collection classes.
+static COLLECTIONS: &[&dyn Collection] = &[&ArrayList, &LinkedList, &HashSet, &HashMap];
+
+/// The modeled class named `class_name`, given without type arguments.
+pub fn modeled_collection(class_name: &str) -> Option<&'static dyn Collection> {
+    let simple_name = class_name.strip_prefix("java.util.").unwrap_or(class_name);
+    COLLECTIONS
+        .iter()
+        .copied()
+        .find(|collection| collection.name() == simple_name)
+}
+
+/// What a modeled collection holds: its elements in iteration order, or
+/// the keys of a map, with the value of each key at the same index of
+/// `values`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Contents {
+    pub elements: Vec<Value>,
+    pub values: Vec<Value>,
+}
+
+/// Exception thrown by a collection method.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectionFault {
+    pub exception_class: &'static str,
+    pub message: String,
+}
+
+/// Result of calling `method` with `args` on a `collection` holding
+/// `contents`, which are updated in place. Void methods give `Ok(None)`.
+/// `equals` compares two elements as their `equals` methods would, or
+/// gives `None` when it can't tell.
+///
+/// Returns `None` for methods that aren't modeled and when the result
+/// depends on an unknown argument; `contents` are then left as they were,
+/// as they are when the call throws.
+pub fn call_collection_method(
+    collection: &dyn Collection,
+    contents: &mut Contents,
+    method: &str,
+    args: &[Value],
+    equals: impl Fn(&Value, &Value) -> Option<bool>,
+) -> Option<Result<Option<Value>, CollectionFault>> {
+    let size = contents.elements.len();
+    let result = match (method, args) {
+        ("size", []) => Some(Value::Int(size as i32)),
+        ("isEmpty", []) => Some(Value::Bool(size == 0)),
+        ("clear", []) => {
+            contents.elements.clear();
+            contents.values.clear();
+            None
+        }
+        _ => return collection.call(contents, method, args, &equals),
+    };
+    Some(Ok(result))
+}
+
+/// Index of the first of `items` equal to `value`, `None` inside if there
+/// is none and outside if that can't be told.
+fn position(
+    items: &[Value],
+    value: &Value,
+    equals: &dyn Fn(&Value, &Value) -> Option<bool>,
+) -> Option<Option<usize>> {
+    for (i, item) in items.iter().enumerate() {
+        if equals(item, value)? {
+            return Some(Some(i));
+        }
+    }
+    Some(None)
+}
+
+struct ArrayList;
+struct LinkedList;
+struct HashSet;
+struct HashMap;
+
+impl Collection for ArrayList {
+    fn name(&self) -> &'static str {
+        "ArrayList"
+    }
+
+    fn call(
+        &self,
+        contents: &mut Contents,
+        method: &str,
+        args: &[Value],
+        equals: &dyn Fn(&Value, &Value) -> Option<bool>,
+    ) -> Option<Result<Option<Value>, CollectionFault>> {
+        call_list_method(contents, method, args, equals, |i, size, end| {
+            if end {
+                format!("Index: {}, Size: {}", i, size)
+            } else {
+                format!("Index {} out of bounds for length {}", i, size)
+            }
+        })
+    }
+}
+
+impl Collection for LinkedList {
+    fn name(&self) -> &'static str {
+        "LinkedList"
+    }
+
+    fn call(
+        &self,
+        contents: &mut Contents,
+        method: &str,
+        args: &[Value],
+        equals: &dyn Fn(&Value, &Value) -> Option<bool>,
+    ) -> Option<Result<Option<Value>, CollectionFault>> {
+        if let ("addFirst", [element]) = (method, args) {
+            contents.elements.insert(0, element.clone());
+            return Some(Ok(None));
+        }
+        call_list_method(contents, method, args, equals, |i, size, _| {
+            format!("Index: {}, Size: {}", i, size)
+        })
+    }
+}
+
+impl Collection for HashSet {
+    fn name(&self) -> &'static str {
+        "HashSet"
+    }
+
+    fn call(
+        &self,
+        contents: &mut Contents,
+        method: &str,
+        args: &[Value],
+        equals: &dyn Fn(&Value, &Value) -> Option<bool>,
+    ) -> Option<Result<Option<Value>, CollectionFault>> {
+        let elements = &mut contents.elements;
+        let result = match (method, args) {
+            ("contains", [element]) => position(elements, element, equals)?.is_some(),
+            ("add", [element]) => match position(elements, element, equals)? {
+                Some(_) => false,
+                None => {
+                    elements.push(element.clone());
+                    true
+                }
+            },
+            ("remove", [element]) => match position(elements, element, equals)? {
+                Some(i) => {
+                    elements.remove(i);
+                    true
+                }
+                None => false,
+            },
+            _ => return None,
+        };
+        Some(Ok(Some(Value::Bool(result))))
+    }
+}
+
+impl Collection for HashMap {
+    fn name(&self) -> &'static str {
+        "HashMap"
+    }
+
+    fn is_map(&self) -> bool {
+        true
+    }
+
+    fn call(
+        &self,
+        contents: &mut Contents,
+        method: &str,
+        args: &[Value],
+        equals: &dyn Fn(&Value, &Value) -> Option<bool>,
+    ) -> Option<Result<Option<Value>, CollectionFault>> {
+        let keys = &mut contents.elements;
+        let values = &mut contents.values;
+        let result = match (method, args) {
+            ("put", [key, value]) => match position(keys, key, equals)? {
+                Some(i) => std::mem::replace(&mut values[i], value.clone()),
+                None => {
+                    keys.push(key.clone());
+                    values.push(value.clone());
+                    Value::Null
+                }
+            },
+            ("get", [key]) => match position(keys, key, equals)? {
+                Some(i) => values[i].clone(),
+                None => Value::Null,
+            },
+            ("getOrDefault", [key, default]) => match position(keys, key, equals)? {
+                Some(i) => values[i].clone(),
+                None => default.clone(),
+            },
+            ("containsKey", [key]) => Value::Bool(position(keys, key, equals)?.is_some()),
+            ("containsValue", [value]) => Value::Bool(position(values, value, equals)?.is_some()),
+            ("remove", [key]) => match position(keys, key, equals)? {
+                Some(i) => {
+                    keys.remove(i);
+                    values.remove(i)
+                }
+                None => Value::Null,
+            },
+            _ => return None,
+        };
+        Some(Ok(Some(result)))
+    }
+}
+
+/// Methods `ArrayList` and `LinkedList` share. `index_message` words the
+/// `IndexOutOfBoundsException` for an index, the size and whether the end
+/// of the list was allowed, as each class words it.
+fn call_list_method(
+    contents: &mut Contents,
+    method: &str,
+    args: &[Value],
+    equals: &dyn Fn(&Value, &Value) -> Option<bool>,
+    index_message: fn(i32, usize, bool) -> String,
+) -> Option<Result<Option<Value>, CollectionFault>> {
+    let elements = &mut contents.elements;
+    let size = elements.len();
+    // Index checks as `get(i)` does them, or `add(i, e)` when `end` is allowed
+    let checked = |index: &Value, end: bool| -> Option<Result<usize, CollectionFault>> {
+        let i = index.as_int()?;
+        let limit = if end { size + 1 } else { size };
+        Some(match usize::try_from(i).ok().filter(|&i| i < limit) {
+            Some(i) => Ok(i),
+            None => Err(CollectionFault {
+                exception_class: "IndexOutOfBoundsException",
+                message: index_message(i, size, end),
+            }),
+        })
+    };
+    let result = match (method, args) {
+        ("contains", [element]) => {
+            Some(Value::Bool(position(elements, element, equals)?.is_some()))
+        }
+        ("add" | "addLast", [element]) => {
+            elements.push(element.clone());
+            (method == "add").then_some(Value::Bool(true))
+        }
+        ("add", [index, element]) => {
+            let i = match checked(index, true)? {
+                Ok(i) => i,
+                Err(fault) => return Some(Err(fault)),
+            };
+            elements.insert(i, element.clone());
+            None
+        }
+        ("get", [index]) => match checked(index, false)? {
+            Ok(i) => Some(elements[i].clone()),
+            Err(fault) => return Some(Err(fault)),
+        },
+        ("set", [index, element]) => {
+            let i = match checked(index, false)? {
+                Ok(i) => i,
+                Err(fault) => return Some(Err(fault)),
+            };
+            Some(std::mem::replace(&mut elements[i], element.clone()))
+        }
+        // `remove(int)` removes by position, `remove(Object)` by equality
+        (
+            "remove",
+            [index @ (Value::Int(_) | Value::Short(_) | Value::Byte(_) | Value::Char(_))],
+        ) => {
+            let i = match checked(index, false)? {
+                Ok(i) => i,
+                Err(fault) => return Some(Err(fault)),
+            };
+            Some(elements.remove(i))
+        }
+        ("remove", [element]) => Some(Value::Bool(match position(elements, element, equals)? {
+            Some(i) => {
+                elements.remove(i);
+                true
+            }
+            None => false,
+        })),
+        ("indexOf", [element]) => Some(Value::Int(
+            position(elements, element, equals)?.map_or(-1, |i| i as i32),
+        )),
+        _ => return None,
+    };
+    Some(Ok(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(
+        collection: &dyn Collection,
+        contents: &mut Contents,
+        method: &str,
+        args: &[Value],
+    ) -> Option<Result<Option<Value>, CollectionFault>> {
+        call_collection_method(collection, contents, method, args, |a, b| {
+            (a.is_known() && b.is_known()).then(|| a == b)
+        })
+    }
+
+    #[test]
+    fn classes_are_found_by_simple_or_qualified_name() {
+        assert_eq!(modeled_collection("ArrayList").unwrap().name(), "ArrayList");
+        assert!(modeled_collection("java.util.HashMap").unwrap().is_map());
+        assert!(modeled_collection("TreeMap").is_none());
+    }
+
+    #[test]
+    fn lists_add_get_set_and_remove_by_index_or_value() {
+        let mut list = Contents::default();
+        let list_call = |list: &mut Contents, method, args: &[Value]| {
+            call(&ArrayList, list, method, args).unwrap()
+        };
+        assert_eq!(
+            list_call(&mut list, "add", &[Value::Int(7)]),
+            Ok(Some(Value::Bool(true)))
+        );
+        assert_eq!(
+            list_call(&mut list, "add", &[Value::Int(0), Value::Int(5)]),
+            Ok(None)
+        );
+        assert_eq!(list.elements, [Value::Int(5), Value::Int(7)]);
+        assert_eq!(
+            list_call(&mut list, "set", &[Value::Int(1), Value::Int(9)]),
+            Ok(Some(Value::Int(7)))
+        );
+        assert_eq!(
+            list_call(&mut list, "remove", &[Value::Int(0)]),
+            Ok(Some(Value::Int(5))),
+            "an int argument is a position"
+        );
+        assert_eq!(
+            list_call(&mut list, "remove", &[Value::Long(9)]),
+            Ok(Some(Value::Bool(false)))
+        );
+        assert_eq!(list_call(&mut list, "size", &[]), Ok(Some(Value::Int(1))));
+    }
+
+    #[test]
+    fn bad_indexes_throw_and_leave_the_list_alone() {
+        let mut list = Contents {
+            elements: vec![Value::Int(1)],
+            values: Vec::new(),
+        };
+        let fault = |collection: &dyn Collection, method, args: &[Value]| {
+            let mut copy = list.clone();
+            match call(collection, &mut copy, method, args) {
+                Some(Err(fault)) => {
+                    assert_eq!(copy, list);
+                    fault.message
+                }
+                other => panic!("expected a fault, got {:?}", other),
+            }
+        };
+        assert_eq!(
+            fault(&ArrayList, "get", &[Value::Int(3)]),
+            "Index 3 out of bounds for length 1"
+        );
+        assert_eq!(
+            fault(&ArrayList, "add", &[Value::Int(2), Value::Int(0)]),
+            "Index: 2, Size: 1"
+        );
+        assert_eq!(
+            fault(&LinkedList, "remove", &[Value::Int(-1)]),
+            "Index: -1, Size: 1"
+        );
+        assert_eq!(call(&ArrayList, &mut list, "get", &[Value::Unknown]), None);
+    }
+
+    #[test]
+    fn sets_hold_each_element_once() {
+        let mut set = Contents::default();
+        let add = |set: &mut Contents, value| call(&HashSet, set, "add", &[value]).unwrap();
+        assert_eq!(add(&mut set, Value::Int(1)), Ok(Some(Value::Bool(true))));
+        assert_eq!(add(&mut set, Value::Int(1)), Ok(Some(Value::Bool(false))));
+        assert_eq!(set.elements.len(), 1);
+        assert_eq!(
+            call(&HashSet, &mut set, "get", &[Value::Int(0)]),
+            None,
+            "sets have no positions"
+        );
+    }
+
+    #[test]
+    fn maps_keep_one_value_per_key() {
+        let mut map = Contents::default();
+        let key = Value::Char('a' as u16);
+        let put =
+            |map: &mut Contents, value| call(&HashMap, map, "put", &[key.clone(), value]).unwrap();
+        assert_eq!(put(&mut map, Value::Int(1)), Ok(Some(Value::Null)));
+        assert_eq!(put(&mut map, Value::Int(2)), Ok(Some(Value::Int(1))));
+        assert_eq!(map.elements, std::slice::from_ref(&key));
+        assert_eq!(map.values, [Value::Int(2)]);
+        assert_eq!(
+            call(
+                &HashMap,
+                &mut map,
+                "containsKey",
+                std::slice::from_ref(&key)
+            ),
+            Some(Ok(Some(Value::Bool(true))))
+        );
+        assert_eq!(
+            call(&HashMap, &mut map, "get", &[Value::Int(0)]),
+            Some(Ok(Some(Value::Null)))
+        );
+        assert_eq!(
+            call(&HashMap, &mut map, "remove", &[key]),
+            Some(Ok(Some(Value::Int(2))))
+        );
+        assert!(map.elements.is_empty() && map.values.is_empty());
+    }
+}
@@ -1,37 +1,100 @@
+use super::heap::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::fmt;
 
-/// Represents a runtime value during execution flow analysis.
-#[derive(Debug, Clone, PartialEq)]
+/// Represents a runtime value during execution flow analysis. Primitive
+/// values carry their Java type, so arithmetic wraps at the type's width.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Value {
-    /// 64-bit signed integer literal
-    Int(i64),
-    /// 64-bit floating-point literal
-    Float(f64),
+    /// Java `byte`
+    Byte(i8),
+    /// Java `short`
+    Short(i16),
+    /// Java `char`, a UTF-16 code unit
+    Char(u16),
+    /// Java `int`
+    Int(i32),
+    /// Java `long`
+    Long(i64),
+    /// Java `float`
+    Float(f32),
+    /// Java `double`
+    Double(f64),
     /// Boolean literal
     Bool(bool),
     /// String literal
     Str(String),
     /// Java `null` reference
     Null,
+    /// Reference to an object on the [`super::heap::Heap`]
+    Ref(ObjectId),
     /// Value that could not be evaluated statically
     Unknown,
 }
 
+/// Operands of a binary numeric operation after binary numeric promotion:
+/// to `double` if either is one, else `float`, else `long`, else `int`.
+enum Promoted {
+    Int(i32, i32),
+    Long(i64, i64),
+    Float(f32, f32),
+    Double(f64, f64),
+}
+
+impl Promoted {
+    fn of(a: &Value, b: &Value) -> Option<Promoted> {
+        let (x, y) = (a.as_f64()?, b.as_f64()?);
+        Some(match (a, b) {
+            (Value::Double(_), _) | (_, Value::Double(_)) => Promoted::Double(x, y),
+            (Value::Float(_), _) | (_, Value::Float(_)) => Promoted::Float(x as f32, y as f32),
+            (Value::Long(_), _) | (_, Value::Long(_)) => {
+                Promoted::Long(a.integral()?, b.integral()?)
+            }
+            _ => Promoted::Int(a.integral()? as i32, b.integral()? as i32),
+        })
+    }
+}
+
+/// Java's `Double.toString`/`Float.toString`: at least one fractional
+/// digit, and computerized scientific notation (`1.0E10`) outside
+/// `[10^-3, 10^7)`. `value` prints the shortest digits of its own width.
+fn java_floating<F: fmt::Display + fmt::LowerExp>(value: F, magnitude: f64) -> String {
+    if magnitude.is_nan() {
+        return "NaN".to_string();
+    }
+    if magnitude.is_infinite() {
+        return if magnitude > 0.0 {
+            "Infinity"
+        } else {
+            "-Infinity"
+        }
+        .to_string();
+    }
+    let with_fraction = |digits: &str| {
+        if digits.contains('.') {
+            digits.to_string()
+        } else {
+            format!("{digits}.0")
+        }
+    };
+    let abs = magnitude.abs();
+    if abs == 0.0 || (1e-3..1e7).contains(&abs) {
+        with_fraction(&value.to_string())
+    } else {
+        let scientific = format!("{value:e}");
+        let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+        format!("{}E{}", with_fraction(mantissa), exponent)
+    }
+}
+
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Value::Int(v) => write!(f, "{v}"),
-            Value::Float(v) => {
-                if v.fract() == 0.0 {
-                    write!(f, "{v:.1}")
-                } else {
-                    write!(f, "{v}")
-                }
-            }
-            Value::Bool(v) => write!(f, "{v}"),
+            Value::Char(_) => write!(f, "'{}'", self.to_storage_string()),
             Value::Str(v) => write!(f, "\"{v}\""),
-            Value::Null => write!(f, "null"),
             Value::Unknown => write!(f, "?"),
+            _ => write!(f, "{}", self.to_storage_string()),
         }
     }
 }
@@ -46,20 +109,59 @@ impl Value {
         }
     }
 
-    /// Try to interpret as f64. Int converts, Float direct, everything else None.
+    /// Try to interpret as f64. Any numeric value converts, everything else None.
     pub fn as_f64(&self) -> Option<f64> {
         match self {
-            Value::Float(f) => Some(*f),
-            Value::Int(i) => Some(*i as f64),
-            _ => None,
+            Value::Float(f) => Some(*f as f64),
+            Value::Double(d) => Some(*d),
+            other => other.integral().map(|i| i as f64),
         }
     }
 
-    /// Try to interpret as i64. Int direct, Float if it's a whole number, everything else None.
+    /// Try to interpret as i64. Integral values direct, floating-point ones
+    /// if they're whole numbers, everything else None.
     pub fn as_i64(&self) -> Option<i64> {
         match self {
-            Value::Int(i) => Some(*i),
-            Value::Float(f) if f.fract() == 0.0 => Some(*f as i64),
+            Value::Float(_) | Value::Double(_) => {
+                let f = self.as_f64()?;
+                (f.fract() == 0.0).then_some(f as i64)
+            }
+            other => other.integral(),
+        }
+    }
+
+    /// The value as an `int` after unary numeric promotion, as used for
+    /// array indexes and lengths. `None` for `long`, floating-point and
+    /// non-numeric values.
+    pub fn as_int(&self) -> Option<i32> {
+        match self {
+            Value::Long(_) => None,
+            other => other.integral().map(|i| i as i32),
+        }
+    }
+
+    /// Value of a `byte`, `short`, `char`, `int` or `long`.
+    fn integral(&self) -> Option<i64> {
+        match self {
+            Value::Byte(v) => Some(*v as i64),
+            Value::Short(v) => Some(*v as i64),
+            Value::Char(v) => Some(*v as i64),
+            Value::Int(v) => Some(*v as i64),
+            Value::Long(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Whether this is a value of an integral type, whose division by zero
+    /// throws rather than giving an infinity or NaN.
+    pub fn is_integral(&self) -> bool {
+        self.integral().is_some()
+    }
+
+    /// Object a reference points to; `None` for `null` and non-references.
+    pub fn as_object(&self) -> Option<ObjectId> {
+        match self {
+            Value::Ref(id) => Some(*id),
             _ => None,
         }
     }
@@ -69,178 +171,256 @@ impl Value {
         !matches!(self, Value::Unknown)
     }
 
+    /// The `MIN_VALUE` or `MAX_VALUE` constant of a primitive wrapper class
+    /// (`Integer`, `Long`, ...), typed like the primitive it bounds.
+    pub fn wrapper_constant(class_name: &str, field: &str) -> Option<Value> {
+        let class_name = class_name.strip_prefix("java.lang.").unwrap_or(class_name);
+        let value = match (class_name, field) {
+            ("Byte", "MIN_VALUE") => Value::Byte(i8::MIN),
+            ("Byte", "MAX_VALUE") => Value::Byte(i8::MAX),
+            ("Short", "MIN_VALUE") => Value::Short(i16::MIN),
+            ("Short", "MAX_VALUE") => Value::Short(i16::MAX),
+            ("Character", "MIN_VALUE") => Value::Char(u16::MIN),
+            ("Character", "MAX_VALUE") => Value::Char(u16::MAX),
+            ("Integer", "MIN_VALUE") => Value::Int(i32::MIN),
+            ("Integer", "MAX_VALUE") => Value::Int(i32::MAX),
+            ("Long", "MIN_VALUE") => Value::Long(i64::MIN),
+            ("Long", "MAX_VALUE") => Value::Long(i64::MAX),
+            // The floating-point MIN_VALUE is the smallest positive value
+            ("Float", "MIN_VALUE") => Value::Float(f32::from_bits(1)),
+            ("Float", "MAX_VALUE") => Value::Float(f32::MAX),
+            ("Double", "MIN_VALUE") => Value::Double(f64::from_bits(1)),
+            ("Double", "MAX_VALUE") => Value::Double(f64::MAX),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Convert to the primitive type `type_name` as a cast or assignment
+    /// does: integers narrow by dropping high bits, floating-point values
+    /// round toward zero and saturate at the bounds of `int`/`long` (NaN
+    /// becomes 0). Non-numeric values and other target types are returned
+    /// unchanged.
+    pub fn convert_to(&self, type_name: &str) -> Value {
+        if !matches!(
+            type_name,
+            "byte" | "short" | "char" | "int" | "long" | "float" | "double"
+        ) {
+            return self.clone();
+        }
+        let Some(as_double) = self.as_f64() else {
+            return self.clone();
+        };
+        // Narrowing from floating point goes through `int`, or `long`
+        let whole = |via_long: bool| match self.integral() {
+            Some(i) => i,
+            None if via_long => as_double as i64,
+            None => as_double as i32 as i64,
+        };
+        match type_name {
+            "byte" => Value::Byte(whole(false) as i8),
+            "short" => Value::Short(whole(false) as i16),
+            "char" => Value::Char(whole(false) as u16),
+            "int" => Value::Int(whole(false) as i32),
+            "long" => Value::Long(whole(true)),
+            "float" => Value::Float(match self {
+                Value::Float(f) => *f,
+                Value::Double(d) => *d as f32,
+                other => other.integral().unwrap_or_default() as f32,
+            }),
+            _ => Value::Double(as_double),
+        }
+    }
+
     /// Convert to string representation suitable for variable storage.
     pub fn to_storage_string(&self) -> String {
         match self {
+            Value::Byte(v) => format!("{v}"),
+            Value::Short(v) => format!("{v}"),
             Value::Int(v) => format!("{v}"),
-            Value::Float(v) => {
-                if v.floor() == *v {
-                    format!("{v:.1}")
-                } else {
-                    format!("{v}")
-                }
-            }
+            Value::Long(v) => format!("{v}"),
+            Value::Char(v) => char::from_u32(*v as u32)
+                .unwrap_or(char::REPLACEMENT_CHARACTER)
+                .to_string(),
+            Value::Float(v) => java_floating(*v, *v as f64),
+            Value::Double(v) => java_floating(*v, *v),
             Value::Bool(v) => format!("{v}"),
             Value::Str(v) => v.clone(),
             Value::Null => "null".to_string(),
+            Value::Ref(id) => format!("@{id}"),
             Value::Unknown => "unknown".to_string(),
         }
     }
 
+    /// Apply the Java binary operator `op` (e.g. `"+"`, `"<="`, `"&&"`).
+    /// Unsupported operators yield `Unknown`.
+    pub fn apply_binary(&self, op: &str, other: &Value) -> Value {
+        match op {
+            "+" => self.add(other),
+            "-" => self.sub(other),
+            "*" => self.mul(other),
+            "/" => self.div(other),
+            "%" => self.rem(other),
+            "<" => self.lt(other),
+            "<=" => self.le(other),
+            ">" => self.gt(other),
+            ">=" => self.ge(other),
+            "==" => self.eq_val(other),
+            "!=" => self.ne_val(other),
+            "&&" => self.and(other),
+            "||" => self.or(other),
+            "&" => self.bitwise(other, |a, b| a & b, |a, b| a & b),
+            "|" => self.bitwise(other, |a, b| a | b, |a, b| a | b),
+            "^" => self.bitwise(other, |a, b| a ^ b, |a, b| a ^ b),
+            "<<" => self.shift(other, |v, n| v << n, |v, n| v << n),
+            ">>" => self.shift(other, |v, n| v >> n, |v, n| v >> n),
+            ">>>" => self.shift(
+                other,
+                |v, n| ((v as u32) >> n) as i32,
+                |v, n| ((v as u64) >> n) as i64,
+            ),
+            _ => Value::Unknown,
+        }
+    }
+
     // -- Arithmetic --
 
-    /// Numeric addition. Int+Int stays Int (wrapping); any Float promotes to Float.
-    /// Propagates `Unknown` if either side is `Unknown`.
+    /// Numeric addition or string concatenation. Numeric operands are
+    /// promoted first, and `int`/`long` results wrap on overflow; a `Str`
+    /// on either side concatenates. Propagates `Unknown` if either side is
+    /// `Unknown`; concatenating an object reference is `Unknown` since its
+    /// `toString` isn't modeled.
     pub fn add(&self, other: &Value) -> Value {
         match (self, other) {
             (Value::Unknown, _) | (_, Value::Unknown) => Value::Unknown,
-            (Value::Int(a), Value::Int(b)) => Value::Int(a.wrapping_add(*b)),
-            (a, b) => match (a.as_f64(), b.as_f64()) {
-                (Some(fa), Some(fb)) => Value::Float(fa + fb),
-                _ => Value::Unknown,
+            (Value::Str(_), Value::Ref(_)) | (Value::Ref(_), Value::Str(_)) => Value::Unknown,
+            (Value::Str(_), _) | (_, Value::Str(_)) => Value::Str(format!(
+                "{}{}",
+                self.to_storage_string(),
+                other.to_storage_string()
+            )),
+            _ => match Promoted::of(self, other) {
+                Some(Promoted::Int(a, b)) => Value::Int(a.wrapping_add(b)),
+                Some(Promoted::Long(a, b)) => Value::Long(a.wrapping_add(b)),
+                Some(Promoted::Float(a, b)) => Value::Float(a + b),
+                Some(Promoted::Double(a, b)) => Value::Double(a + b),
+                None => Value::Unknown,
             },
         }
     }
 
-    /// Numeric subtraction. Int-Int stays Int (wrapping); any Float promotes to Float.
+    /// Numeric subtraction of the promoted operands; `int`/`long` wrap.
     /// Propagates `Unknown` if either side is `Unknown`.
     pub fn sub(&self, other: &Value) -> Value {
-        match (self, other) {
-            (Value::Unknown, _) | (_, Value::Unknown) => Value::Unknown,
-            (Value::Int(a), Value::Int(b)) => Value::Int(a.wrapping_sub(*b)),
-            (a, b) => match (a.as_f64(), b.as_f64()) {
-                (Some(fa), Some(fb)) => Value::Float(fa - fb),
-                _ => Value::Unknown,
-            },
+        match Promoted::of(self, other) {
+            Some(Promoted::Int(a, b)) => Value::Int(a.wrapping_sub(b)),
+            Some(Promoted::Long(a, b)) => Value::Long(a.wrapping_sub(b)),
+            Some(Promoted::Float(a, b)) => Value::Float(a - b),
+            Some(Promoted::Double(a, b)) => Value::Double(a - b),
+            None => Value::Unknown,
         }
     }
 
-    /// Numeric multiplication. Int*Int stays Int (wrapping); any Float promotes to Float.
+    /// Numeric multiplication of the promoted operands; `int`/`long` wrap.
     /// Propagates `Unknown` if either side is `Unknown`.
     pub fn mul(&self, other: &Value) -> Value {
-        match (self, other) {
-            (Value::Unknown, _) | (_, Value::Unknown) => Value::Unknown,
-            (Value::Int(a), Value::Int(b)) => Value::Int(a.wrapping_mul(*b)),
-            (a, b) => match (a.as_f64(), b.as_f64()) {
-                (Some(fa), Some(fb)) => Value::Float(fa * fb),
-                _ => Value::Unknown,
-            },
+        match Promoted::of(self, other) {
+            Some(Promoted::Int(a, b)) => Value::Int(a.wrapping_mul(b)),
+            Some(Promoted::Long(a, b)) => Value::Long(a.wrapping_mul(b)),
+            Some(Promoted::Float(a, b)) => Value::Float(a * b),
+            Some(Promoted::Double(a, b)) => Value::Double(a * b),
+            None => Value::Unknown,
         }
     }
 
-    /// Numeric division. Int/Int truncates toward zero; any Float promotes to Float.
-    /// Division by zero and `Unknown` operands yield `Unknown`.
+    /// Numeric division. Integer division truncates toward zero, and
+    /// dividing by an integer zero (which throws) yields `Unknown`; a
+    /// floating-point division by zero gives an infinity or NaN.
     pub fn div(&self, other: &Value) -> Value {
-        match (self, other) {
-            (Value::Unknown, _) | (_, Value::Unknown) => Value::Unknown,
-            (Value::Int(a), Value::Int(b)) => {
-                if *b == 0 {
-                    Value::Unknown
-                } else {
-                    Value::Int(a / b)
-                }
-            }
-            (a, b) => match (a.as_f64(), b.as_f64()) {
-                (Some(_), Some(fb)) if fb == 0.0 => Value::Unknown,
-                (Some(fa), Some(fb)) => Value::Float(fa / fb),
-                _ => Value::Unknown,
-            },
+        match Promoted::of(self, other) {
+            Some(Promoted::Int(_, 0)) | Some(Promoted::Long(_, 0)) => Value::Unknown,
+            Some(Promoted::Int(a, b)) => Value::Int(a.wrapping_div(b)),
+            Some(Promoted::Long(a, b)) => Value::Long(a.wrapping_div(b)),
+            Some(Promoted::Float(a, b)) => Value::Float(a / b),
+            Some(Promoted::Double(a, b)) => Value::Double(a / b),
+            None => Value::Unknown,
         }
     }
 
-    /// Numeric remainder (`%`). Int%Int stays Int; any Float promotes to Float.
-    /// Remainder by zero and `Unknown` operands yield `Unknown`.
+    /// Numeric remainder (`%`), taking the sign of the dividend. An integer
+    /// remainder by zero yields `Unknown`; a floating-point one is NaN.
     pub fn rem(&self, other: &Value) -> Value {
-        match (self, other) {
-            (Value::Unknown, _) | (_, Value::Unknown) => Value::Unknown,
-            (Value::Int(a), Value::Int(b)) => {
-                if *b == 0 {
-                    Value::Unknown
-                } else {
-                    Value::Int(a % b)
-                }
-            }
-            (a, b) => match (a.as_f64(), b.as_f64()) {
-                (Some(_), Some(fb)) if fb == 0.0 => Value::Unknown,
-                (Some(fa), Some(fb)) => Value::Float(fa % fb),
-                _ => Value::Unknown,
-            },
+        match Promoted::of(self, other) {
+            Some(Promoted::Int(_, 0)) | Some(Promoted::Long(_, 0)) => Value::Unknown,
+            Some(Promoted::Int(a, b)) => Value::Int(a.wrapping_rem(b)),
+            Some(Promoted::Long(a, b)) => Value::Long(a.wrapping_rem(b)),
+            Some(Promoted::Float(a, b)) => Value::Float(a % b),
+            Some(Promoted::Double(a, b)) => Value::Double(a % b),
+            None => Value::Unknown,
         }
     }
 
     // -- Comparison --
 
-    /// Less-than comparison. Promotes to f64 for mixed numerics.
+    /// Compare the promoted numeric operands; `test` receives their
+    /// ordering, `None` when either is NaN. Propagates `Unknown` if either
+    /// side is `Unknown` or non-numeric.
+    fn compare(&self, other: &Value, test: impl Fn(Option<Ordering>) -> bool) -> Value {
+        let ordering = match Promoted::of(self, other) {
+            Some(Promoted::Int(a, b)) => a.partial_cmp(&b),
+            Some(Promoted::Long(a, b)) => a.partial_cmp(&b),
+            Some(Promoted::Float(a, b)) => a.partial_cmp(&b),
+            Some(Promoted::Double(a, b)) => a.partial_cmp(&b),
+            None => return Value::Unknown,
+        };
+        Value::Bool(test(ordering))
+    }
+
+    /// Less-than comparison of the promoted operands.
     /// Propagates `Unknown` if either side is `Unknown` or non-numeric.
     pub fn lt(&self, other: &Value) -> Value {
-        match (self, other) {
-            (Value::Unknown, _) | (_, Value::Unknown) => Value::Unknown,
-            (a, b) => match (a.as_f64(), b.as_f64()) {
-                (Some(fa), Some(fb)) => Value::Bool(fa < fb),
-                _ => Value::Unknown,
-            },
-        }
+        self.compare(other, |o| o == Some(Ordering::Less))
     }
 
-    /// Less-than-or-equal comparison. Promotes to f64 for mixed numerics.
+    /// Less-than-or-equal comparison of the promoted operands.
     /// Propagates `Unknown` if either side is `Unknown` or non-numeric.
     pub fn le(&self, other: &Value) -> Value {
-        match (self, other) {
-            (Value::Unknown, _) | (_, Value::Unknown) => Value::Unknown,
-            (a, b) => match (a.as_f64(), b.as_f64()) {
-                (Some(fa), Some(fb)) => Value::Bool(fa <= fb),
-                _ => Value::Unknown,
-            },
-        }
+        self.compare(other, |o| {
+            matches!(o, Some(Ordering::Less | Ordering::Equal))
+        })
     }
 
-    /// Greater-than comparison. Promotes to f64 for mixed numerics.
+    /// Greater-than comparison of the promoted operands.
     /// Propagates `Unknown` if either side is `Unknown` or non-numeric.
     pub fn gt(&self, other: &Value) -> Value {
-        match (self, other) {
-            (Value::Unknown, _) | (_, Value::Unknown) => Value::Unknown,
-            (a, b) => match (a.as_f64(), b.as_f64()) {
-                (Some(fa), Some(fb)) => Value::Bool(fa > fb),
-                _ => Value::Unknown,
-            },
-        }
+        self.compare(other, |o| o == Some(Ordering::Greater))
     }
 
-    /// Greater-than-or-equal comparison. Promotes to f64 for mixed numerics.
+    /// Greater-than-or-equal comparison of the promoted operands.
     /// Propagates `Unknown` if either side is `Unknown` or non-numeric.
     pub fn ge(&self, other: &Value) -> Value {
-        match (self, other) {
-            (Value::Unknown, _) | (_, Value::Unknown) => Value::Unknown,
-            (a, b) => match (a.as_f64(), b.as_f64()) {
-                (Some(fa), Some(fb)) => Value::Bool(fa >= fb),
-                _ => Value::Unknown,
-            },
-        }
+        self.compare(other, |o| {
+            matches!(o, Some(Ordering::Greater | Ordering::Equal))
+        })
     }
 
-    /// Equality comparison. Bools compare directly; numerics promote to f64.
+    /// Equality comparison. Bools compare directly; numerics are promoted;
+    /// references and `null` compare by identity.
     /// Propagates `Unknown` if either side is `Unknown` or the types don't match.
     pub fn eq_val(&self, other: &Value) -> Value {
         match (self, other) {
             (Value::Unknown, _) | (_, Value::Unknown) => Value::Unknown,
             (Value::Bool(a), Value::Bool(b)) => Value::Bool(a == b),
-            (a, b) => match (a.as_f64(), b.as_f64()) {
-                (Some(fa), Some(fb)) => Value::Bool(fa == fb),
-                _ => Value::Unknown,
-            },
+            (Value::Ref(a), Value::Ref(b)) => Value::Bool(a == b),
+            (Value::Null, Value::Null) => Value::Bool(true),
+            (Value::Null, Value::Ref(_)) | (Value::Ref(_), Value::Null) => Value::Bool(false),
+            _ => self.compare(other, |o| o == Some(Ordering::Equal)),
         }
     }
 
     /// Inequality comparison, the dual of [`Value::eq_val`].
     pub fn ne_val(&self, other: &Value) -> Value {
-        match (self, other) {
-            (Value::Unknown, _) | (_, Value::Unknown) => Value::Unknown,
-            (Value::Bool(a), Value::Bool(b)) => Value::Bool(a != b),
-            (a, b) => match (a.as_f64(), b.as_f64()) {
-                (Some(fa), Some(fb)) => Value::Bool(fa != fb),
-                _ => Value::Unknown,
-            },
-        }
+        self.eq_val(other).not()
     }
 
     // -- Boolean --
@@ -279,32 +459,126 @@ impl Value {
         }
     }
 
+    // -- Bitwise --
+
+    /// `&`, `|` or `^`: bitwise on the promoted integral operands, or the
+    /// logical operator (evaluating both sides) on two booleans.
+    /// Floating-point, mixed and `Unknown` operands yield `Unknown`.
+    fn bitwise(
+        &self,
+        other: &Value,
+        bits: fn(i64, i64) -> i64,
+        logic: fn(bool, bool) -> bool,
+    ) -> Value {
+        if let (Value::Bool(a), Value::Bool(b)) = (self, other) {
+            return Value::Bool(logic(*a, *b));
+        }
+        if !self.is_integral() || !other.is_integral() {
+            return Value::Unknown;
+        }
+        match Promoted::of(self, other) {
+            Some(Promoted::Int(a, b)) => Value::Int(bits(a.into(), b.into()) as i32),
+            Some(Promoted::Long(a, b)) => Value::Long(bits(a, b)),
+            _ => Value::Unknown,
+        }
+    }
+
+    /// `<<`, `>>` or `>>>`. Unlike other binary operators the operands are
+    /// promoted separately: the result has the type of the promoted left
+    /// operand, which is shifted by the low 5 (`int`) or 6 (`long`) bits of
+    /// the distance.
+    fn shift(
+        &self,
+        distance: &Value,
+        int: fn(i32, u32) -> i32,
+        long: fn(i64, u32) -> i64,
+    ) -> Value {
+        let Some(distance) = distance.integral() else {
+            return Value::Unknown;
+        };
+        match self {
+            Value::Long(v) => Value::Long(long(*v, (distance & 63) as u32)),
+            other => match other.as_int() {
+                Some(v) => Value::Int(int(v, (distance & 31) as u32)),
+                None => Value::Unknown,
+            },
+        }
+    }
+
+    /// Bitwise complement (`~`) of an integral value, promoted to `int`
+    /// unless it's a `long`; anything else returns `Unknown`.
+    pub fn bit_not(&self) -> Value {
+        match self {
+            Value::Long(v) => Value::Long(!v),
+            other => match other.as_int() {
+                Some(v) => Value::Int(!v),
+                None => Value::Unknown,
+            },
+        }
+    }
+
     // -- Unary --
 
-    /// Arithmetic negation. Only defined for `Int` and `Float`;
-    /// anything else returns `Unknown`.
+    /// Arithmetic negation. `byte`, `short` and `char` are promoted to
+    /// `int`, and negating the minimum `int`/`long` wraps back to it;
+    /// anything non-numeric returns `Unknown`.
     pub fn negate(&self) -> Value {
         match self {
-            Value::Int(v) => Value::Int(-v),
+            Value::Long(v) => Value::Long(v.wrapping_neg()),
             Value::Float(v) => Value::Float(-v),
-            _ => Value::Unknown,
+            Value::Double(v) => Value::Double(-v),
+            other => match other.as_int() {
+                Some(v) => Value::Int(v.wrapping_neg()),
+                None => Value::Unknown,
+            },
         }
     }
 }
 
-/// Signal emitted by flow-control statements (break, continue).
+/// Signal emitted by flow-control statements (break, continue, return,
+/// throw, yield).
 #[derive(Debug, Clone, PartialEq)]
 pub enum FlowSignal {
     /// Emitted by a `break` statement; terminates the enclosing loop.
     Break,
     /// Emitted by a `continue` statement; skips to the next iteration.
     Continue,
+    /// Emitted by a `return` statement; ends the running method with the
+    /// value, `Unknown` for a bare `return;`.
+    Return(Value),
+    /// Emitted by a `throw` statement; unwinds until a `catch` takes the
+    /// exception object.
+    Throw(ObjectId),
+    /// Emitted by a `yield` statement; completes the enclosing switch
+    /// expression with the value.
+    Yield(Value),
 }
 
 #[cfg(test)]
+#[allow(clippy::approx_constant)]
 mod tests {
     use super::*;
 
+    // -- Wrapper constants --
+
+    #[test]
+    fn wrapper_constants_take_the_primitive_type() {
+        assert_eq!(
+            Value::wrapper_constant("Integer", "MAX_VALUE"),
+            Some(Value::Int(i32::MAX))
+        );
+        assert_eq!(
+            Value::wrapper_constant("java.lang.Long", "MIN_VALUE"),
+            Some(Value::Long(i64::MIN))
+        );
+        assert_eq!(
+            Value::wrapper_constant("Character", "MAX_VALUE"),
+            Some(Value::Char(0xFFFF))
+        );
+        assert_eq!(Value::wrapper_constant("Integer", "SIZE"), None);
+        assert_eq!(Value::wrapper_constant("Dog", "MAX_VALUE"), None);
+    }
+
     // -- Arithmetic --
 
     #[test]
@@ -337,6 +611,23 @@ mod tests {
         assert_eq!(Value::Int(3).add(&Value::Float(1.5)), Value::Float(4.5));
     }
 
+    #[test]
+    fn string_concatenation() {
+        assert_eq!(
+            Value::Str("n=".into()).add(&Value::Int(3)),
+            Value::Str("n=3".into())
+        );
+        assert_eq!(
+            Value::Float(1.0).add(&Value::Str("!".into())),
+            Value::Str("1.0!".into())
+        );
+        assert_eq!(
+            Value::Str("x".into()).add(&Value::Null),
+            Value::Str("xnull".into())
+        );
+        assert_eq!(Value::Str("x".into()).add(&Value::Ref(1)), Value::Unknown);
+    }
+
     #[test]
     fn float_arithmetic() {
         assert_eq!(
@@ -352,7 +643,84 @@ mod tests {
 
     #[test]
     fn div_by_zero_float() {
-        assert_eq!(Value::Float(5.0).div(&Value::Float(0.0)), Value::Unknown);
+        assert_eq!(
+            Value::Double(5.0).div(&Value::Int(0)),
+            Value::Double(f64::INFINITY)
+        );
+        assert_eq!(
+            Value::Float(-1.0).div(&Value::Float(0.0)).to_string(),
+            "-Infinity"
+        );
+        assert_eq!(Value::Double(0.0).rem(&Value::Int(0)).to_string(), "NaN");
+    }
+
+    #[test]
+    fn int_arithmetic_wraps_at_32_bits() {
+        assert_eq!(
+            Value::Int(i32::MAX).add(&Value::Int(1)),
+            Value::Int(i32::MIN)
+        );
+        assert_eq!(
+            Value::Int(100_000).mul(&Value::Int(100_000)),
+            Value::Int(1_410_065_408)
+        );
+        assert_eq!(
+            Value::Long(100_000).mul(&Value::Int(100_000)),
+            Value::Long(10_000_000_000)
+        );
+        assert_eq!(
+            Value::Int(i32::MIN).div(&Value::Int(-1)),
+            Value::Int(i32::MIN)
+        );
+        assert_eq!(Value::Int(i32::MIN).negate(), Value::Int(i32::MIN));
+    }
+
+    #[test]
+    fn binary_numeric_promotion() {
+        // byte, short and char operands compute as int
+        assert_eq!(Value::Byte(100).add(&Value::Byte(100)), Value::Int(200));
+        assert_eq!(Value::Short(3).mul(&Value::Char(2)), Value::Int(6));
+        assert_eq!(Value::Int(7).div(&Value::Long(2)), Value::Long(3));
+        assert_eq!(Value::Long(1).add(&Value::Float(0.5)), Value::Float(1.5));
+        assert_eq!(
+            Value::Float(1.5).add(&Value::Double(1.0)),
+            Value::Double(2.5)
+        );
+        assert_eq!(Value::Int(7).div(&Value::Double(2.0)), Value::Double(3.5));
+        assert_eq!(Value::Int(-7).rem(&Value::Int(2)), Value::Int(-1));
+    }
+
+    #[test]
+    fn char_arithmetic_and_concatenation() {
+        let a = Value::Char('a' as u16);
+        assert_eq!(a.add(&Value::Int(1)), Value::Int(98));
+        assert_eq!(a.lt(&Value::Char('b' as u16)), Value::Bool(true));
+        assert_eq!(
+            Value::Str("x".into()).add(&a).add(&Value::Int(1)),
+            Value::Str("xa1".into())
+        );
+        assert_eq!(a.to_string(), "'a'");
+    }
+
+    #[test]
+    fn casts_narrow_and_truncate() {
+        assert_eq!(Value::Double(3.9).convert_to("int"), Value::Int(3));
+        assert_eq!(Value::Double(-3.9).convert_to("int"), Value::Int(-3));
+        assert_eq!(Value::Double(1e20).convert_to("int"), Value::Int(i32::MAX));
+        assert_eq!(Value::Double(f64::NAN).convert_to("long"), Value::Long(0));
+        assert_eq!(Value::Int(300).convert_to("byte"), Value::Byte(44));
+        assert_eq!(Value::Double(300.7).convert_to("byte"), Value::Byte(44));
+        assert_eq!(Value::Long(1 << 32).convert_to("int"), Value::Int(0));
+        assert_eq!(Value::Int(65).convert_to("char"), Value::Char('A' as u16));
+        assert_eq!(Value::Int(-1).convert_to("char"), Value::Char(u16::MAX));
+        assert_eq!(Value::Char('A' as u16).convert_to("int"), Value::Int(65));
+        assert_eq!(Value::Int(7).convert_to("double"), Value::Double(7.0));
+        assert_eq!(Value::Double(0.1).convert_to("float"), Value::Float(0.1));
+        assert_eq!(
+            Value::Str("7".into()).convert_to("int"),
+            Value::Str("7".into())
+        );
+        assert_eq!(Value::Int(7).convert_to("Integer"), Value::Int(7));
     }
 
     // -- Comparison --
@@ -384,12 +752,125 @@ mod tests {
         );
     }
 
+    #[test]
+    fn eq_val_references_compare_identity() {
+        assert_eq!(Value::Ref(1).eq_val(&Value::Ref(1)), Value::Bool(true));
+        assert_eq!(Value::Ref(1).eq_val(&Value::Ref(2)), Value::Bool(false));
+        assert_eq!(Value::Ref(1).eq_val(&Value::Null), Value::Bool(false));
+        assert_eq!(Value::Null.ne_val(&Value::Null), Value::Bool(false));
+    }
+
     #[test]
     fn ne_val_ints() {
         assert_eq!(Value::Int(1).ne_val(&Value::Int(2)), Value::Bool(true));
         assert_eq!(Value::Int(3).ne_val(&Value::Int(3)), Value::Bool(false));
     }
 
+    #[test]
+    fn apply_binary_dispatches_on_operator() {
+        assert_eq!(
+            Value::Int(6).apply_binary("*", &Value::Int(7)),
+            Value::Int(42)
+        );
+        assert_eq!(
+            Value::Int(1).apply_binary("<=", &Value::Int(1)),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            Value::Int(1).apply_binary("??", &Value::Int(1)),
+            Value::Unknown
+        );
+    }
+
+    // -- Bitwise --
+
+    #[test]
+    fn bitwise_operators_on_integers() {
+        assert_eq!(
+            Value::Int(0b1100).apply_binary("&", &Value::Int(0b1010)),
+            Value::Int(0b1000)
+        );
+        assert_eq!(
+            Value::Int(0b1100).apply_binary("|", &Value::Int(0b1010)),
+            Value::Int(0b1110)
+        );
+        assert_eq!(
+            Value::Int(0b1100).apply_binary("^", &Value::Int(0b1010)),
+            Value::Int(0b0110)
+        );
+        assert_eq!(
+            Value::Char('a' as u16).apply_binary("&", &Value::Int(0x5F)),
+            Value::Int('A' as i32)
+        );
+        assert_eq!(
+            Value::Int(-1).apply_binary("&", &Value::Long(0xFF)),
+            Value::Long(0xFF)
+        );
+        assert_eq!(Value::Int(5).bit_not(), Value::Int(-6));
+        assert_eq!(Value::Byte(0).bit_not(), Value::Int(-1));
+        assert_eq!(Value::Long(0).bit_not(), Value::Long(-1));
+        assert_eq!(
+            Value::Double(1.0).apply_binary("&", &Value::Int(1)),
+            Value::Unknown
+        );
+    }
+
+    #[test]
+    fn logical_operators_on_booleans_evaluate_both_sides() {
+        assert_eq!(
+            Value::Bool(true).apply_binary("&", &Value::Bool(false)),
+            Value::Bool(false)
+        );
+        assert_eq!(
+            Value::Bool(false).apply_binary("|", &Value::Bool(true)),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            Value::Bool(true).apply_binary("^", &Value::Bool(true)),
+            Value::Bool(false)
+        );
+        assert_eq!(
+            Value::Bool(false).apply_binary("&", &Value::Unknown),
+            Value::Unknown
+        );
+    }
+
+    #[test]
+    fn shifts_mask_the_distance_and_keep_the_left_type() {
+        assert_eq!(
+            Value::Int(1).apply_binary("<<", &Value::Int(31)),
+            Value::Int(i32::MIN)
+        );
+        assert_eq!(
+            Value::Int(1).apply_binary("<<", &Value::Int(33)),
+            Value::Int(2)
+        );
+        assert_eq!(
+            Value::Long(1).apply_binary("<<", &Value::Int(40)),
+            Value::Long(1 << 40)
+        );
+        assert_eq!(
+            Value::Int(1).apply_binary("<<", &Value::Long(3)),
+            Value::Int(8)
+        );
+        assert_eq!(
+            Value::Int(-16).apply_binary(">>", &Value::Int(2)),
+            Value::Int(-4)
+        );
+        assert_eq!(
+            Value::Int(-16).apply_binary(">>>", &Value::Int(28)),
+            Value::Int(15)
+        );
+        assert_eq!(
+            Value::Long(-1).apply_binary(">>>", &Value::Int(60)),
+            Value::Long(15)
+        );
+        assert_eq!(
+            Value::Byte(-8).apply_binary(">>>", &Value::Int(1)),
+            Value::Int(0x7FFF_FFFC)
+        );
+    }
+
     // -- Boolean --
 
     #[test]
@@ -456,6 +937,20 @@ mod tests {
         assert_eq!(Value::Float(3.14).to_storage_string(), "3.14");
     }
 
+    #[test]
+    fn storage_string_floating_follows_java() {
+        assert_eq!(
+            Value::Double(0.1 + 0.2).to_storage_string(),
+            "0.30000000000000004"
+        );
+        assert_eq!(Value::Float(0.1).to_storage_string(), "0.1");
+        assert_eq!(Value::Double(1e7).to_storage_string(), "1.0E7");
+        assert_eq!(Value::Double(1.5e-4).to_storage_string(), "1.5E-4");
+        assert_eq!(Value::Double(-0.0).to_storage_string(), "-0.0");
+        assert_eq!(Value::Long(-8).to_storage_string(), "-8");
+        assert_eq!(Value::Char('z' as u16).to_storage_string(), "z");
+    }
+
     #[test]
     fn storage_string_bool() {
         assert_eq!(Value::Bool(true).to_storage_string(), "true");
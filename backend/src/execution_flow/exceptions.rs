@@ -0,0 +1,118 @@
+/// JDK throwables a trace can meet without their source, as
+/// `(class, superclass, package)`. User exceptions extend one of these.
+const BUILTIN_THROWABLES: &[(&str, &str, &str)] = &[
+    ("Throwable", "Object", "java.lang"),
+    ("Exception", "Throwable", "java.lang"),
+    ("Error", "Throwable", "java.lang"),
+    ("RuntimeException", "Exception", "java.lang"),
+    ("ArithmeticException", "RuntimeException", "java.lang"),
+    ("ArrayStoreException", "RuntimeException", "java.lang"),
+    ("ClassCastException", "RuntimeException", "java.lang"),
+    ("IllegalArgumentException", "RuntimeException", "java.lang"),
+    (
+        "NumberFormatException",
+        "IllegalArgumentException",
+        "java.lang",
+    ),
+    ("IllegalStateException", "RuntimeException", "java.lang"),
+    ("IndexOutOfBoundsException", "RuntimeException", "java.lang"),
+    (
+        "ArrayIndexOutOfBoundsException",
+        "IndexOutOfBoundsException",
+        "java.lang",
+    ),
+    (
+        "StringIndexOutOfBoundsException",
+        "IndexOutOfBoundsException",
+        "java.lang",
+    ),
+    (
+        "NegativeArraySizeException",
+        "RuntimeException",
+        "java.lang",
+    ),
+    ("NullPointerException", "RuntimeException", "java.lang"),
+    (
+        "UnsupportedOperationException",
+        "RuntimeException",
+        "java.lang",
+    ),
+    ("CloneNotSupportedException", "Exception", "java.lang"),
+    ("InterruptedException", "Exception", "java.lang"),
+    ("NoSuchElementException", "RuntimeException", "java.util"),
+    (
+        "InputMismatchException",
+        "NoSuchElementException",
+        "java.util",
+    ),
+    (
+        "ConcurrentModificationException",
+        "RuntimeException",
+        "java.util",
+    ),
+    ("IOException", "Exception", "java.io"),
+    ("FileNotFoundException", "IOException", "java.io"),
+    ("UncheckedIOException", "RuntimeException", "java.io"),
+    ("AssertionError", "Error", "java.lang"),
+    ("VirtualMachineError", "Error", "java.lang"),
+    ("StackOverflowError", "VirtualMachineError", "java.lang"),
+    ("OutOfMemoryError", "VirtualMachineError", "java.lang"),
+];
+
+/// Superclass of the JDK throwable `class_name`, `None` for other classes.
+pub fn builtin_superclass(class_name: &str) -> Option<&'static str> {
+    BUILTIN_THROWABLES
+        .iter()
+        .find(|(name, _, _)| *name == class_name)
+        .map(|(_, superclass, _)| *superclass)
+}
+
+/// Name of `class_name` as a stack trace prints it: JDK throwables with
+/// their package, classes from the analyzed source as written.
+pub fn qualified_name(class_name: &str) -> String {
+    match BUILTIN_THROWABLES
+        .iter()
+        .find(|(name, _, _)| *name == class_name)
+    {
+        Some((name, _, package)) => format!("{}.{}", package, name),
+        None => class_name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_hierarchy_reaches_throwable() {
+        let mut chain = vec!["InputMismatchException"];
+        while let Some(superclass) = builtin_superclass(chain.last().unwrap()) {
+            chain.push(superclass);
+        }
+        assert_eq!(
+            chain,
+            [
+                "InputMismatchException",
+                "NoSuchElementException",
+                "RuntimeException",
+                "Exception",
+                "Throwable",
+                "Object"
+            ]
+        );
+        assert_eq!(builtin_superclass("Dog"), None);
+    }
+
+    #[test]
+    fn stack_traces_qualify_jdk_classes_only() {
+        assert_eq!(
+            qualified_name("ArithmeticException"),
+            "java.lang.ArithmeticException"
+        );
+        assert_eq!(qualified_name("IOException"), "java.io.IOException");
+        assert_eq!(
+            qualified_name("InsufficientFundsException"),
+            "InsufficientFundsException"
+        );
+    }
+}
@@ -1,14 +1,253 @@
+use super::collections::{Collection, Contents, call_collection_method, modeled_collection};
 use super::evaluator::{FlowSignal, Value};
-use crate::{analyzer::AnalysisResult, parser::node_text};
+use super::exceptions::{builtin_superclass, qualified_name};
+use super::frame::StackFrame;
+use super::heap::{ClassStatics, Heap, HeapObject, ObjectId};
+use super::scanner::Stdin;
+use super::strings::call_string_method;
+use crate::{
+    analyzer::AnalysisResult,
+    parser::node_text,
+    repr::{JavaMethod, JavaParameter},
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tree_sitter::Node;
 
 const MAX_LOOP_ITERATIONS: usize = 100;
 
-/// Maps (class_name, method_name) -> (start_byte, end_byte) of the method body in source
+/// Arrays with more elements than this, rows included, aren't allocated:
+/// every step keeps its own copy of the heap.
+const MAX_ARRAY_ELEMENTS: usize = 1000;
+
+/// Maps (class_name, signature) -> (start_byte, end_byte) of the method body in source.
+/// A signature is the method name followed by its erased parameter types,
+/// e.g. `add(double)`; constructors use the name `<init>`.
 pub type MethodBodyMap = HashMap<(String, String), (usize, usize)>;
 
+/// Maps (class_name, signature) -> formal parameters of the declaration,
+/// keyed like [`MethodBodyMap`].
+type MethodParameterMap = HashMap<(String, String), Vec<JavaParameter>>;
+
+/// Java primitive types in widening order; `char` widens to `int` and up.
+const NUMERIC_WIDENING: &[&str] = &["byte", "short", "int", "long", "float", "double"];
+
+/// Primitive types paired with their wrapper classes.
+const BOXED_TYPES: &[(&str, &str)] = &[
+    ("byte", "Byte"),
+    ("short", "Short"),
+    ("int", "Integer"),
+    ("long", "Long"),
+    ("float", "Float"),
+    ("double", "Double"),
+    ("char", "Character"),
+    ("boolean", "Boolean"),
+];
+
+/// Build the signature `name(T1,T2)` used as a [`MethodBodyMap`] key.
+/// Generic arguments are erased, as they are for Java overloading.
+fn method_signature<'a>(name: &str, param_types: impl IntoIterator<Item = &'a str>) -> String {
+    let params: Vec<String> = param_types.into_iter().map(erase_type).collect();
+    format!("{}({})", name, params.join(","))
+}
+
+/// Strip generic arguments and whitespace: `Map<K, V>` becomes `Map`.
+fn erase_type(type_name: &str) -> String {
+    let mut erased = String::new();
+    let mut depth = 0;
+    for c in type_name.chars() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            c if depth == 0 && !c.is_whitespace() => erased.push(c),
+            _ => {}
+        }
+    }
+    erased
+}
+
+/// Parameter types encoded in a signature built by [`method_signature`].
+fn signature_parameter_types(signature: &str) -> Vec<&str> {
+    let inner = signature
+        .split_once('(')
+        .map(|(_, rest)| rest.trim_end_matches(')'))
+        .unwrap_or("");
+    if inner.is_empty() {
+        Vec::new()
+    } else {
+        inner.split(',').collect()
+    }
+}
+
+/// Initialization code of a class, kept in source order because Java runs
+/// field initializers and initializer blocks top to bottom.
+#[derive(Debug, Clone)]
+enum Initializer {
+    /// `Type name = <expr>;`, with the byte range of the expression
+    Field { name: String, value: (usize, usize) },
+    /// `{ ... }` or `static { ... }` initializer block, with its byte range
+    Block((usize, usize)),
+}
+
+/// The statement being executed, which steps are recorded at, and the
+/// program it is part of.
+#[derive(Clone, Copy)]
+struct StepContext<'a, 'tree> {
+    line_number: usize,
+    source_line: &'a str,
+    source: &'a str,
+    root_node: &'a Node<'tree>,
+}
+
+/// Field slot named by an expression such as `this.f`, `obj.f` or a bare `f`.
+struct FieldTarget {
+    /// Object holding the field
+    object_id: ObjectId,
+    /// Class whose declaration of the field is meant
+    declaring_class: String,
+    /// Name of the field
+    field_name: String,
+    /// How the target is written for step descriptions, e.g. `this.age`
+    display_name: String,
+}
+
+/// An exception the JVM raises on its own while evaluating an expression.
+struct RuntimeFault {
+    exception_class: &'static str,
+    /// `None` for an exception thrown without a message
+    message: Option<String>,
+    /// Where the offending value came from, for the step description
+    origin: Option<String>,
+}
+
+/// `StringIndexOutOfBoundsException` for a String method given an index
+/// outside `text`.
+fn string_index_fault(text: &str, method: &str, args: &[Value]) -> Option<RuntimeFault> {
+    let length = text.chars().count() as i64;
+    let indexes: Vec<i64> = args
+        .iter()
+        .map(|arg| arg.as_int().map(i64::from))
+        .collect::<Option<_>>()?;
+    let message = match (method, indexes.as_slice()) {
+        ("charAt", [i]) if !(0..length).contains(i) => {
+            format!("Index {} out of bounds for length {}", i, length)
+        }
+        ("substring", [begin]) if !(0..=length).contains(begin) => {
+            format!("begin {}, end {}, length {}", begin, length, length)
+        }
+        ("substring", [begin, end]) if *begin < 0 || begin > end || *end > length => {
+            format!("begin {}, end {}, length {}", begin, end, length)
+        }
+        _ => return None,
+    };
+    Some(RuntimeFault {
+        exception_class: "StringIndexOutOfBoundsException",
+        message: Some(message),
+        origin: None,
+    })
+}
+
+/// Value of an integer literal such as `42`, `0x7FFF_FFFF` or `10L`. Hex,
+/// octal and binary `int` literals may set the sign bit (`0xFFFFFFFF` is
+/// -1), so the digits are read unsigned and reinterpreted at the width.
+fn integer_literal(text: &str) -> Option<Value> {
+    let text = text.replace('_', "");
+    let (digits, long) = match text.strip_suffix(['l', 'L']) {
+        Some(digits) => (digits, true),
+        None => (text.as_str(), false),
+    };
+    let (digits, radix) = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        (hex, 16)
+    } else if let Some(binary) = digits
+        .strip_prefix("0b")
+        .or_else(|| digits.strip_prefix("0B"))
+    {
+        (binary, 2)
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        (&digits[1..], 8)
+    } else {
+        (digits, 10)
+    };
+    let bits = u64::from_str_radix(digits, radix).ok()?;
+    Some(if long {
+        Value::Long(bits as i64)
+    } else {
+        Value::Int(bits as u32 as i32)
+    })
+}
+
+/// Value of a floating-point literal: a `float` with an `f` suffix, a
+/// `double` otherwise.
+fn floating_literal(text: &str) -> Option<Value> {
+    let text = text.replace('_', "");
+    match text.strip_suffix(['f', 'F']) {
+        Some(digits) => digits.parse::<f32>().ok().map(Value::Float),
+        None => text
+            .trim_end_matches(['d', 'D'])
+            .parse::<f64>()
+            .ok()
+            .map(Value::Double),
+    }
+}
+
+/// Value of a `char` literal such as `'a'`, `'\n'`, `'\101'` or `'\u0041'`.
+fn char_literal(text: &str) -> Option<Value> {
+    let inner = text.strip_prefix('\'')?.strip_suffix('\'')?;
+    let Some(escape) = inner.strip_prefix('\\') else {
+        let mut units = [0; 2];
+        let mut chars = inner.chars();
+        let (c, None) = (chars.next()?, chars.next()) else {
+            return None;
+        };
+        return match c.encode_utf16(&mut units) {
+            [unit] => Some(Value::Char(*unit)),
+            _ => None,
+        };
+    };
+    let unit = match escape {
+        "b" => 0x08,
+        "t" => 0x09,
+        "n" => 0x0A,
+        "f" => 0x0C,
+        "r" => 0x0D,
+        "s" => 0x20,
+        "\"" => 0x22,
+        "'" => 0x27,
+        "\\" => 0x5C,
+        _ if escape.starts_with('u') => {
+            u16::from_str_radix(escape.trim_start_matches('u'), 16).ok()?
+        }
+        _ => u16::from_str_radix(escape, 8).ok()?,
+    };
+    Some(Value::Char(unit))
+}
+
+/// Where a method invocation dispatches, found before it runs.
+struct CallTarget {
+    method_name: String,
+    /// Receiver expression as written; `None` for an unqualified call
+    caller: Option<String>,
+    /// Class the lookup starts from, or the declaring class once a body is found
+    target_class: String,
+    /// Object `this` refers to in the called method
+    receiver: Option<ObjectId>,
+    /// Body that runs; `None` for methods outside the analyzed source
+    resolved: Option<ResolvedMethod>,
+}
+
+/// Method body picked for a call site by [`ExecutionAnalyzer::resolve_method_body`].
+struct ResolvedMethod {
+    /// Class that declares the body
+    class_name: String,
+    /// Signature of the chosen overload
+    signature: String,
+    /// Byte range of the body in the source
+    body: (usize, usize),
+}
+
 /// One atomic event in the execution trace, tied to a source line.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ExecutionStep {
@@ -26,6 +265,14 @@ pub struct ExecutionStep {
     pub active_objects: Vec<String>,
     /// Human-readable explanation of the step
     pub description: String,
+    /// Every object on the heap once this step has run, ordered by id
+    pub heap: Vec<HeapObject>,
+    /// Static fields of every class initialized so far, in initialization order
+    pub statics: Vec<ClassStatics>,
+    /// Stack frames with their parameters and locals (outermost first)
+    pub frames: Vec<StackFrame>,
+    /// Everything written to `System.out` up to and including this step
+    pub console: String,
 }
 
 /// The kind of thing an `ExecutionStep` represents.
@@ -37,12 +284,37 @@ pub enum ExecutionAction {
         method_name: String,
         target_class: String,
         parameters: Vec<String>,
+        /// Overload the call bound to; `None` if no body was found
+        signature: Option<String>,
     },
     /// `new ClassName(...)` produced a new instance.
     ObjectCreation {
         variable_name: String,
         class_name: String,
+        object_id: ObjectId,
         constructor_params: Vec<String>,
+        /// Constructor overload that ran; `None` if the class declares none
+        signature: Option<String>,
+    },
+    /// The fields of a new object were set to their default values, before
+    /// any initializer or constructor code runs.
+    FieldDefaults {
+        class_name: String,
+        object_id: ObjectId,
+    },
+    /// `new T[n]` or an array initializer produced a new array.
+    ArrayCreation {
+        variable_name: String,
+        element_type: String,
+        object_id: ObjectId,
+        length: usize,
+    },
+    /// A reference variable was pointed at an existing object, or at
+    /// `null` when `object_id` is `None`. No object is created.
+    ReferenceAssignment {
+        variable_name: String,
+        class_name: String,
+        object_id: Option<ObjectId>,
     },
     /// A local variable was assigned a value.
     VariableAssignment {
@@ -53,6 +325,7 @@ pub enum ExecutionAction {
     /// A `return` statement executed.
     MethodReturn {
         method_name: String,
+        /// The value returned, or its source text when it can't be evaluated
         return_value: Option<String>,
     },
     /// An `if` / `else` branch decision was taken.
@@ -60,6 +333,14 @@ pub enum ExecutionAction {
         condition: String,
         branch_taken: bool,
     },
+    /// A `switch` compared its selector with the case labels.
+    SwitchBranch {
+        selector: String,
+        value: String,
+        /// Label of the case that runs, e.g. `case 1, 2` or `default`;
+        /// `None` if no case matched
+        matched_label: Option<String>,
+    },
     /// One iteration of a `for` / `while` / `do` loop ran.
     LoopIteration {
         loop_type: String,
@@ -80,16 +361,70 @@ pub enum ExecutionAction {
     /// A field was read.
     FieldAccess {
         class_name: String,
+        object_id: ObjectId,
         field_name: String,
         value: Option<String>,
     },
     /// A field was written.
     FieldMutation {
+        class_name: String,
+        object_id: ObjectId,
+        field_name: String,
+        old_value: Option<String>,
+        new_value: String,
+    },
+    /// A class was initialized: its static fields were created with their
+    /// default values, before its static initializers run.
+    ClassInitialization { class_name: String },
+    /// A static field was written.
+    StaticFieldMutation {
         class_name: String,
         field_name: String,
         old_value: Option<String>,
         new_value: String,
     },
+    /// An array element was written.
+    ArrayElementAssignment {
+        array_name: String,
+        object_id: ObjectId,
+        index: usize,
+        old_value: Option<String>,
+        new_value: String,
+    },
+    /// Nothing on the stack or in a static field refers to these objects
+    /// any more, directly or through other objects. They are shown for
+    /// this step only, then removed from the heap.
+    EligibleForGc { object_ids: Vec<ObjectId> },
+    /// A block ended and the locals declared in it were dropped from the
+    /// frame.
+    VariablesOutOfScope { variable_names: Vec<String> },
+    /// An exception object was thrown.
+    ExceptionThrown {
+        exception_class: String,
+        object_id: ObjectId,
+        message: Option<String>,
+    },
+    /// A frame was popped by an exception propagating out of its method.
+    FrameUnwound {
+        method_name: String,
+        exception_class: String,
+    },
+    /// A `catch` clause took the exception, bound to `variable_name`.
+    ExceptionCaught {
+        exception_class: String,
+        object_id: ObjectId,
+        variable_name: String,
+    },
+    /// Control entered a `finally` block. A pending exception is rethrown
+    /// once it completes.
+    FinallyBlock { pending_exception: Option<String> },
+    /// An exception propagated out of `main`, ending the program.
+    UncaughtException {
+        exception_class: String,
+        object_id: ObjectId,
+        /// The trace Java prints: a header line, then one `at` line per frame
+        stack_trace: Vec<String>,
+    },
 }
 
 /// The full execution trace produced by [`ExecutionAnalyzer::analyze_execution_flow`].
@@ -111,21 +446,37 @@ pub struct ExecutionFlow {
 pub struct ExecutionAnalyzer {
     analysis_result: AnalysisResult,
     current_step: usize,
-    call_stack: Vec<String>,
-    active_objects: HashMap<String, String>, // variable_name -> class_name
+    frames: Vec<StackFrame>,
     steps: Vec<ExecutionStep>,
     call_graph: HashMap<String, Vec<String>>,
     object_lifecycle: HashMap<String, Vec<usize>>,
     source_lines: Vec<String>,
     enhanced_object_tracking: bool,
     method_bodies: MethodBodyMap,
+    method_parameters: MethodParameterMap,
+    instance_initializers: HashMap<String, Vec<Initializer>>,
+    static_initializers: HashMap<String, Vec<Initializer>>,
     max_call_depth: usize,
     current_call_depth: usize,
-    field_values: HashMap<(String, String), String>,
-    current_class: Option<String>,
-    param_values: HashMap<String, String>,
-    local_variables: HashMap<String, String>,
+    heap: Heap,
     flow_signal: Option<FlowSignal>,
+    /// Signals held back while `finally` blocks run, innermost last
+    suspended_signals: Vec<FlowSignal>,
+    /// Objects only hidden JVM state refers to, innermost last: arrays whose
+    /// elements are still being created, and the arrays and iterators the
+    /// running enhanced `for` loops walk
+    hidden_roots: Vec<ObjectId>,
+    console: String,
+    stdin: Option<Stdin>,
+    /// Values of the `Scanner` reads in the statement being executed, by node id
+    input_reads: HashMap<usize, Value>,
+    /// Values of the operands evaluated so far in the running statements,
+    /// calls included, by frame depth and node id so recursive calls keep
+    /// their own. Each is captured as it is evaluated, so side effects of
+    /// the operands to its right don't change it.
+    operand_values: HashMap<(usize, usize), Value>,
+    /// `at` lines of each exception, captured where it was first thrown
+    stack_traces: HashMap<ObjectId, Vec<String>>,
 }
 
 impl ExecutionAnalyzer {
@@ -135,30 +486,62 @@ impl ExecutionAnalyzer {
         ExecutionAnalyzer {
             analysis_result,
             current_step: 0,
-            call_stack: Vec::new(),
-            active_objects: HashMap::new(),
+            frames: Vec::new(),
             steps: Vec::new(),
             call_graph: HashMap::new(),
             object_lifecycle: HashMap::new(),
             source_lines: Vec::new(),
             enhanced_object_tracking: true,
             method_bodies: HashMap::new(),
+            method_parameters: HashMap::new(),
+            instance_initializers: HashMap::new(),
+            static_initializers: HashMap::new(),
             max_call_depth: 10,
             current_call_depth: 0,
-            field_values: HashMap::new(),
-            current_class: None,
-            param_values: HashMap::new(),
-            local_variables: HashMap::new(),
+            heap: Heap::new(),
             flow_signal: None,
+            suspended_signals: Vec::new(),
+            hidden_roots: Vec::new(),
+            console: String::new(),
+            stdin: None,
+            input_reads: HashMap::new(),
+            operand_values: HashMap::new(),
+            stack_traces: HashMap::new(),
         }
     }
 
+    /// Feed `stdin` to the program's `Scanner` reads. Without it the values
+    /// read are unknown.
+    pub fn with_stdin(mut self, stdin: Option<&str>) -> Self {
+        self.stdin = stdin.map(Stdin::new);
+        self
+    }
+
     /// Walk the AST starting from `main` and produce an [`ExecutionFlow`].
     /// Returns an empty flow if no `main` method is found.
     /// Loops are bounded by `MAX_LOOP_ITERATIONS` and recursion by `max_call_depth`.
     pub fn analyze_execution_flow(&mut self, root_node: &Node, source: &str) -> ExecutionFlow {
         // Build method body map before walking main
         self.method_bodies = Self::build_method_body_map(root_node, source);
+        Self::for_each_method_declaration(
+            root_node,
+            source,
+            &mut |class_name, name, declaration| {
+                self.method_parameters.insert(
+                    (
+                        class_name.to_string(),
+                        Self::declared_signature(declaration, name, source),
+                    ),
+                    Self::declared_parameters(declaration, source),
+                );
+            },
+        );
+        Self::collect_initializers(
+            root_node,
+            source,
+            &mut self.instance_initializers,
+            &mut self.static_initializers,
+        );
 
         // Split source into lines for reference
         self.source_lines = source.lines().map(|s| s.to_string()).collect();
@@ -215,14 +598,40 @@ impl ExecutionAnalyzer {
         method_name: String,
         root_node: &Node,
     ) {
-        self.call_stack.push(method_name.clone());
+        // Static entry point: no receiver, but unqualified calls resolve
+        // against the class that declares it
+        let class_name = Self::enclosing_class_name(method_node, source);
+        if let Some(class_name) = &class_name {
+            self.initialize_class(class_name, source, root_node);
+        }
+        self.frames
+            .push(StackFrame::new(&method_name, class_name, None));
 
         // Find the method body
-        if let Some(body) = method_node.child_by_field_name("body") {
+        if let Some(body) = method_node.child_by_field_name("body")
+            && !self.is_throwing()
+        {
             self.analyze_block(&body, source, root_node);
         }
 
-        self.call_stack.pop();
+        let line_number = self.frames.pop().map_or(0, |frame| frame.line);
+        if let Some(FlowSignal::Throw(id)) = self.flow_signal.take() {
+            self.report_uncaught(id, line_number);
+        }
+    }
+
+    /// Name of the class declaration that contains `node`.
+    fn enclosing_class_name(node: &Node, source: &str) -> Option<String> {
+        let mut current = node.parent();
+        while let Some(parent) = current {
+            if parent.kind() == "class_declaration" {
+                return parent
+                    .child_by_field_name("name")
+                    .map(|name| node_text(&name, source).to_string());
+            }
+            current = parent.parent();
+        }
+        None
     }
 
     fn analyze_block(&mut self, block_node: &Node, source: &str, root_node: &Node) {
@@ -233,12 +642,134 @@ impl ExecutionAnalyzer {
             if self.flow_signal.is_some() {
                 break;
             }
+            // Results of the statement's calls were only needed while it ran
+            let depth = self.frames.len();
+            self.operand_values.retain(|&(d, _), _| d != depth);
+            self.collect_garbage(&child);
+        }
+    }
+
+    /// Run `block_node` as a nested block: the locals it declares go out of
+    /// scope when it ends.
+    fn analyze_scoped_block(&mut self, block_node: &Node, source: &str, root_node: &Node) {
+        self.enter_scope();
+        self.analyze_block(block_node, source, root_node);
+        self.exit_scope(block_node);
+    }
+
+    fn enter_scope(&mut self) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.enter_scope();
+        }
+    }
+
+    /// Drop the locals of the innermost block, which ends with `block_node`.
+    /// The step is only shown when the block ends normally or by `break`/
+    /// `continue`; a `return` or an exception discards the whole frame.
+    fn exit_scope(&mut self, block_node: &Node) {
+        let Some(frame) = self.frames.last_mut() else {
+            return;
+        };
+        let dropped = frame.exit_scope();
+        if dropped.is_empty()
+            || matches!(
+                self.flow_signal,
+                Some(FlowSignal::Return(_) | FlowSignal::Throw(_))
+            )
+        {
+            return;
+        }
+
+        let line_number = block_node.end_position().row + 1;
+        frame.line = line_number;
+        let source_line = self.get_source_line(line_number);
+        let variable_names: Vec<String> = dropped.into_iter().map(|v| v.name).collect();
+        let description = match variable_names.as_slice() {
+            [name] => format!("End of block: {} goes out of scope", name),
+            _ => format!(
+                "End of block: {} go out of scope",
+                variable_names.join(", ")
+            ),
+        };
+        self.add_execution_step(
+            line_number,
+            &source_line,
+            ExecutionAction::VariablesOutOfScope { variable_names },
+            description,
+        );
+        self.collect_garbage(block_node);
+    }
+
+    /// Find the objects that became unreachable during `statement`, give
+    /// them an `EligibleForGc` step, then remove them from the heap. Runs
+    /// between statements, where no value is held by an expression being
+    /// evaluated, so the roots are the stack, the static fields, the
+    /// results of the calls in the running statements, arrays still being
+    /// filled in and what a pending `return` or exception carries through a
+    /// `finally` block.
+    fn collect_garbage(&mut self, statement: &Node) {
+        let roots: Vec<ObjectId> = self
+            .frames
+            .iter()
+            .flat_map(|frame| {
+                let variables = frame.parameters.iter().chain(&frame.locals);
+                frame
+                    .this_object
+                    .into_iter()
+                    .chain(variables.filter_map(|v| v.value.as_object()))
+            })
+            .chain(self.operand_values.values().filter_map(Value::as_object))
+            .chain(
+                self.suspended_signals
+                    .iter()
+                    .filter_map(|signal| match signal {
+                        FlowSignal::Throw(id) => Some(*id),
+                        FlowSignal::Return(value) | FlowSignal::Yield(value) => value.as_object(),
+                        FlowSignal::Break | FlowSignal::Continue => None,
+                    }),
+            )
+            .chain(self.hidden_roots.iter().copied())
+            .collect();
+        let unreachable = self.heap.unreachable(roots);
+        if unreachable.is_empty() {
+            return;
+        }
+
+        let names: Vec<String> = unreachable
+            .iter()
+            .map(|&id| format!("{}@{}", self.class_of(id), id))
+            .collect();
+        let description = match names.as_slice() {
+            [name] => format!(
+                "{} is no longer reachable: eligible for garbage collection",
+                name
+            ),
+            _ => format!(
+                "{} are no longer reachable: eligible for garbage collection",
+                names.join(", ")
+            ),
+        };
+        let line_number = statement.end_position().row + 1;
+        let source_line = self.get_source_line(line_number);
+        self.add_execution_step(
+            line_number,
+            &source_line,
+            ExecutionAction::EligibleForGc {
+                object_ids: unreachable.clone(),
+            },
+            description,
+        );
+        for id in unreachable {
+            self.heap.free(id);
         }
     }
 
     fn analyze_statement(&mut self, stmt_node: &Node, source: &str, root_node: &Node) {
         let line_number = stmt_node.start_position().row + 1;
         let source_line = self.get_source_line(line_number);
+        if let Some(frame) = self.frames.last_mut() {
+            frame.line = line_number;
+        }
 
         match stmt_node.kind() {
             "local_variable_declaration" => {
@@ -290,7 +821,42 @@ impl ExecutionAnalyzer {
                 );
             }
             "block" => {
-                self.analyze_block(stmt_node, source, root_node);
+                self.analyze_scoped_block(stmt_node, source, root_node);
+            }
+            "explicit_constructor_invocation" => {
+                // super(...) / this(...) already ran in run_constructor
+            }
+            "throw_statement" => {
+                self.analyze_throw_statement(
+                    stmt_node,
+                    source,
+                    line_number,
+                    &source_line,
+                    root_node,
+                );
+            }
+            "try_statement" | "try_with_resources_statement" => {
+                self.analyze_try_statement(stmt_node, source, root_node);
+            }
+            "switch_expression" => {
+                if self.check_expression(stmt_node, source, root_node) {
+                    self.execute_switch(
+                        stmt_node,
+                        source,
+                        line_number,
+                        &source_line,
+                        root_node,
+                        false,
+                    );
+                }
+            }
+            "yield_statement" => {
+                if self.check_expression(stmt_node, source, root_node)
+                    && let Some(expr) = stmt_node.named_child(0)
+                {
+                    let value = self.yielded_value(&expr, source);
+                    self.flow_signal = Some(FlowSignal::Yield(value));
+                }
             }
             "break_statement" => {
                 self.flow_signal = Some(FlowSignal::Break);
@@ -328,6 +894,9 @@ impl ExecutionAnalyzer {
         let mut variable_name = String::new();
         let mut class_name = String::new();
         let mut value_handled = false;
+        if !self.check_expression(decl_node, source, root_node) {
+            return;
+        }
 
         // Extract variable name and type
         let mut cursor = decl_node.walk();
@@ -347,83 +916,48 @@ impl ExecutionAnalyzer {
                     if let Some(name_node) = child.child_by_field_name("name") {
                         variable_name = node_text(&name_node, source).to_string();
                     }
+                    // `int a[] = ...` puts the array dimensions on the declarator
+                    let declared_type = match child.child_by_field_name("dimensions") {
+                        Some(dims) => {
+                            format!("{}{}", class_name, erase_type(node_text(&dims, source)))
+                        }
+                        None => class_name.clone(),
+                    };
 
                     // Check if there's an object creation
                     if let Some(value_node) = child.child_by_field_name("value") {
                         if value_node.kind() == "object_creation_expression" {
                             value_handled = true;
-                            let params = self.extract_constructor_parameters(&value_node, source);
-
-                            // Extract actual class name from the creation expression
-                            let creation_class =
-                                if let Some(type_node) = value_node.child_by_field_name("type") {
-                                    node_text(&type_node, source).to_string()
-                                } else {
-                                    class_name.clone()
-                                };
-
-                            self.active_objects
-                                .insert(variable_name.clone(), creation_class.clone());
-                            self.record_object_creation(&variable_name);
-
-                            // Push constructor onto call stack BEFORE emitting the step
-                            let body_range = self.find_method_body(&creation_class, "<init>");
-                            let has_body = body_range.is_some()
-                                && self.current_call_depth < self.max_call_depth;
-
-                            if has_body {
-                                self.current_call_depth += 1;
-                                self.call_stack.push(format!("{}.<init>", creation_class));
-                            }
-
-                            self.add_execution_step(
-                                line_number,
-                                source_line,
-                                ExecutionAction::ObjectCreation {
-                                    variable_name: variable_name.clone(),
-                                    class_name: creation_class.clone(),
-                                    constructor_params: params.clone(),
+                            self.execute_object_creation(
+                                &value_node,
+                                &variable_name,
+                                Some(&class_name),
+                                StepContext {
+                                    line_number,
+                                    source_line,
+                                    source,
+                                    root_node,
                                 },
-                                format!("Create new {} object: {}", creation_class, variable_name),
                             );
-
-                            // Step into constructor body if available
-                            if let Some((start, end)) = body_range
-                                && has_body
-                                && let Some(body_node) =
-                                    root_node.descendant_for_byte_range(start, end)
-                            {
-                                let old_class = self.current_class.take();
-                                self.current_class = Some(creation_class.clone());
-
-                                // Map formal constructor param names to call-site arg values
-                                let old_params = std::mem::take(&mut self.param_values);
-                                let old_locals = std::mem::take(&mut self.local_variables);
-                                if let Some(class) = self
-                                    .analysis_result
-                                    .classes
-                                    .iter()
-                                    .find(|c| c.name == creation_class)
-                                    && let Some(ctor) = class.constructors.first()
-                                {
-                                    for (i, formal) in ctor.parameters.iter().enumerate() {
-                                        if let Some(arg_val) = params.get(i) {
-                                            self.param_values
-                                                .insert(formal.name.clone(), arg_val.clone());
-                                        }
-                                    }
-                                }
-
-                                self.analyze_block(&body_node, source, root_node);
-
-                                self.local_variables = old_locals;
-                                self.param_values = old_params;
-                                self.current_class = old_class;
-                            }
-
-                            if has_body {
-                                self.call_stack.pop();
-                                self.current_call_depth -= 1;
+                        } else if matches!(
+                            value_node.kind(),
+                            "array_creation_expression" | "array_initializer"
+                        ) {
+                            value_handled = true;
+                            self.execute_array_creation(
+                                &value_node,
+                                &variable_name,
+                                &declared_type,
+                                true,
+                                StepContext {
+                                    line_number,
+                                    source_line,
+                                    source,
+                                    root_node,
+                                },
+                            );
+                            if self.is_throwing() {
+                                return;
                             }
                         } else if value_node.kind() == "method_invocation" {
                             value_handled = true;
@@ -434,7 +968,36 @@ impl ExecutionAnalyzer {
                                 source_line,
                                 root_node,
                             );
-                            let value = node_text(&value_node, source).to_string();
+                            if self.is_throwing() {
+                                return;
+                            }
+                            // Built-in String methods have a known result
+                            let evaluated = self.evaluate_expression(&value_node, source);
+                            if let Value::Str(text) = &evaluated {
+                                self.bind_new_string(
+                                    &variable_name,
+                                    &class_name,
+                                    text,
+                                    false,
+                                    line_number,
+                                    source_line,
+                                );
+                                continue;
+                            }
+                            // `Dog d = make();` points `d` at the returned object
+                            if self.is_reference_value(&evaluated, &class_name) {
+                                self.declare_local(&variable_name, &class_name, evaluated.clone());
+                                self.emit_reference_assignment(
+                                    &variable_name,
+                                    &class_name,
+                                    &evaluated,
+                                    line_number,
+                                    source_line,
+                                );
+                                continue;
+                            }
+                            let value = Self::display_value(&evaluated, &value_node, source);
+                            self.declare_local(&variable_name, &class_name, evaluated);
                             self.add_execution_step(
                                 line_number,
                                 source_line,
@@ -447,17 +1010,61 @@ impl ExecutionAnalyzer {
                             );
                         } else {
                             value_handled = true;
-                            let raw = node_text(&value_node, source).to_string();
-                            let resolved = self.resolve_value(&raw);
-                            self.local_variables
-                                .insert(variable_name.clone(), resolved.clone());
+                            let evaluated = if value_node.kind() == "switch_expression" {
+                                let value = self.execute_switch(
+                                    &value_node,
+                                    source,
+                                    line_number,
+                                    source_line,
+                                    root_node,
+                                    true,
+                                );
+                                if self.is_throwing() {
+                                    return;
+                                }
+                                value.unwrap_or(Value::Unknown)
+                            } else {
+                                self.evaluate_expression(&value_node, source)
+                            };
+                            // `double d = 7;` widens, `char c = 65;` narrows
+                            let evaluated = evaluated.convert_to(&declared_type);
+
+                            // `String s = "Rex";` refers to the pooled literal;
+                            // `s + "!"` produces a new String object
+                            if let Value::Str(text) = &evaluated {
+                                self.bind_new_string(
+                                    &variable_name,
+                                    &class_name,
+                                    text,
+                                    Self::is_constant_expression(&value_node),
+                                    line_number,
+                                    source_line,
+                                );
+                                continue;
+                            }
+
+                            // `Dog b = a;` copies the reference, not the object
+                            if self.is_reference_value(&evaluated, &class_name) {
+                                self.declare_local(&variable_name, &class_name, evaluated.clone());
+                                self.emit_reference_assignment(
+                                    &variable_name,
+                                    &class_name,
+                                    &evaluated,
+                                    line_number,
+                                    source_line,
+                                );
+                                continue;
+                            }
+
+                            let display = Self::display_value(&evaluated, &value_node, source);
+                            self.declare_local(&variable_name, &class_name, evaluated);
                             self.add_execution_step(
                                 line_number,
                                 source_line,
                                 ExecutionAction::VariableAssignment {
                                     variable_name: variable_name.clone(),
                                     value_type: class_name.clone(),
-                                    value: resolved,
+                                    value: display,
                                 },
                                 format!("Assign value to variable: {}", variable_name),
                             );
@@ -469,6 +1076,7 @@ impl ExecutionAnalyzer {
         }
 
         if !value_handled && !variable_name.is_empty() {
+            self.declare_local(&variable_name, &class_name, Value::Unknown);
             self.add_execution_step(
                 line_number,
                 source_line,
@@ -482,362 +1090,3308 @@ impl ExecutionAnalyzer {
         }
     }
 
-    fn analyze_expression_statement(
+    /// Allocate the object described by `creation_node` on the heap, emit its
+    /// `ObjectCreation` step and run the constructor with `this` bound to it.
+    /// `variable_name` is the assignment target (`d`, `this.owner`, ...).
+    /// When `local_type` is given the target is a local variable of that
+    /// declared type and is pointed at the object before the constructor runs,
+    /// so the diagram shows the arrow while the fields are filled in; other
+    /// targets are left to the caller.
+    fn execute_object_creation(
         &mut self,
-        expr_node: &Node,
-        source: &str,
-        line_number: usize,
-        source_line: &str,
-        root_node: &Node,
-    ) {
-        if let Some(expr) = expr_node.child(0) {
-            if expr.kind() == "method_invocation" {
-                self.analyze_method_invocation(&expr, source, line_number, source_line, root_node);
-            } else if expr.kind() == "assignment_expression" {
-                self.analyze_assignment(&expr, source, line_number, source_line, root_node);
-            }
+        creation_node: &Node,
+        variable_name: &str,
+        local_type: Option<&str>,
+        ctx: StepContext,
+    ) -> ObjectId {
+        let StepContext {
+            source,
+            line_number,
+            source_line,
+            root_node,
+        } = ctx;
+        let params = self.extract_constructor_parameters(creation_node, source);
+        let arg_types = self.argument_types(creation_node, source);
+        let creation_class = creation_node
+            .child_by_field_name("type")
+            .map(|type_node| node_text(&type_node, source).to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        if creation_class == "String" {
+            return self.execute_string_creation(
+                creation_node,
+                source,
+                variable_name,
+                local_type,
+                line_number,
+                source_line,
+            );
         }
-    }
-
-    fn analyze_method_invocation(
-        &mut self,
-        method_node: &Node,
-        source: &str,
-        line_number: usize,
-        source_line: &str,
-        root_node: &Node,
-    ) {
-        let mut method_name = String::new();
-        let mut caller = None;
-        let mut target_class = "unknown".to_string();
-        let mut parameters = Vec::new();
-
-        // Extract method name
-        if let Some(name_node) = method_node.child_by_field_name("name") {
-            method_name = node_text(&name_node, source).to_string();
+        if let Some(collection) = modeled_collection(&erase_type(&creation_class)) {
+            return self.execute_collection_creation(
+                collection,
+                creation_node,
+                variable_name,
+                local_type,
+                ctx,
+            );
         }
 
-        // Extract caller object
-        if let Some(object_node) = method_node.child_by_field_name("object") {
-            let caller_name = node_text(&object_node, source).to_string();
-            caller = Some(caller_name.clone());
+        self.initialize_class(&creation_class, source, root_node);
 
-            // Enhanced object class resolution
-            target_class = self.resolve_object_class_enhanced(&caller_name);
+        // Every field, inherited ones included, starts at its default value
+        let object_id = self.heap.allocate(&creation_class);
+        if self.is_subclass_of(&creation_class, "Throwable") {
+            self.heap
+                .set_field(object_id, "Throwable", "message", Value::Null);
         }
-
-        // Extract parameters
-        if let Some(args_node) = method_node.child_by_field_name("arguments") {
-            parameters = self.extract_method_arguments(&args_node, source);
+        let fields = self.instance_fields(&creation_class);
+        for (declaring_class, field_name, field_type) in &fields {
+            self.heap.set_field(
+                object_id,
+                declaring_class,
+                field_name,
+                Self::default_value(field_type),
+            );
         }
-
-        // Record the method call in call graph
-        let caller_method = self
-            .call_stack
-            .last()
-            .unwrap_or(&"unknown".to_string())
-            .clone();
-        let called_method = format!("{}.{}", target_class, method_name);
-
-        self.call_graph
-            .entry(caller_method)
-            .or_default()
-            .push(called_method.clone());
-
-        // Record object usage
-        if let Some(caller_name) = &caller {
-            self.record_object_usage(caller_name);
+        let args = self.evaluate_call_arguments(creation_node, source);
+        if builtin_superclass(&creation_class).is_some() {
+            self.init_builtin_throwable(object_id, &args);
         }
-
-        // Push call stack BEFORE emitting the MethodCall step
-        let body_range = self.find_method_body(&target_class, &method_name);
-        let has_body = body_range.is_some() && self.current_call_depth < self.max_call_depth;
-
-        if has_body {
-            self.current_call_depth += 1;
-            self.call_stack
-                .push(format!("{}.{}", target_class, method_name));
+        self.record_object_creation(variable_name);
+        if let Some(type_name) = local_type {
+            self.bind_local(variable_name, type_name, Value::Ref(object_id));
         }
 
+        // Push constructor onto call stack BEFORE emitting the step
+        let constructor = self.resolve_method_body(&creation_class, "<init>", &arg_types);
+        let signature = constructor.as_ref().map(|c| c.signature.clone());
+        let entered =
+            self.enter_constructor(&creation_class, signature.as_deref(), object_id, args);
+
         self.add_execution_step(
             line_number,
             source_line,
-            ExecutionAction::MethodCall {
-                caller,
-                method_name: method_name.clone(),
-                target_class: target_class.clone(),
-                parameters: parameters.clone(),
+            ExecutionAction::ObjectCreation {
+                variable_name: variable_name.to_string(),
+                class_name: creation_class.clone(),
+                object_id,
+                constructor_params: params,
+                signature,
             },
-            format!("Call method: {}", method_name),
+            format!("Create new {} object: {}", creation_class, variable_name),
         );
-
-        if let Some((start, end)) = body_range
-            && has_body
-            && let Some(body_node) = root_node.descendant_for_byte_range(start, end)
-        {
-            let old_class = self.current_class.take();
-            self.current_class = Some(target_class.clone());
-
-            // Map formal parameter names to call-site argument values
-            let resolved_args: Vec<String> = parameters
+        if !fields.is_empty() {
+            let defaults: Vec<String> = fields
                 .iter()
-                .map(|arg| self.resolve_value(arg))
+                .map(|(_, name, field_type)| {
+                    format!("{} = {}", name, Self::default_value(field_type))
+                })
                 .collect();
+            self.add_execution_step(
+                line_number,
+                source_line,
+                ExecutionAction::FieldDefaults {
+                    class_name: creation_class.clone(),
+                    object_id,
+                },
+                format!(
+                    "Fields of the new {} start at their default values: {}",
+                    creation_class,
+                    defaults.join(", ")
+                ),
+            );
+        }
 
-            let old_params = std::mem::take(&mut self.param_values);
-            let old_locals = std::mem::take(&mut self.local_variables);
-            if let Some(class) = self
-                .analysis_result
-                .classes
-                .iter()
-                .find(|c| c.name == target_class)
-                && let Some(method) = class.methods.iter().find(|m| m.name == method_name)
-            {
-                for (i, formal) in method.parameters.iter().enumerate() {
-                    if let Some(arg_val) = resolved_args.get(i) {
-                        self.param_values
-                            .insert(formal.name.clone(), arg_val.clone());
-                    }
-                }
-            }
+        if entered {
+            self.run_constructor(&creation_class, constructor, object_id, ctx);
+        }
 
-            self.analyze_block(&body_node, source, root_node);
+        object_id
+    }
 
-            self.local_variables = old_locals;
-            self.param_values = old_params;
-            self.current_class = old_class;
+    /// `new ArrayList<>()` and the other modeled collections: an empty
+    /// instance with its backing arrays, or one holding the contents of the
+    /// collection passed to the constructor. The JDK constructor isn't
+    /// stepped into.
+    fn execute_collection_creation(
+        &mut self,
+        collection: &'static dyn Collection,
+        creation_node: &Node,
+        variable_name: &str,
+        local_type: Option<&str>,
+        ctx: StepContext,
+    ) -> ObjectId {
+        let StepContext {
+            source,
+            line_number,
+            source_line,
+            ..
+        } = ctx;
+        let params = self.extract_constructor_parameters(creation_node, source);
+        let args = self.evaluate_call_arguments(creation_node, source);
+        let mut contents = Contents::default();
+        if let [Value::Ref(other)] = args.as_slice()
+            && self
+                .collection_of(*other)
+                .is_some_and(|c| c.is_map() == collection.is_map())
+            && let Some(copied) = self.collection_contents(*other)
+        {
+            if collection.is_map() {
+                contents = copied;
+            } else {
+                // `new HashSet<>(list)` drops the duplicates
+                for element in copied.elements {
+                    call_collection_method(collection, &mut contents, "add", &[element], |a, b| {
+                        self.java_equals(a, b)
+                    });
+                }
+            }
         }
 
-        if has_body {
-            self.call_stack.pop();
-            self.current_call_depth -= 1;
+        let object_id = self.heap.allocate(collection.name());
+        for field in collection.array_fields() {
+            let array = self.heap.allocate_array("Object", Vec::new());
+            self.heap
+                .set_field(object_id, collection.name(), field, Value::Ref(array));
+        }
+        self.store_collection_contents(object_id, contents);
+        self.record_object_creation(variable_name);
+        if let Some(type_name) = local_type {
+            self.bind_local(variable_name, type_name, Value::Ref(object_id));
         }
+        self.add_execution_step(
+            line_number,
+            source_line,
+            ExecutionAction::ObjectCreation {
+                variable_name: variable_name.to_string(),
+                class_name: collection.name().to_string(),
+                object_id,
+                constructor_params: params,
+                signature: None,
+            },
+            format!("Create new {} object: {}", collection.name(), variable_name),
+        );
+        object_id
     }
 
-    fn analyze_assignment(
+    /// The modeled collection class of the object `id`, if it is one.
+    fn collection_of(&self, id: ObjectId) -> Option<&'static dyn Collection> {
+        self.heap
+            .get(id)
+            .and_then(|obj| modeled_collection(&obj.class_name))
+    }
+
+    /// The modeled collection the invocation `call` is made on, if any.
+    fn collection_receiver(&self, call: &Node, source: &str) -> Option<ObjectId> {
+        let object = call.child_by_field_name("object")?;
+        match self.evaluate_expression(&object, source) {
+            Value::Ref(id) if self.collection_of(id).is_some() => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Contents of the modeled collection `id`, read from its backing arrays.
+    fn collection_contents(&self, id: ObjectId) -> Option<Contents> {
+        let collection = self.collection_of(id)?;
+        let array = |field: &str| match self.heap.get_field(id, collection.name(), field) {
+            Some(Value::Ref(array)) => self.heap.get(*array)?.elements.clone(),
+            _ => None,
+        };
+        let fields = collection.array_fields();
+        Some(Contents {
+            elements: array(fields[0])?,
+            values: match fields.get(1) {
+                Some(field) => array(field)?,
+                None => Vec::new(),
+            },
+        })
+    }
+
+    /// Write `contents` back to the backing arrays of the collection `id`
+    /// and update its `size`.
+    fn store_collection_contents(&mut self, id: ObjectId, contents: Contents) {
+        let Some(collection) = self.collection_of(id) else {
+            return;
+        };
+        let size = contents.elements.len();
+        for (field, items) in collection
+            .array_fields()
+            .iter()
+            .zip([contents.elements, contents.values])
+        {
+            if let Some(&Value::Ref(array)) = self.heap.get_field(id, collection.name(), field) {
+                self.heap.set_elements(array, items);
+            }
+        }
+        self.heap
+            .set_field(id, collection.name(), "size", Value::Int(size as i32));
+    }
+
+    /// Run the method `method_name` of the modeled collection `id` for the
+    /// invocation `call`, storing what it changes. Objects created in the
+    /// arguments, as in `dogs.add(new Dog())`, are allocated first.
+    fn call_collection(
+        &mut self,
+        id: ObjectId,
+        method_name: &str,
+        call: &Node,
+        ctx: StepContext,
+    ) -> Option<Result<Option<Value>, RuntimeFault>> {
+        let source = ctx.source;
+        let collection = self.collection_of(id)?;
+        let receiver = call
+            .child_by_field_name("object")
+            .map(|object| node_text(&object, source).to_string())
+            .unwrap_or_default();
+        let target_name = format!("{}.{}(...)", receiver, method_name);
+        let mut args = Vec::new();
+        if let Some(args_node) = call.child_by_field_name("arguments") {
+            let mut cursor = args_node.walk();
+            let arg_nodes: Vec<Node> = args_node.named_children(&mut cursor).collect();
+            for arg in arg_nodes {
+                let value = if Self::allocates(&arg) {
+                    self.evaluate_allocating(&arg, &target_name, "Object", ctx)
+                } else {
+                    self.evaluate_expression(&arg, source)
+                };
+                args.push(self.store_string(value, Self::is_constant_expression(&arg)));
+            }
+        }
+        let mut contents = self.collection_contents(id)?;
+        let result =
+            call_collection_method(collection, &mut contents, method_name, &args, |a, b| {
+                self.java_equals(a, b)
+            })?;
+        match result {
+            Ok(returned) => {
+                self.store_collection_contents(id, contents);
+                Some(Ok(returned))
+            }
+            Err(fault) => Some(Err(RuntimeFault {
+                exception_class: fault.exception_class,
+                message: Some(fault.message),
+                origin: None,
+            })),
+        }
+    }
+
+    /// `a.equals(b)` for the elements of a modeled collection. Strings and
+    /// primitives compare by value, other objects by identity; `None` when
+    /// either is unknown or a class in the source overrides `equals`.
+    fn java_equals(&self, a: &Value, b: &Value) -> Option<bool> {
+        let text = |value: &Value| match value {
+            Value::Str(text) => Some(text.clone()),
+            Value::Ref(id) => self.heap.get(*id)?.string_value().map(str::to_string),
+            _ => None,
+        };
+        let overrides_equals = |value: &Value| match value {
+            Value::Ref(id) => {
+                let class_name = self.class_of(*id);
+                self.method_bodies.keys().any(|(owner, signature)| {
+                    signature.starts_with("equals(") && self.is_subclass_of(&class_name, owner)
+                })
+            }
+            _ => false,
+        };
+        if !a.is_known() || !b.is_known() || overrides_equals(a) {
+            return None;
+        }
+        match (text(a), text(b)) {
+            (Some(a), Some(b)) => Some(a == b),
+            _ => Some(a == b),
+        }
+    }
+
+    /// `new String(...)`: always a new object, even when the argument is a
+    /// literal whose pooled instance already holds the same characters.
+    fn execute_string_creation(
+        &mut self,
+        creation_node: &Node,
+        source: &str,
+        variable_name: &str,
+        local_type: Option<&str>,
+        line_number: usize,
+        source_line: &str,
+    ) -> ObjectId {
+        let params = self.extract_constructor_parameters(creation_node, source);
+        let args = self.evaluate_call_arguments(creation_node, source);
+        let text = match args.as_slice() {
+            [] => Some(String::new()),
+            [arg] => match self.string_operand(arg.clone()) {
+                Value::Str(text) => Some(text),
+                _ => None,
+            },
+            _ => None,
+        };
+        let object_id = match text {
+            Some(text) => self.heap.allocate_string(&text),
+            None => self.heap.allocate("String"),
+        };
+        self.record_object_creation(variable_name);
+        if let Some(type_name) = local_type {
+            self.bind_local(variable_name, type_name, Value::Ref(object_id));
+        }
+
+        self.add_execution_step(
+            line_number,
+            source_line,
+            ExecutionAction::ObjectCreation {
+                variable_name: variable_name.to_string(),
+                class_name: "String".to_string(),
+                object_id,
+                constructor_params: params,
+                signature: None,
+            },
+            format!("Create new String object: {}", variable_name),
+        );
+        object_id
+    }
+
+    /// Allocate the array described by `creation_node` (`new T[n]`,
+    /// `new T[n][m]`, `new T[]{...}` or a bare `{...}` initializer) and emit
+    /// its `ArrayCreation` step. `array_type` is the declared type of the
+    /// target, which is all a bare initializer has to go on. When
+    /// `bind_local` is set the target is a local variable and is pointed at
+    /// the array.
+    ///
+    /// As in the JVM the array exists, filled with default values, before
+    /// any element initializer runs; elements that create objects, and the
+    /// rows of a nested initializer, are then stored one by one with their
+    /// own steps. Each row is an array object of its own.
+    ///
+    /// A negative length throws `NegativeArraySizeException`. When a length
+    /// can't be evaluated (input that wasn't given), or the array would hold
+    /// more than [`MAX_ARRAY_ELEMENTS`], no array is allocated and the value
+    /// is unknown.
+    fn execute_array_creation(
+        &mut self,
+        creation_node: &Node,
+        variable_name: &str,
+        array_type: &str,
+        bind_local: bool,
+        ctx: StepContext,
+    ) -> Value {
+        let StepContext {
+            source,
+            line_number,
+            source_line,
+            root_node,
+        } = ctx;
+        // Lengths of the dimensions given in brackets, outermost first
+        let (array_type, initializer, lengths) = match creation_node.kind() {
+            "array_initializer" => (erase_type(array_type), Some(*creation_node), Vec::new()),
+            _ => {
+                let base = creation_node
+                    .child_by_field_name("type")
+                    .map(|t| node_text(&t, source).to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+                let mut rank = 0;
+                let mut lengths = Vec::new();
+                let mut cursor = creation_node.walk();
+                for dims in creation_node.children_by_field_name("dimensions", &mut cursor) {
+                    if dims.kind() == "dimensions_expr" {
+                        lengths.push(
+                            dims.named_child(0)
+                                .and_then(|expr| self.evaluate_expression(&expr, source).as_int()),
+                        );
+                        rank += 1;
+                    } else {
+                        rank += node_text(&dims, source).matches('[').count();
+                    }
+                }
+                (
+                    format!("{}{}", base, "[]".repeat(rank.max(1))),
+                    creation_node.child_by_field_name("value"),
+                    lengths,
+                )
+            }
+        };
+        let element_type = array_type
+            .strip_suffix("[]")
+            .unwrap_or(&array_type)
+            .to_string();
+
+        // Every length is checked before anything is allocated
+        if let Some(negative) = lengths.iter().flatten().find(|&&n| n < 0) {
+            self.raise(
+                RuntimeFault {
+                    exception_class: "NegativeArraySizeException",
+                    message: Some(negative.to_string()),
+                    origin: None,
+                },
+                line_number,
+                source_line,
+            );
+            return Value::Unknown;
+        }
+        let lengths = lengths
+            .into_iter()
+            .map(|n| n.map(|n| n as usize))
+            .collect::<Option<Vec<usize>>>();
+        let too_large = lengths.as_ref().is_some_and(|lengths| {
+            lengths
+                .iter()
+                .try_fold(1usize, |total, &n| total.checked_mul(n))
+                .is_none_or(|total| total > MAX_ARRAY_ELEMENTS)
+        });
+        let Some(lengths) = lengths.filter(|_| !too_large) else {
+            if bind_local {
+                self.bind_local(variable_name, &array_type, Value::Unknown);
+                self.add_execution_step(
+                    line_number,
+                    source_line,
+                    ExecutionAction::VariableAssignment {
+                        variable_name: variable_name.to_string(),
+                        value_type: array_type.clone(),
+                        value: node_text(creation_node, source).to_string(),
+                    },
+                    format!(
+                        "Assign value to variable: {} ({})",
+                        variable_name,
+                        if too_large {
+                            "array too large to track"
+                        } else {
+                            "array length unknown"
+                        }
+                    ),
+                );
+            }
+            return Value::Unknown;
+        };
+
+        // Plain element values are known up front; object-creating ones
+        // (rows of a nested initializer included) start at the default
+        // value and are filled in below
+        let element_nodes: Vec<Node> = match initializer {
+            Some(init) => {
+                let mut cursor = init.walk();
+                init.named_children(&mut cursor).collect()
+            }
+            None => Vec::new(),
+        };
+        let object_id = match initializer {
+            Some(_) => {
+                let elements = element_nodes
+                    .iter()
+                    .map(|element| {
+                        if Self::allocates(element) {
+                            Self::default_value(&element_type)
+                        } else {
+                            self.evaluate_expression(element, source)
+                                .convert_to(&element_type)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let array_id = self.heap.allocate_array(&element_type, elements);
+                for (index, element) in element_nodes.iter().enumerate() {
+                    if let Some(value @ Value::Str(_)) =
+                        self.heap.get_element(array_id, index).cloned()
+                    {
+                        let stored =
+                            self.store_string(value, Self::is_constant_expression(element));
+                        self.heap.set_element(array_id, index, stored);
+                    }
+                }
+                array_id
+            }
+            None => self.allocate_dimensions(&element_type, &lengths),
+        };
+        // Element constructors run statements, and with them the collector,
+        // before anything refers to the array
+        self.hidden_roots.push(object_id);
+        let length = self.heap.array_length(object_id).unwrap_or(0);
+        self.record_object_creation(variable_name);
+        if bind_local {
+            self.bind_local(variable_name, &array_type, Value::Ref(object_id));
+        }
+
+        // Shape as written: `int[2][3]`, `int[3][]`, or `int[2][]` for an
+        // initializer whose rows are created below
+        let base = array_type.trim_end_matches("[]");
+        let rank = (array_type.len() - base.len()) / 2;
+        let known = if lengths.is_empty() {
+            vec![length]
+        } else {
+            lengths
+        };
+        let shape = format!(
+            "{}{}{}",
+            base,
+            known.iter().map(|n| format!("[{}]", n)).collect::<String>(),
+            "[]".repeat(rank.saturating_sub(known.len())),
+        );
+
+        self.add_execution_step(
+            line_number,
+            source_line,
+            ExecutionAction::ArrayCreation {
+                variable_name: variable_name.to_string(),
+                element_type: element_type.clone(),
+                object_id,
+                length,
+            },
+            format!("Create new {} array: {}", shape, variable_name),
+        );
+
+        for (index, element) in element_nodes.iter().enumerate() {
+            if !Self::allocates(element) {
+                continue;
+            }
+            let element_name = format!("{}[{}]", variable_name, index);
+            let value = self.evaluate_allocating(
+                element,
+                &element_name,
+                &element_type,
+                StepContext {
+                    line_number,
+                    source_line,
+                    source,
+                    root_node,
+                },
+            );
+            if self.is_throwing() {
+                break;
+            }
+            let new_value = self.describe_value(&value);
+            let old = self.heap.set_element(object_id, index, value);
+            self.add_execution_step(
+                line_number,
+                source_line,
+                ExecutionAction::ArrayElementAssignment {
+                    array_name: variable_name.to_string(),
+                    object_id,
+                    index,
+                    old_value: old.map(|v| self.describe_value(&v)),
+                    new_value,
+                },
+                format!("Set array element: {}", element_name),
+            );
+        }
+        self.hidden_roots.pop();
+
+        Value::Ref(object_id)
+    }
+
+    /// Allocate an array of `element_type` with the given dimension
+    /// `lengths`, outermost first, filled with default values. Like the
+    /// JVM's `multianewarray`, every dimension with a length gets its own
+    /// row arrays; dimensions without one (`new int[3][]`) stay `null`.
+    fn allocate_dimensions(&mut self, element_type: &str, lengths: &[usize]) -> ObjectId {
+        let length = lengths.first().copied().unwrap_or(0);
+        let id = self.heap.allocate_array(
+            element_type,
+            vec![Self::default_value(element_type); length],
+        );
+        if lengths.len() > 1
+            && let Some(row_type) = element_type.strip_suffix("[]")
+        {
+            for index in 0..length {
+                let row = self.allocate_dimensions(row_type, &lengths[1..]);
+                self.heap.set_element(id, index, Value::Ref(row));
+            }
+        }
+        id
+    }
+
+    /// Whether evaluating `node` allocates on the heap and so has to go
+    /// through [`Self::evaluate_allocating`].
+    fn allocates(node: &Node) -> bool {
+        matches!(
+            node.kind(),
+            "object_creation_expression" | "array_creation_expression" | "array_initializer"
+        )
+    }
+
+    /// Evaluate an expression whose value is stored into `target_name`
+    /// (a field or array element) of declared type `target_type`.
+    /// Object and array creations run with their own steps, unless
+    /// [`Self::run_operands`] already allocated them; anything else is
+    /// evaluated without side effects.
+    fn evaluate_allocating(
+        &mut self,
+        node: &Node,
+        target_name: &str,
+        target_type: &str,
+        ctx: StepContext,
+    ) -> Value {
+        let StepContext {
+            source,
+            line_number,
+            source_line,
+            root_node,
+        } = ctx;
+        if let Some(value) = self.operand_values.get(&(self.frames.len(), node.id())) {
+            return value.clone();
+        }
+        match node.kind() {
+            "object_creation_expression" => {
+                Value::Ref(self.execute_object_creation(node, target_name, None, ctx))
+            }
+            "array_creation_expression" | "array_initializer" => self.execute_array_creation(
+                node,
+                target_name,
+                target_type,
+                false,
+                StepContext {
+                    line_number,
+                    source_line,
+                    source,
+                    root_node,
+                },
+            ),
+            _ => self.evaluate_expression(node, source),
+        }
+    }
+
+    /// Push the frame of a constructor of `class_name` running on
+    /// `object_id`. Classes without a declared constructor get the implicit
+    /// no-argument one. Returns `false`, pushing nothing, for classes outside
+    /// the analyzed source or when the call depth limit is reached.
+    fn enter_constructor(
+        &mut self,
+        class_name: &str,
+        signature: Option<&str>,
+        object_id: ObjectId,
+        args: Vec<Value>,
+    ) -> bool {
+        let is_user_class = self
+            .analysis_result
+            .classes
+            .iter()
+            .any(|c| c.name == class_name);
+        if !is_user_class || self.current_call_depth >= self.max_call_depth {
+            return false;
+        }
+        self.current_call_depth += 1;
+        self.push_frame(
+            class_name,
+            signature.unwrap_or("<init>()"),
+            Some(object_id),
+            args,
+        );
+        true
+    }
+
+    /// Run a constructor whose frame [`Self::enter_constructor`] pushed, in
+    /// Java's initialization order, then pop the frame:
+    /// 1. `this(...)` delegation, or an explicit or implicit `super(...)`
+    /// 2. field initializers and initializer blocks (skipped after `this(...)`,
+    ///    since the delegate already ran them)
+    /// 3. the rest of the constructor body
+    fn run_constructor(
+        &mut self,
+        class_name: &str,
+        constructor: Option<ResolvedMethod>,
+        object_id: ObjectId,
+        ctx: StepContext,
+    ) {
+        let StepContext {
+            line_number,
+            source_line,
+            source,
+            root_node,
+        } = ctx;
+        let body =
+            constructor.and_then(|c| root_node.descendant_for_byte_range(c.body.0, c.body.1));
+        let explicit_call = body
+            .and_then(|b| b.named_child(0))
+            .filter(|first| first.kind() == "explicit_constructor_invocation");
+        let delegates_to_this = explicit_call
+            .and_then(|call| call.child_by_field_name("constructor"))
+            .is_some_and(|keyword| keyword.kind() == "this");
+
+        if delegates_to_this {
+            self.chain_constructor(class_name, explicit_call.as_ref(), object_id, ctx);
+        } else {
+            if let Some(superclass) = self.superclass_of(class_name).map(str::to_string) {
+                self.chain_constructor(&superclass, explicit_call.as_ref(), object_id, ctx);
+            }
+            if !self.is_throwing() {
+                self.run_instance_initializers(class_name, object_id, source, root_node);
+            }
+        }
+
+        if let Some(body) = body
+            && !self.is_throwing()
+        {
+            self.analyze_block(&body, source, root_node);
+            // `return;` ends the constructor
+            self.flow_signal
+                .take_if(|signal| matches!(signal, FlowSignal::Return(_)));
+        }
+
+        self.pop_frame(line_number, source_line);
+        self.current_call_depth -= 1;
+    }
+
+    /// Invoke a constructor of `class_name` on the object under construction,
+    /// for a `super(...)` / `this(...)` call or, when `invocation` is `None`,
+    /// the implicit `super()`. Emits a `MethodCall` step for the chained call.
+    fn chain_constructor(
+        &mut self,
+        class_name: &str,
+        invocation: Option<&Node>,
+        object_id: ObjectId,
+        ctx: StepContext,
+    ) {
+        let StepContext {
+            line_number,
+            source_line,
+            source,
+            root_node,
+        } = ctx;
+        let (args, arg_types, parameters, keyword, line_number, source_line) = match invocation {
+            Some(call) => {
+                let line = call.start_position().row + 1;
+                (
+                    self.evaluate_call_arguments(call, source),
+                    self.argument_types(call, source),
+                    self.extract_constructor_parameters(call, source),
+                    call.child_by_field_name("constructor")
+                        .map(|k| node_text(&k, source).to_string())
+                        .unwrap_or_else(|| "super".to_string()),
+                    line,
+                    self.get_source_line(line),
+                )
+            }
+            None => (
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                "super".to_string(),
+                line_number,
+                source_line.to_string(),
+            ),
+        };
+
+        if builtin_superclass(class_name).is_some() {
+            self.init_builtin_throwable(object_id, &args);
+            return;
+        }
+        let constructor = self.resolve_method_body(class_name, "<init>", &arg_types);
+        let signature = constructor.as_ref().map(|c| c.signature.clone());
+        if !self.enter_constructor(class_name, signature.as_deref(), object_id, args) {
+            return;
+        }
+
+        let description = match (invocation, keyword.as_str()) {
+            (None, _) => format!("Implicit super() call: {}.<init>", class_name),
+            (Some(_), "this") => format!("Delegate to constructor: {}.<init>", class_name),
+            (Some(_), _) => format!("Call superclass constructor: {}.<init>", class_name),
+        };
+        self.add_execution_step(
+            line_number,
+            &source_line,
+            ExecutionAction::MethodCall {
+                caller: Some(keyword),
+                method_name: "<init>".to_string(),
+                target_class: class_name.to_string(),
+                parameters,
+                signature,
+            },
+            description,
+        );
+
+        self.run_constructor(
+            class_name,
+            constructor,
+            object_id,
+            StepContext {
+                line_number,
+                source_line: &source_line,
+                source,
+                root_node,
+            },
+        );
+    }
+
+    /// Run the field initializers and instance initializer blocks declared
+    /// in `class_name` itself, in source order, on `object_id`.
+    fn run_instance_initializers(
+        &mut self,
+        class_name: &str,
+        object_id: ObjectId,
+        source: &str,
+        root_node: &Node,
+    ) {
+        let initializers = self
+            .instance_initializers
+            .get(class_name)
+            .cloned()
+            .unwrap_or_default();
+
+        for initializer in initializers {
+            if self.is_throwing() {
+                break;
+            }
+            match initializer {
+                Initializer::Field {
+                    name,
+                    value: (start, end),
+                } => {
+                    let Some(value_node) = root_node.descendant_for_byte_range(start, end) else {
+                        continue;
+                    };
+                    let line_number = value_node.start_position().row + 1;
+                    let source_line = self.get_source_line(line_number);
+                    if !self.check_expression(&value_node, source, root_node) {
+                        break;
+                    }
+                    let display_name = format!("this.{}", name);
+
+                    let old = self.heap.get_field(object_id, class_name, &name).cloned();
+                    let field_type = self
+                        .analysis_result
+                        .class(class_name)
+                        .and_then(|c| c.fields.iter().find(|f| f.name == name))
+                        .map(|f| f.field_type.clone())
+                        .unwrap_or_default();
+                    let value = self.evaluate_allocating(
+                        &value_node,
+                        &display_name,
+                        &field_type,
+                        StepContext {
+                            line_number,
+                            source_line: &source_line,
+                            source,
+                            root_node,
+                        },
+                    );
+                    let value = self.store_string(
+                        value.convert_to(&field_type),
+                        Self::is_constant_expression(&value_node),
+                    );
+                    let new_display = if value.is_known() {
+                        self.describe_value(&value)
+                    } else {
+                        node_text(&value_node, source).to_string()
+                    };
+                    self.heap.set_field(object_id, class_name, &name, value);
+
+                    let runtime_class = self
+                        .heap
+                        .get(object_id)
+                        .map(|obj| obj.class_name.clone())
+                        .unwrap_or_default();
+                    self.add_execution_step(
+                        line_number,
+                        &source_line,
+                        ExecutionAction::FieldMutation {
+                            class_name: runtime_class,
+                            object_id,
+                            field_name: name,
+                            old_value: old.map(|v| self.describe_value(&v)),
+                            new_value: new_display.clone(),
+                        },
+                        format!("Initialize field: {} = {}", display_name, new_display),
+                    );
+                }
+                Initializer::Block((start, end)) => {
+                    if let Some(block) = root_node.descendant_for_byte_range(start, end) {
+                        self.analyze_scoped_block(&block, source, root_node);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Initialize `class_name` on its first active use (a `new`, a static
+    /// method call or a static field access), superclass first: its static
+    /// fields start at their default values, then its static field
+    /// initializers and `static` blocks run in source order in a `<clinit>`
+    /// frame. Classes outside the analyzed source, or already initialized,
+    /// are left alone, and classes without static state get no steps.
+    fn initialize_class(&mut self, class_name: &str, source: &str, root_node: &Node) {
+        let Some(class) = self.analysis_result.class(class_name) else {
+            return;
+        };
+        if self.heap.is_initialized(class_name) {
+            return;
+        }
+        let static_fields: Vec<(String, String)> = class
+            .fields
+            .iter()
+            .filter(|f| f.is_static)
+            .map(|f| (f.name.clone(), f.field_type.clone()))
+            .collect();
+        if let Some(superclass) = self.superclass_of(class_name).map(str::to_string) {
+            self.initialize_class(&superclass, source, root_node);
+        }
+        // Marked before the initializers run, so uses of the class from
+        // within them don't start over
+        if !self.heap.initialize_class(class_name) {
+            return;
+        }
+        for (name, field_type) in &static_fields {
+            self.heap
+                .set_static(class_name, name, Self::default_value(field_type));
+        }
+        let initializers = self
+            .static_initializers
+            .get(class_name)
+            .cloned()
+            .unwrap_or_default();
+        if static_fields.is_empty() && initializers.is_empty() {
+            return;
+        }
+
+        let line_number = Self::find_class_declaration(root_node, source, class_name)
+            .map_or(0, |class| class.start_position().row + 1);
+        let source_line = self.get_source_line(line_number);
+        self.push_frame(class_name, "<clinit>()", None, Vec::new());
+        self.add_execution_step(
+            line_number,
+            &source_line,
+            ExecutionAction::ClassInitialization {
+                class_name: class_name.to_string(),
+            },
+            format!(
+                "Initialize class {}: static fields start at their default values",
+                class_name
+            ),
+        );
+
+        for initializer in initializers {
+            if self.is_throwing() {
+                break;
+            }
+            match initializer {
+                Initializer::Field {
+                    name,
+                    value: (start, end),
+                } => {
+                    let Some(value_node) = root_node.descendant_for_byte_range(start, end) else {
+                        continue;
+                    };
+                    let line_number = value_node.start_position().row + 1;
+                    let source_line = self.get_source_line(line_number);
+                    if !self.check_expression(&value_node, source, root_node) {
+                        break;
+                    }
+                    let field_type = self.field_type(class_name, &name);
+                    let value = self.evaluate_allocating(
+                        &value_node,
+                        &format!("{}.{}", class_name, name),
+                        &field_type,
+                        StepContext {
+                            line_number,
+                            source_line: &source_line,
+                            source,
+                            root_node,
+                        },
+                    );
+                    let value = self.store_string(
+                        value.convert_to(&field_type),
+                        Self::is_constant_expression(&value_node),
+                    );
+                    self.store_static(
+                        (class_name, &name),
+                        value,
+                        node_text(&value_node, source),
+                        true,
+                        line_number,
+                        &source_line,
+                    );
+                }
+                Initializer::Block((start, end)) => {
+                    if let Some(block) = root_node.descendant_for_byte_range(start, end) {
+                        self.analyze_scoped_block(&block, source, root_node);
+                    }
+                }
+            }
+        }
+
+        self.pop_frame(line_number, &source_line);
+    }
+
+    /// Write `value` to the static field `(class_name, field_name)` and
+    /// emit the step, showing `written_source` when the value is unknown.
+    /// `initializing` tells a static initializer from a later assignment.
+    fn store_static(
+        &mut self,
+        (class_name, field_name): (&str, &str),
+        value: Value,
+        written_source: &str,
+        initializing: bool,
+        line_number: usize,
+        source_line: &str,
+    ) {
+        let new_display = if value.is_known() {
+            self.describe_value(&value)
+        } else {
+            written_source.to_string()
+        };
+        let old = self.heap.set_static(class_name, field_name, value);
+        let description = if initializing {
+            format!(
+                "Initialize static field: {}.{} = {}",
+                class_name, field_name, new_display
+            )
+        } else {
+            format!("Mutate static field: {}.{}", class_name, field_name)
+        };
+        self.add_execution_step(
+            line_number,
+            source_line,
+            ExecutionAction::StaticFieldMutation {
+                class_name: class_name.to_string(),
+                field_name: field_name.to_string(),
+                old_value: old.map(|v| self.describe_value(&v)),
+                new_value: new_display,
+            },
+            description,
+        );
+    }
+
+    /// Declaration of the class `class_name` in the tree under `node`.
+    fn find_class_declaration<'a>(
+        node: &Node<'a>,
+        source: &str,
+        class_name: &str,
+    ) -> Option<Node<'a>> {
+        if node.kind() == "class_declaration"
+            && node
+                .child_by_field_name("name")
+                .is_some_and(|name| node_text(&name, source) == class_name)
+        {
+            return Some(*node);
+        }
+        let mut cursor = node.walk();
+        let children: Vec<Node<'a>> = node.named_children(&mut cursor).collect();
+        children
+            .iter()
+            .find_map(|child| Self::find_class_declaration(child, source, class_name))
+    }
+
+    /// Declared type of the field `field_name` of `declaring_class`, or an
+    /// empty string if the static analysis doesn't know it.
+    fn field_type(&self, declaring_class: &str, field_name: &str) -> String {
+        self.analysis_result
+            .class(declaring_class)
+            .and_then(|c| c.fields.iter().find(|f| f.name == field_name))
+            .map(|f| f.field_type.clone())
+            .unwrap_or_default()
+    }
+
+    /// Non-static fields of `class_name` as `(declaring class, name, type)`,
+    /// inherited ones first.
+    fn instance_fields(&self, class_name: &str) -> Vec<(String, String, String)> {
+        self.analysis_result
+            .effective_fields(class_name)
+            .into_iter()
+            .filter(|f| !f.field.is_static)
+            .map(|f| {
+                (
+                    f.declaring_class.to_string(),
+                    f.field.name.clone(),
+                    f.field.field_type.clone(),
+                )
+            })
+            .collect()
+    }
+
+    /// Value a field of type `type_name` holds before any initializer runs.
+    fn default_value(type_name: &str) -> Value {
+        match type_name {
+            "byte" | "short" | "char" | "int" | "long" | "float" | "double" => {
+                Value::Int(0).convert_to(type_name)
+            }
+            "boolean" => Value::Bool(false),
+            _ => Value::Null,
+        }
+    }
+
+    /// Push a frame for the overload `signature` of `class_name` with `this`
+    /// bound to `receiver` and each formal parameter bound to a copy of its
+    /// argument.
+    fn push_frame(
+        &mut self,
+        class_name: &str,
+        signature: &str,
+        receiver: Option<ObjectId>,
+        args: Vec<Value>,
+    ) {
+        let method_name = signature.split('(').next().unwrap_or(signature);
+        let mut frame = StackFrame::new(
+            &format!("{}.{}", class_name, method_name),
+            Some(class_name.to_string()),
+            receiver,
+        );
+        for (formal, arg) in self
+            .formal_parameters(class_name, signature)
+            .iter()
+            .zip(args)
+        {
+            let arg = arg.convert_to(&formal.param_type);
+            frame.bind_parameter(&formal.name, &formal.param_type, arg);
+        }
+        self.frames.push(frame);
+    }
+
+    /// Formal parameters of the overload `signature` of `class_name`, as
+    /// declared in the source.
+    fn formal_parameters(&self, class_name: &str, signature: &str) -> Vec<JavaParameter> {
+        self.method_parameters
+            .get(&(class_name.to_string(), signature.to_string()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Class whose code is currently running, if known.
+    fn current_class(&self) -> Option<&String> {
+        self.frames.last().and_then(|f| f.class_name.as_ref())
+    }
+
+    /// Object `this` refers to in the running frame.
+    fn this_object(&self) -> Option<ObjectId> {
+        self.frames.last().and_then(|f| f.this_object)
+    }
+
+    /// Name of the running method, or `"unknown"` outside of any frame.
+    fn current_method_name(&self) -> String {
+        self.frames
+            .last()
+            .map(|f| f.method.clone())
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+
+    /// Declare a local variable in the running frame. A primitive value is
+    /// converted to the declared type, as assignment does.
+    fn declare_local(&mut self, name: &str, type_name: &str, value: Value) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.declare_local(name, type_name, value.convert_to(type_name));
+        }
+    }
+
+    /// Store `value` in an existing variable `name`, or declare it with
+    /// `type_name` if the running frame has no such variable yet. A
+    /// primitive value is converted to the variable's type.
+    fn bind_local(&mut self, name: &str, type_name: &str, value: Value) {
+        let Some(frame) = self.frames.last_mut() else {
+            return;
+        };
+        match frame.lookup_mut(name) {
+            Some(variable) => variable.value = value.convert_to(&variable.type_name),
+            None => frame.declare_local(name, type_name, value.convert_to(type_name)),
+        }
+    }
+
+    fn analyze_expression_statement(
+        &mut self,
+        expr_node: &Node,
+        source: &str,
+        line_number: usize,
+        source_line: &str,
+        root_node: &Node,
+    ) {
+        if !self.check_expression(expr_node, source, root_node) {
+            return;
+        }
+        if let Some(expr) = expr_node.child(0) {
+            if expr.kind() == "method_invocation" {
+                self.analyze_method_invocation(&expr, source, line_number, source_line, root_node);
+            } else if expr.kind() == "assignment_expression" {
+                self.analyze_assignment(&expr, source, line_number, source_line, root_node);
+            } else if expr.kind() == "update_expression" {
+                self.analyze_update(&expr, source, line_number, source_line);
+            }
+        }
+    }
+
+    /// `x++;`, `--x;` and the like as a statement: a local, field or static
+    /// field goes up or down by one, converted back to its type as a
+    /// compound assignment is.
+    fn analyze_update(
+        &mut self,
+        update_node: &Node,
+        source: &str,
+        line_number: usize,
+        source_line: &str,
+    ) {
+        let Some(target) = update_node.named_child(0) else {
+            return;
+        };
+        let operator = if node_text(update_node, source).contains("++") {
+            "+="
+        } else {
+            "-="
+        };
+
+        if let Some((class_name, field_name)) = self.resolve_static_field(&target, source) {
+            let old = self.heap.get_static(&class_name, &field_name).cloned();
+            let new_value = self
+                .combine_assignment(operator, old, Value::Int(1))
+                .convert_to(&self.field_type(&class_name, &field_name));
+            self.store_static(
+                (&class_name, &field_name),
+                new_value,
+                node_text(update_node, source),
+                false,
+                line_number,
+                source_line,
+            );
+        } else if let Some(FieldTarget {
+            object_id,
+            declaring_class,
+            field_name,
+            display_name,
+        }) = self.resolve_field_target(&target, source)
+        {
+            let old = self
+                .heap
+                .get_field(object_id, &declaring_class, &field_name)
+                .cloned();
+            let new_value = self
+                .combine_assignment(operator, old.clone(), Value::Int(1))
+                .convert_to(&self.field_type(&declaring_class, &field_name));
+            let new_display = Self::display_value(&new_value, update_node, source);
+            self.heap
+                .set_field(object_id, &declaring_class, &field_name, new_value);
+            self.add_execution_step(
+                line_number,
+                source_line,
+                ExecutionAction::FieldMutation {
+                    class_name: self.class_of(object_id),
+                    object_id,
+                    field_name,
+                    old_value: old.map(|v| self.describe_value(&v)),
+                    new_value: new_display,
+                },
+                format!("Mutate field: {}", display_name),
+            );
+        } else if target.kind() == "identifier" {
+            let variable_name = node_text(&target, source).to_string();
+            self.execute_update_expression(update_node, source);
+            let value = self.resolve_variable_value(&variable_name);
+            self.add_execution_step(
+                line_number,
+                source_line,
+                ExecutionAction::VariableAssignment {
+                    variable_name: variable_name.clone(),
+                    value_type: "assigned".to_string(),
+                    value: Self::display_value(&value, update_node, source),
+                },
+                format!("Assign value to: {}", variable_name),
+            );
+        }
+    }
+
+    fn analyze_method_invocation(
+        &mut self,
+        method_node: &Node,
+        source: &str,
+        line_number: usize,
+        source_line: &str,
+        root_node: &Node,
+    ) {
+        let CallTarget {
+            method_name,
+            caller,
+            target_class,
+            receiver,
+            resolved,
+        } = self.call_target(method_node, source);
+        let signature = resolved.as_ref().map(|m| m.signature.clone());
+        let mut parameters = Vec::new();
+
+        // Extract parameters
+        if let Some(args_node) = method_node.child_by_field_name("arguments") {
+            parameters = self.extract_method_arguments(&args_node, source);
+        }
+
+        // Record the method call in call graph
+        let caller_method = self.current_method_name();
+        let called_method = format!("{}.{}", target_class, method_name);
+
+        self.call_graph
+            .entry(caller_method)
+            .or_default()
+            .push(called_method.clone());
+
+        // Record object usage
+        if let Some(caller_name) = &caller {
+            self.record_object_usage(caller_name);
+        }
+
+        // Push call stack BEFORE emitting the MethodCall step
+        let body_range = resolved.map(|m| m.body);
+        let has_body = body_range.is_some() && self.current_call_depth < self.max_call_depth;
+
+        if let Some(signature) = &signature
+            && has_body
+        {
+            // Arguments are evaluated in the caller's frame, then copied in
+            let args = self.evaluate_call_arguments(method_node, source);
+            self.initialize_class(&target_class, source, root_node);
+            self.current_call_depth += 1;
+            self.push_frame(&target_class, signature, receiver, args);
+        }
+
+        // System.out isn't stepped into; what it prints goes to the console
+        if let Some(object_node) = method_node.child_by_field_name("object")
+            && node_text(&object_node, source) == "System.out"
+            && matches!(method_name.as_str(), "print" | "println")
+        {
+            self.print_to_console(method_node, method_name == "println", source, root_node);
+        }
+
+        // Modeled collections change before the step, so it shows the result
+        let mut collection_fault = None;
+        let mut collection_result = None;
+        if !has_body && let Some(id) = self.collection_receiver(method_node, source) {
+            match self.call_collection(
+                id,
+                &method_name,
+                method_node,
+                StepContext {
+                    line_number,
+                    source_line,
+                    source,
+                    root_node,
+                },
+            ) {
+                Some(Ok(returned)) => {
+                    collection_result = returned.clone();
+                    self.operand_values.insert(
+                        (self.frames.len(), method_node.id()),
+                        returned.unwrap_or(Value::Unknown),
+                    );
+                }
+                Some(Err(fault)) => collection_fault = Some(fault),
+                None => {}
+            }
+        }
+
+        let mut description = format!(
+            "Call method: {}",
+            signature.as_deref().unwrap_or(&method_name)
+        );
+        if let Some(value) = self.input_reads.get(&method_node.id())
+            && value.is_known()
+        {
+            description.push_str(&format!(" (reads {} from input)", value));
+        }
+        if let Some(value) = &collection_result {
+            description.push_str(&format!(" (returns {})", self.describe_value(value)));
+        }
+        self.add_execution_step(
+            line_number,
+            source_line,
+            ExecutionAction::MethodCall {
+                caller,
+                method_name: method_name.clone(),
+                target_class: target_class.clone(),
+                parameters,
+                signature: signature.clone(),
+            },
+            description,
+        );
+        if let Some(fault) = collection_fault {
+            self.raise(fault, line_number, source_line);
+            return;
+        }
+
+        if let (Some(signature), Some(body)) = (signature, body_range)
+            && has_body
+        {
+            let returned = self.run_method_body(
+                &target_class,
+                &signature,
+                body,
+                StepContext {
+                    line_number,
+                    source_line,
+                    source,
+                    root_node,
+                },
+            );
+            // The caller reads the result back when it evaluates the call
+            self.operand_values
+                .insert((self.frames.len(), method_node.id()), returned);
+        }
+    }
+
+    /// Run the body of the overload `signature` of `class_name`, whose
+    /// frame has been pushed, then pop the frame. Returns the value the
+    /// method returned, converted to its return type.
+    fn run_method_body(
+        &mut self,
+        class_name: &str,
+        signature: &str,
+        (start, end): (usize, usize),
+        ctx: StepContext,
+    ) -> Value {
+        let StepContext {
+            line_number,
+            source_line,
+            source,
+            root_node,
+        } = ctx;
+        let mut returned = Value::Unknown;
+        if let Some(body_node) = root_node.descendant_for_byte_range(start, end) {
+            self.analyze_block(&body_node, source, root_node);
+            if let Some(FlowSignal::Return(value)) = self
+                .flow_signal
+                .take_if(|signal| matches!(signal, FlowSignal::Return(_)))
+            {
+                returned = value;
+            }
+        }
+        self.pop_frame(line_number, source_line);
+        self.current_call_depth -= 1;
+        match self.return_type(class_name, signature) {
+            Some(return_type) => returned.convert_to(&return_type),
+            None => returned,
+        }
+    }
+
+    /// Call the no-argument method `method_name` on `receiver` where the
+    /// compiler generates the call, as for the `iterator()`, `hasNext()`
+    /// and `next()` of an enhanced `for`. `caller` is shown as the receiver.
+    /// Returns `None` if the source declares no such method.
+    fn call_implicitly(
+        &mut self,
+        receiver: ObjectId,
+        caller: String,
+        method_name: &str,
+        ctx: StepContext,
+    ) -> Option<Value> {
+        let StepContext {
+            line_number,
+            source_line,
+            source,
+            root_node,
+        } = ctx;
+        let class_name = self.class_of(receiver);
+        let method = self.resolve_method_body(&class_name, method_name, &[])?;
+        if self.current_call_depth >= self.max_call_depth {
+            return None;
+        }
+        self.call_graph
+            .entry(self.current_method_name())
+            .or_default()
+            .push(format!("{}.{}", method.class_name, method_name));
+        self.record_object_usage(&caller);
+
+        self.current_call_depth += 1;
+        self.push_frame(
+            &method.class_name,
+            &method.signature,
+            Some(receiver),
+            Vec::new(),
+        );
+        self.add_execution_step(
+            line_number,
+            source_line,
+            ExecutionAction::MethodCall {
+                caller: Some(caller),
+                method_name: method_name.to_string(),
+                target_class: method.class_name.clone(),
+                parameters: Vec::new(),
+                signature: Some(method.signature.clone()),
+            },
+            format!("Call method: {}", method.signature),
+        );
+        Some(self.run_method_body(
+            &method.class_name,
+            &method.signature,
+            method.body,
+            StepContext {
+                line_number,
+                source_line,
+                source,
+                root_node,
+            },
+        ))
+    }
+
+    /// Resolve the method `method_node` invokes: the class its lookup
+    /// starts from and the body that runs, if the source declares one.
+    fn call_target(&self, method_node: &Node, source: &str) -> CallTarget {
+        let mut method_name = String::new();
+        let mut caller = None;
+        let mut target_class = "unknown".to_string();
+        let mut receiver = self.this_object();
+
+        // Extract method name
+        if let Some(name_node) = method_node.child_by_field_name("name") {
+            method_name = node_text(&name_node, source).to_string();
+        }
+
+        // Where the method lookup starts: the runtime class of the receiver,
+        // or the superclass of the running code for `super.method()`
+        let object_node = method_node.child_by_field_name("object");
+        if let Some(object_node) = &object_node {
+            let caller_name = node_text(object_node, source).to_string();
+            caller = Some(caller_name.clone());
+
+            if object_node.kind() == "super" {
+                target_class = self
+                    .current_class()
+                    .and_then(|class_name| self.superclass_of(class_name))
+                    .unwrap_or("unknown")
+                    .to_string();
+            } else {
+                // Enhanced object class resolution
+                target_class = self.resolve_object_class_enhanced(&caller_name);
+
+                receiver = match self.evaluate_expression(object_node, source) {
+                    Value::Ref(id) => Some(id),
+                    _ => None,
+                };
+                if let Some(obj) = receiver.and_then(|id| self.heap.get(id)) {
+                    target_class = obj.class_name.clone();
+                }
+            }
+        } else if let Some(class_name) = receiver
+            .and_then(|id| self.heap.get(id))
+            .map(|obj| obj.class_name.clone())
+            .or_else(|| self.current_class().cloned())
+        {
+            // Unqualified call: dispatch on `this`, or stay in the running class
+            target_class = class_name;
+        }
+
+        // The overload is picked from the compile-time type of the receiver
+        // (the enclosing class for an unqualified call), then the runtime
+        // class's override of that exact signature runs. Receivers of
+        // unknown static type fall back to the runtime class.
+        let arg_types = self.argument_types(method_node, source);
+        let static_class = match &object_node {
+            Some(object) if object.kind() == "super" => Some(target_class.clone()),
+            Some(object) => self
+                .static_type_of(object, source)
+                .map(|t| erase_type(&t))
+                .filter(|t| self.analysis_result.class(t).is_some()),
+            None => self.current_class().cloned(),
+        };
+        let is_super = object_node.is_some_and(|object| object.kind() == "super");
+        let resolved = static_class
+            .and_then(|class| self.resolve_method_body(&class, &method_name, &arg_types))
+            .map(|method| {
+                if is_super {
+                    method
+                } else {
+                    self.dispatch(&target_class, method)
+                }
+            })
+            .or_else(|| self.resolve_method_body(&target_class, &method_name, &arg_types));
+
+        // Inherited methods run with the declaring class as their context
+        if let Some(method) = &resolved {
+            target_class = method.class_name.clone();
+            if self
+                .declared_method(&method.class_name, &method.signature)
+                .is_some_and(|m| m.is_static)
+            {
+                receiver = None;
+            }
+        }
+        CallTarget {
+            method_name,
+            caller,
+            target_class,
+            receiver,
+            resolved,
+        }
+    }
+
+    /// The body of `method` that runs on an instance of `runtime_class`: the
+    /// override of the same signature in the closest class from
+    /// `runtime_class` up. Static and private methods aren't overridden, so
+    /// calls to them stay bound to the class the compiler chose.
+    fn dispatch(&self, runtime_class: &str, method: ResolvedMethod) -> ResolvedMethod {
+        if self
+            .declared_method(&method.class_name, &method.signature)
+            .is_some_and(|m| m.is_static || m.visibility == "private")
+        {
+            return method;
+        }
+        let mut current = Some(runtime_class);
+        for _ in 0..=self.analysis_result.classes.len() {
+            let Some(class) = current.filter(|&class| class != method.class_name) else {
+                break;
+            };
+            if let Some(body) = self
+                .method_bodies
+                .get(&(class.to_string(), method.signature.clone()))
+            {
+                return ResolvedMethod {
+                    class_name: class.to_string(),
+                    signature: method.signature,
+                    body: *body,
+                };
+            }
+            current = self.superclass_of(class);
+        }
+        method
+    }
+
+    /// Declaration of the overload `signature` in `class_name`.
+    fn declared_method(&self, class_name: &str, signature: &str) -> Option<&JavaMethod> {
+        self.analysis_result
+            .class(class_name)?
+            .methods
+            .iter()
+            .find(|m| {
+                method_signature(&m.name, m.parameters.iter().map(|p| p.param_type.as_str()))
+                    == signature
+            })
+    }
+
+    /// Declared return type of the overload `signature` of `class_name`.
+    fn return_type(&self, class_name: &str, signature: &str) -> Option<String> {
+        self.declared_method(class_name, signature)
+            .map(|m| m.return_type.clone())
+    }
+
+    /// Append what `System.out.print(x)` (or `println` when `newline` is
+    /// set) writes to the console.
+    fn print_to_console(
+        &mut self,
+        call_node: &Node,
+        newline: bool,
+        source: &str,
+        root_node: &Node,
+    ) {
+        if let Some(arg) = call_node
+            .child_by_field_name("arguments")
+            .and_then(|args| args.named_child(0))
+        {
+            let line_number = call_node.start_position().row + 1;
+            let source_line = self.get_source_line(line_number);
+            let value = self.printable_value(&arg, line_number, &source_line, source, root_node);
+            let text = self.java_string(&value, line_number, &source_line, source, root_node);
+            self.console.push_str(&text);
+        }
+        if newline {
+            self.console.push('\n');
+        }
+    }
+
+    /// Evaluate `node` the way printing sees it: objects concatenated to a
+    /// string are converted with their `toString()`, which the read-only
+    /// [`Self::evaluate_expression`] can't run. Calls to it are traced at
+    /// `line_number`.
+    fn printable_value(
+        &mut self,
+        node: &Node,
+        line_number: usize,
+        source_line: &str,
+        source: &str,
+        root_node: &Node,
+    ) -> Value {
+        let value = self.evaluate_expression(node, source);
+        if value.is_known() {
+            return value;
+        }
+        match node.kind() {
+            "parenthesized_expression" => match node.named_child(0) {
+                Some(inner) => {
+                    self.printable_value(&inner, line_number, source_line, source, root_node)
+                }
+                None => value,
+            },
+            "binary_expression"
+                if node
+                    .child(1)
+                    .is_some_and(|op| node_text(&op, source) == "+") =>
+            {
+                let (Some(l), Some(r)) = (
+                    node.child_by_field_name("left"),
+                    node.child_by_field_name("right"),
+                ) else {
+                    return value;
+                };
+                let left = self.printable_value(&l, line_number, source_line, source, root_node);
+                let left = self.string_operand(left);
+                let right = self.printable_value(&r, line_number, source_line, source, root_node);
+                let right = self.string_operand(right);
+                let concatenates = matches!(left, Value::Str(_)) || matches!(right, Value::Str(_));
+                if concatenates && left.is_known() && right.is_known() {
+                    Value::Str(format!(
+                        "{}{}",
+                        self.java_string(&left, line_number, source_line, source, root_node),
+                        self.java_string(&right, line_number, source_line, source, root_node)
+                    ))
+                } else {
+                    left.add(&right)
+                }
+            }
+            _ => value,
+        }
+    }
+
+    /// Text Java produces for `value` when printing or concatenating it.
+    fn java_string(
+        &mut self,
+        value: &Value,
+        line_number: usize,
+        source_line: &str,
+        source: &str,
+        root_node: &Node,
+    ) -> String {
+        match value {
+            Value::Str(text) => text.clone(),
+            Value::Ref(id) => {
+                self.object_to_string(*id, line_number, source_line, source, root_node)
+            }
+            Value::Unknown => value.to_string(),
+            other => other.to_storage_string(),
+        }
+    }
+
+    /// `toString()` of the object `id`. A user-defined `toString` is called
+    /// on the object like any other method, its steps traced at
+    /// `line_number`; anything else gets `Object.toString`'s `Class@hash`,
+    /// with the id standing in for the hash code.
+    fn object_to_string(
+        &mut self,
+        id: ObjectId,
+        line_number: usize,
+        source_line: &str,
+        source: &str,
+        root_node: &Node,
+    ) -> String {
+        let Some(obj) = self.heap.get(id) else {
+            return Value::Ref(id).to_string();
+        };
+        if let Some(text) = obj.string_value() {
+            return text.to_string();
+        }
+        let class_name = obj.class_name.clone();
+        if obj.is_array() {
+            return format!("{}@{:x}", Self::array_descriptor(&class_name), id);
+        }
+        if let Some(collection) = modeled_collection(&class_name)
+            && let Some(contents) = self.collection_contents(id)
+        {
+            let mut parts = Vec::new();
+            for (i, element) in contents.elements.iter().enumerate() {
+                let text = self.java_string(element, line_number, source_line, source, root_node);
+                parts.push(match contents.values.get(i) {
+                    Some(value) => format!(
+                        "{}={}",
+                        text,
+                        self.java_string(value, line_number, source_line, source, root_node)
+                    ),
+                    None => text,
+                });
+            }
+            return if collection.is_map() {
+                format!("{{{}}}", parts.join(", "))
+            } else {
+                format!("[{}]", parts.join(", "))
+            };
+        }
+
+        let caller = self.describe_value(&Value::Ref(id));
+        if let Some(value) = self.call_implicitly(
+            id,
+            caller,
+            "toString",
+            StepContext {
+                line_number,
+                source_line,
+                source,
+                root_node,
+            },
+        ) {
+            return self.java_string(&value, line_number, source_line, source, root_node);
+        }
+        if self.is_subclass_of(&class_name, "Throwable") {
+            return self.throwable_string(id);
+        }
+        format!("{}@{:x}", class_name, id)
+    }
+
+    /// JVM descriptor Java prints for an array type: `int[]` is `[I`,
+    /// `Dog[][]` is `[[LDog;`.
+    fn array_descriptor(array_type: &str) -> String {
+        let element = array_type.trim_end_matches("[]");
+        let rank = (array_type.len() - element.len()) / 2;
+        let code = match element {
+            "int" => "I".to_string(),
+            "long" => "J".to_string(),
+            "double" => "D".to_string(),
+            "float" => "F".to_string(),
+            "boolean" => "Z".to_string(),
+            "char" => "C".to_string(),
+            "byte" => "B".to_string(),
+            "short" => "S".to_string(),
+            class => format!("L{};", class),
+        };
+        format!("{}{}", "[".repeat(rank), code)
+    }
+
+    /// `throw expr;`: evaluate the exception object and start unwinding.
+    fn analyze_throw_statement(
+        &mut self,
+        throw_node: &Node,
+        source: &str,
+        line_number: usize,
+        source_line: &str,
+        root_node: &Node,
+    ) {
+        if !self.check_expression(throw_node, source, root_node) {
+            return;
+        }
+        let Some(expr) = throw_node.named_child(0) else {
+            return;
+        };
+        let value = self.evaluate_allocating(
+            &expr,
+            "thrown exception",
+            "Throwable",
+            StepContext {
+                line_number,
+                source_line,
+                source,
+                root_node,
+            },
+        );
+        // A constructor that throws replaces the exception being built
+        if self.is_throwing() {
+            return;
+        }
+        match value {
+            Value::Ref(id) => {
+                let description = match self.exception_message(id) {
+                    Some(message) => format!("Throw {}: {}", self.class_of(id), message),
+                    None => format!("Throw {}", self.class_of(id)),
+                };
+                self.throw_exception(id, line_number, source_line, description);
+            }
+            Value::Null => {
+                let fault = self.null_pointer("Cannot throw exception".to_string(), &expr, source);
+                self.raise(fault, line_number, source_line);
+            }
+            _ => {}
+        }
+    }
+
+    /// `try`/`catch`/`finally`. An exception escaping the body goes to the
+    /// first `catch` clause naming its class or a superclass. The `finally`
+    /// block runs whatever happened, after which the pending exception,
+    /// `break` or `continue` resumes unless the block raised its own.
+    fn analyze_try_statement(&mut self, try_node: &Node, source: &str, root_node: &Node) {
+        // Resources are in scope in the try block only
+        self.enter_scope();
+        let mut resources = Vec::new();
+        if let Some(resource_list) = try_node.child_by_field_name("resources") {
+            let mut cursor = resource_list.walk();
+            for resource in resource_list.named_children(&mut cursor) {
+                if self.is_throwing() {
+                    break;
+                }
+                resources.extend(self.declare_resource(&resource, source, root_node));
+            }
+        }
+        if let Some(body) = try_node.child_by_field_name("body") {
+            if !self.is_throwing() {
+                self.analyze_block(&body, source, root_node);
+            }
+            self.close_resources(&resources, &body, source, root_node);
+            self.exit_scope(&body);
+        }
+
+        let mut cursor = try_node.walk();
+        let clauses: Vec<Node> = try_node.named_children(&mut cursor).collect();
+
+        if let Some(FlowSignal::Throw(id)) = self.flow_signal {
+            let exception_class = self.class_of(id);
+            let handler = clauses
+                .iter()
+                .filter(|clause| clause.kind() == "catch_clause")
+                .find_map(|clause| {
+                    let mut cursor = clause.walk();
+                    let parameter = clause
+                        .named_children(&mut cursor)
+                        .find(|child| child.kind() == "catch_formal_parameter")?;
+                    let mut cursor = parameter.walk();
+                    let catch_type = parameter
+                        .named_children(&mut cursor)
+                        .find(|child| child.kind() == "catch_type")?;
+                    let catches = node_text(&catch_type, source).split('|').any(|t| {
+                        let t = t.trim();
+                        self.is_subclass_of(&exception_class, t.rsplit('.').next().unwrap_or(t))
+                    });
+                    catches.then_some((*clause, parameter, catch_type))
+                });
+
+            if let Some((clause, parameter, catch_type)) = handler {
+                let line_number = clause.start_position().row + 1;
+                let source_line = self.get_source_line(line_number);
+                let variable_name = parameter
+                    .child_by_field_name("name")
+                    .map(|name| node_text(&name, source).to_string())
+                    .unwrap_or_default();
+
+                self.flow_signal = None;
+                if let Some(frame) = self.frames.last_mut() {
+                    frame.line = line_number;
+                }
+                self.enter_scope();
+                self.declare_local(
+                    &variable_name,
+                    node_text(&catch_type, source),
+                    Value::Ref(id),
+                );
+                self.add_execution_step(
+                    line_number,
+                    &source_line,
+                    ExecutionAction::ExceptionCaught {
+                        exception_class: exception_class.clone(),
+                        object_id: id,
+                        variable_name: variable_name.clone(),
+                    },
+                    format!("Catch {} as {}", exception_class, variable_name),
+                );
+                if let Some(body) = clause.child_by_field_name("body") {
+                    self.analyze_block(&body, source, root_node);
+                    self.exit_scope(&body);
+                }
+            }
+        }
+
+        if let Some(finally) = clauses.iter().find(|c| c.kind() == "finally_clause") {
+            let pending = self.flow_signal.take();
+            let pending_exception = match pending {
+                Some(FlowSignal::Throw(id)) => Some(self.class_of(id)),
+                _ => None,
+            };
+            let line_number = finally.start_position().row + 1;
+            let source_line = self.get_source_line(line_number);
+            let description = match &pending_exception {
+                Some(class) => format!("Run finally block, then rethrow {}", class),
+                None => "Run finally block".to_string(),
+            };
+            if let Some(frame) = self.frames.last_mut() {
+                frame.line = line_number;
+            }
+            self.add_execution_step(
+                line_number,
+                &source_line,
+                ExecutionAction::FinallyBlock { pending_exception },
+                description,
+            );
+            let suspended = self.suspended_signals.len();
+            self.suspended_signals.extend(pending.clone());
+            if let Some(block) = finally.named_child(0) {
+                self.analyze_scoped_block(&block, source, root_node);
+            }
+            self.suspended_signals.truncate(suspended);
+            if self.flow_signal.is_none() {
+                self.flow_signal = pending;
+            }
+        }
+    }
+
+    /// Declare a try-with-resources resource as a local. Returns the name
+    /// of the variable to close, which for `try (existing)` is an existing
+    /// one.
+    fn declare_resource(
+        &mut self,
+        resource: &Node,
+        source: &str,
+        root_node: &Node,
+    ) -> Option<String> {
+        let (Some(type_node), Some(name), Some(value)) = (
+            resource.child_by_field_name("type"),
+            resource.child_by_field_name("name"),
+            resource.child_by_field_name("value"),
+        ) else {
+            return resource
+                .named_child(0)
+                .filter(|child| child.kind() == "identifier")
+                .map(|child| node_text(&child, source).to_string());
+        };
+        let line_number = resource.start_position().row + 1;
+        let source_line = self.get_source_line(line_number);
+        let type_name = self.extract_type_name(&type_node, source);
+        let name = node_text(&name, source);
+        if value.kind() == "object_creation_expression" {
+            self.execute_object_creation(
+                &value,
+                name,
+                Some(&type_name),
+                StepContext {
+                    line_number,
+                    source_line: &source_line,
+                    source,
+                    root_node,
+                },
+            );
+        } else {
+            let value = self.evaluate_expression(&value, source);
+            self.declare_local(name, &type_name, value);
+        }
+        Some(name.to_string())
+    }
+
+    /// Close the try-with-resources variables `resources` in reverse order
+    /// at the end of the try block `body`, however it ended. Null resources
+    /// are skipped. An exception from `close()` replaces a pending `return`
+    /// or `break` but is suppressed by a pending exception.
+    fn close_resources(
+        &mut self,
+        resources: &[String],
+        body: &Node,
+        source: &str,
+        root_node: &Node,
+    ) {
+        let line_number = body.end_position().row + 1;
+        let source_line = self.get_source_line(line_number);
+        for name in resources.iter().rev() {
+            let Some(Value::Ref(id)) = self
+                .frames
+                .last()
+                .and_then(|frame| frame.lookup(name))
+                .map(|variable| variable.value.clone())
+            else {
+                continue;
+            };
+            let pending = self.flow_signal.take();
+            let suspended = self.suspended_signals.len();
+            self.suspended_signals.extend(pending.clone());
+            if let Some(frame) = self.frames.last_mut() {
+                frame.line = line_number;
+            }
+            self.call_implicitly(
+                id,
+                name.clone(),
+                "close",
+                StepContext {
+                    line_number,
+                    source_line: &source_line,
+                    source,
+                    root_node,
+                },
+            );
+            self.suspended_signals.truncate(suspended);
+            if matches!(pending, Some(FlowSignal::Throw(_))) || !self.is_throwing() {
+                self.flow_signal = pending;
+            }
+        }
+    }
+
+    /// Whether an exception is propagating.
+    fn is_throwing(&self) -> bool {
+        matches!(self.flow_signal, Some(FlowSignal::Throw(_)))
+    }
+
+    /// Throw the exception object `id` from the statement at `line_number`.
+    /// Its stack trace is taken the first time it is thrown; rethrowing
+    /// keeps it, as in Java.
+    fn throw_exception(
         &mut self,
-        assign_node: &Node,
-        source: &str,
+        id: ObjectId,
         line_number: usize,
         source_line: &str,
-        root_node: &Node,
+        description: String,
     ) {
-        let mut variable_name = String::new();
-        let mut value = String::new();
+        let file = self
+            .frames
+            .first()
+            .and_then(|frame| frame.class_name.as_deref())
+            .unwrap_or("Main")
+            .to_string();
+        let trace = self
+            .frames
+            .iter()
+            .rev()
+            .map(|frame| {
+                format!(
+                    "at {}({}.java:{})",
+                    Self::frame_method_name(frame),
+                    file,
+                    frame.line
+                )
+            })
+            .collect();
+        self.stack_traces.entry(id).or_insert(trace);
+
+        let exception_class = self.class_of(id);
+        let message = self.exception_message(id);
+        self.add_execution_step(
+            line_number,
+            source_line,
+            ExecutionAction::ExceptionThrown {
+                exception_class,
+                object_id: id,
+                message,
+            },
+            description,
+        );
+        self.flow_signal = Some(FlowSignal::Throw(id));
+    }
 
-        if let Some(left) = assign_node.child_by_field_name("left") {
-            variable_name = node_text(&left, source).to_string();
+    /// Raise the exception `fault` describes, as the JVM would, from the
+    /// statement at `line_number`.
+    fn raise(&mut self, fault: RuntimeFault, line_number: usize, source_line: &str) {
+        let id = self.heap.allocate(fault.exception_class);
+        let mut description = fault.exception_class.to_string();
+        let message = match &fault.message {
+            Some(text) => {
+                description.push_str(&format!(": {}", text));
+                Value::Ref(self.heap.allocate_string(text))
+            }
+            None => Value::Null,
+        };
+        self.heap.set_field(id, "Throwable", "message", message);
+        if let Some(origin) = fault.origin {
+            description.push_str(&format!(" ({})", origin));
+        }
+        self.throw_exception(id, line_number, source_line, description);
+    }
 
-            // Detect field mutation: this.field = ...
-            if left.kind() == "field_access"
-                && let Some(object) = left.child_by_field_name("object")
-                && node_text(&object, source) == "this"
-                && let Some(field) = left.child_by_field_name("field")
-            {
-                let field_name = node_text(&field, source).to_string();
+    /// Perform the side effects of evaluating `node` (input reads and
+    /// method calls), then raise the exception evaluating it would throw,
+    /// if any. Returns whether execution of the statement goes on.
+    fn check_expression(&mut self, node: &Node, source: &str, root_node: &Node) -> bool {
+        self.forget_operands(node);
+        self.run_operands(node, source, root_node);
+        if self.is_throwing() {
+            return false;
+        }
+        let Some(fault) = self.runtime_fault(node, source) else {
+            return true;
+        };
+        let line_number = node.start_position().row + 1;
+        let source_line = self.get_source_line(line_number);
+        if let Some(frame) = self.frames.last_mut() {
+            frame.line = line_number;
+        }
+        self.raise(fault, line_number, &source_line);
+        false
+    }
 
-                if let Some(right) = assign_node.child_by_field_name("right") {
-                    value = node_text(&right, source).to_string();
+    /// First exception evaluating `node` would raise: a receiver proven
+    /// null, an integer divisor proven zero or an index proven out of
+    /// range. Found without side effects, and only for operands that run:
+    /// the right side of `&&`/`||` and the branches of `?:` are checked
+    /// when the left side or condition selects them.
+    fn runtime_fault(&self, node: &Node, source: &str) -> Option<RuntimeFault> {
+        let check = |child: Option<Node>| child.and_then(|c| self.runtime_fault(&c, source));
+        match node.kind() {
+            "block" | "lambda_expression" | "class_body" | "switch_block" => None,
+            "binary_expression" => {
+                let left = node.child_by_field_name("left")?;
+                let right = node.child_by_field_name("right")?;
+                let op = node_text(&node.child_by_field_name("operator")?, source);
+                if let Some(fault) = check(Some(left)) {
+                    return Some(fault);
+                }
+                if matches!(op, "&&" | "||") {
+                    let runs_right =
+                        self.evaluate_expression(&left, source).as_bool() == Some(op == "&&");
+                    return if runs_right { check(Some(right)) } else { None };
+                }
+                if let Some(fault) = check(Some(right)) {
+                    return Some(fault);
                 }
+                self.division_fault(op, &left, &right, source)
+            }
+            "ternary_expression" => {
+                let condition = node.child_by_field_name("condition")?;
+                if let Some(fault) = check(Some(condition)) {
+                    return Some(fault);
+                }
+                match self.evaluate_expression(&condition, source).as_bool()? {
+                    true => check(node.child_by_field_name("consequence")),
+                    false => check(node.child_by_field_name("alternative")),
+                }
+            }
+            "assignment_expression" => {
+                let left = node.child_by_field_name("left")?;
+                let right = node.child_by_field_name("right")?;
+                let op = node_text(&node.child_by_field_name("operator")?, source);
+                // The target's object and index are evaluated before the value
+                let target_parts = match left.kind() {
+                    "field_access" => vec![left.child_by_field_name("object")],
+                    "array_access" => vec![
+                        left.child_by_field_name("array"),
+                        left.child_by_field_name("index"),
+                    ],
+                    _ => Vec::new(),
+                };
+                if let Some(fault) = target_parts.into_iter().find_map(check) {
+                    return Some(fault);
+                }
+                if let Some(fault) = check(Some(right)) {
+                    return Some(fault);
+                }
+                if left.kind() == "array_access" && op != "=" {
+                    // `a[i] += x` loads the element before storing it
+                    if let Some(fault) = self.fault_at(&left, source) {
+                        return Some(fault);
+                    }
+                }
+                if let Some(fault) = self.store_fault(&left, source) {
+                    return Some(fault);
+                }
+                let op = op.strip_suffix('=').unwrap_or(op);
+                self.division_fault(op, &left, &right, source)
+            }
+            _ => {
+                let mut cursor = node.walk();
+                let children: Vec<Node> = node.named_children(&mut cursor).collect();
+                children
+                    .into_iter()
+                    .find_map(|child| check(Some(child)))
+                    .or_else(|| self.fault_at(node, source))
+            }
+        }
+    }
 
-                // Detect compound assignment (+=, -=, etc.)
-                let is_compound = assign_node
-                    .child(1)
-                    .map(|op| {
-                        let op_text = node_text(&op, source);
-                        op_text != "="
-                    })
-                    .unwrap_or(false);
+    /// `ArithmeticException` for an integer `/` or `%` by zero. Floating
+    /// point division by zero yields infinity or NaN instead.
+    fn division_fault(
+        &self,
+        op: &str,
+        left: &Node,
+        right: &Node,
+        source: &str,
+    ) -> Option<RuntimeFault> {
+        if !matches!(op, "/" | "%") {
+            return None;
+        }
+        match (
+            self.evaluate_expression(left, source),
+            self.evaluate_expression(right, source),
+        ) {
+            (dividend, divisor)
+                if dividend.is_integral()
+                    && divisor.is_integral()
+                    && divisor.as_i64() == Some(0) =>
+            {
+                Some(RuntimeFault {
+                    exception_class: "ArithmeticException",
+                    message: Some("/ by zero".to_string()),
+                    origin: None,
+                })
+            }
+            _ => None,
+        }
+    }
 
-                let new_value = if is_compound {
-                    self.evaluate_compound_assignment(assign_node, source, &field_name, &value)
+    /// Fault raised by `node` itself once its operands are evaluated: a
+    /// method call, field read or array load on null, an array index out
+    /// of bounds, or a String index out of range.
+    fn fault_at(&self, node: &Node, source: &str) -> Option<RuntimeFault> {
+        match node.kind() {
+            "method_invocation" => {
+                let object = node.child_by_field_name("object")?;
+                let name = node_text(&node.child_by_field_name("name")?, source);
+                match self.evaluate_expression(&object, source) {
+                    Value::Null => {
+                        let method = self.invoked_method_name(node, &object, name, source);
+                        Some(self.null_pointer(
+                            format!("Cannot invoke \"{}\"", method),
+                            &object,
+                            source,
+                        ))
+                    }
+                    value => {
+                        let Value::Str(text) = self.string_operand(value) else {
+                            return None;
+                        };
+                        let args = self.evaluate_arguments(node, source);
+                        string_index_fault(&text, name, &args)
+                    }
+                }
+            }
+            "field_access" => {
+                let object = node.child_by_field_name("object")?;
+                let field = node_text(&node.child_by_field_name("field")?, source);
+                if self.evaluate_expression(&object, source) != Value::Null {
+                    return None;
+                }
+                let is_array = self
+                    .static_type_of(&object, source)
+                    .is_some_and(|t| t.ends_with("[]"));
+                let action = if field == "length" && is_array {
+                    "Cannot read the array length".to_string()
                 } else {
-                    self.resolve_value(&value)
+                    format!("Cannot read field \"{}\"", field)
                 };
+                Some(self.null_pointer(action, &object, source))
+            }
+            "array_access" => self.element_fault(node, "load from", source),
+            _ => None,
+        }
+    }
 
-                if let Some(class_name) = self.current_class.clone() {
-                    let old_value = self
-                        .field_values
-                        .get(&(class_name.clone(), field_name.clone()))
-                        .cloned();
+    /// Fault raised when storing into the assignment target `left`.
+    fn store_fault(&self, left: &Node, source: &str) -> Option<RuntimeFault> {
+        match left.kind() {
+            "field_access" => {
+                let object = left.child_by_field_name("object")?;
+                let field = node_text(&left.child_by_field_name("field")?, source);
+                (self.evaluate_expression(&object, source) == Value::Null).then(|| {
+                    self.null_pointer(
+                        format!("Cannot assign field \"{}\"", field),
+                        &object,
+                        source,
+                    )
+                })
+            }
+            "array_access" => self.element_fault(left, "store to", source),
+            _ => None,
+        }
+    }
 
-                    self.field_values
-                        .insert((class_name.clone(), field_name.clone()), new_value.clone());
+    /// Fault raised by the array access `node` loading or storing (`verb`)
+    /// an element: a null array, or an index outside it.
+    fn element_fault(&self, node: &Node, verb: &str, source: &str) -> Option<RuntimeFault> {
+        let array = node.child_by_field_name("array")?;
+        let index = node.child_by_field_name("index")?;
+        match self.evaluate_expression(&array, source) {
+            Value::Null => {
+                let element_type = self
+                    .static_type_of(&array, source)
+                    .and_then(|t| t.strip_suffix("[]").map(str::to_string));
+                let kind = match element_type.as_deref() {
+                    Some(t) if !t.ends_with("[]") && Self::default_value(t) != Value::Null => t,
+                    _ => "object",
+                };
+                Some(self.null_pointer(format!("Cannot {} {} array", verb, kind), &array, source))
+            }
+            Value::Ref(id) => {
+                let length = self.heap.array_length(id)?;
+                let i = self.evaluate_expression(&index, source).as_int()?;
+                let in_bounds = usize::try_from(i).is_ok_and(|i| i < length);
+                (!in_bounds).then(|| RuntimeFault {
+                    exception_class: "ArrayIndexOutOfBoundsException",
+                    message: Some(format!("Index {} out of bounds for length {}", i, length)),
+                    origin: None,
+                })
+            }
+            _ => None,
+        }
+    }
 
-                    self.add_execution_step(
-                        line_number,
-                        source_line,
-                        ExecutionAction::FieldMutation {
-                            class_name,
-                            field_name: field_name.clone(),
-                            old_value,
-                            new_value,
-                        },
-                        format!("Mutate field: this.{}", field_name),
-                    );
-                    return;
+    /// A `NullPointerException` in the JVM's helpful wording: `action`
+    /// (e.g. `Cannot invoke "Dog.bark()"`) failed because `receiver` is null.
+    fn null_pointer(&self, action: String, receiver: &Node, source: &str) -> RuntimeFault {
+        let name = match self.resolve_field_target(receiver, source) {
+            Some(target) if receiver.kind() == "identifier" => target.display_name,
+            _ => node_text(receiver, source).to_string(),
+        };
+        RuntimeFault {
+            exception_class: "NullPointerException",
+            message: Some(format!("{} because \"{}\" is null", action, name)),
+            origin: self.null_origin(receiver, source),
+        }
+    }
+
+    /// `Class.method(ParamTypes)` as a `NullPointerException` names the
+    /// method invoked on a null `object`.
+    fn invoked_method_name(&self, call: &Node, object: &Node, name: &str, source: &str) -> String {
+        let class = self
+            .static_type_of(object, source)
+            .unwrap_or_else(|| "Object".to_string());
+        let arg_types = self.argument_types(call, source);
+        let signature = self
+            .resolve_method_body(&class, name, &arg_types)
+            .map(|m| m.signature)
+            .unwrap_or_else(|| {
+                let types = arg_types
+                    .iter()
+                    .map(|t| t.clone().unwrap_or_else(|| "Object".to_string()));
+                method_signature(name, types.collect::<Vec<_>>().iter().map(String::as_str))
+            });
+        format!("{}.{}", class, signature.replace(',', ", "))
+    }
+
+    /// Where the null in `node` came from, found in the steps so far: the
+    /// assignment that stored it, a parameter the caller passed it for, or
+    /// a field or element still holding its default value.
+    fn null_origin(&self, node: &Node, source: &str) -> Option<String> {
+        if node.kind() == "array_access" {
+            let Value::Ref(array) =
+                self.evaluate_expression(&node.child_by_field_name("array")?, source)
+            else {
+                return None;
+            };
+            let index = self
+                .evaluate_expression(&node.child_by_field_name("index")?, source)
+                .as_int()?;
+            let text = node_text(node, source);
+            let stored = self.steps.iter().rev().find_map(|s| match &s.action {
+                ExecutionAction::ArrayElementAssignment {
+                    object_id,
+                    index: i,
+                    ..
+                } if *object_id == array && usize::try_from(index) == Ok(*i) => Some(s.line_number),
+                _ => None,
+            });
+            return Some(match stored {
+                Some(line) => format!("{} was set to null on line {}", text, line),
+                None => format!(
+                    "{} was never assigned, so it still holds the default value null",
+                    text
+                ),
+            });
+        }
+
+        if let Some(target) = self.resolve_field_target(node, source) {
+            let stored = self.steps.iter().rev().find_map(|s| match &s.action {
+                ExecutionAction::FieldMutation {
+                    object_id,
+                    field_name,
+                    ..
+                } if *object_id == target.object_id && *field_name == target.field_name => {
+                    Some(s.line_number)
                 }
+                _ => None,
+            });
+            return Some(match stored {
+                Some(line) => format!("{} was set to null on line {}", target.display_name, line),
+                None => format!(
+                    "{} was never assigned, so it still holds the default value null",
+                    target.display_name
+                ),
+            });
+        }
+
+        if node.kind() != "identifier" {
+            return None;
+        }
+        let name = node_text(node, source);
+        let frame = self.frames.last()?;
+        // Only steps of the running method's activation, latest first
+        let depth = self.frames.len();
+        let assigned = self
+            .steps
+            .iter()
+            .rev()
+            .take_while(|s| s.frames.len() >= depth)
+            .filter(|s| s.frames.len() == depth)
+            .find_map(|s| match &s.action {
+                ExecutionAction::ReferenceAssignment {
+                    variable_name,
+                    object_id: None,
+                    ..
+                } if variable_name == name => Some(s.line_number),
+                _ => None,
+            });
+        match assigned {
+            Some(line) => Some(format!("{} was set to null on line {}", name, line)),
+            None if frame.parameters.iter().any(|p| p.name == name) => {
+                let caller_line = self.frames.iter().rev().nth(1).map(|f| f.line)?;
+                Some(format!(
+                    "{} is a parameter; the caller passed null on line {}",
+                    name, caller_line
+                ))
             }
+            None => None,
         }
+    }
 
-        if let Some(right) = assign_node.child_by_field_name("right") {
-            if right.kind() == "object_creation_expression" {
-                let params = self.extract_constructor_parameters(&right, source);
-                let creation_class = if let Some(type_node) = right.child_by_field_name("type") {
-                    node_text(&type_node, source).to_string()
-                } else {
-                    "unknown".to_string()
-                };
+    /// Pop the running method's frame, returning to the call at
+    /// `line_number`. When an exception is propagating the frame is unwound
+    /// instead, which gets a step of its own.
+    fn pop_frame(&mut self, line_number: usize, source_line: &str) {
+        let Some(frame) = self.frames.pop() else {
+            return;
+        };
+        // Operands evaluated in the frame go with it
+        let depth = self.frames.len();
+        self.operand_values.retain(|&(d, _), _| d <= depth);
+        if let Some(FlowSignal::Throw(id)) = self.flow_signal {
+            let method_name = Self::frame_method_name(&frame);
+            let exception_class = self.class_of(id);
+            let caller = self
+                .frames
+                .last()
+                .map(Self::frame_method_name)
+                .unwrap_or_default();
+            self.add_execution_step(
+                line_number,
+                source_line,
+                ExecutionAction::FrameUnwound {
+                    method_name: method_name.clone(),
+                    exception_class: exception_class.clone(),
+                },
+                format!(
+                    "Unwind {}: {} propagates to {}",
+                    method_name, exception_class, caller
+                ),
+            );
+        }
+    }
 
-                self.clear_field_values_for_class(&creation_class);
+    /// Final step for an exception that escaped `main`, whose frame ran
+    /// `line_number` last: the stack trace the JVM prints.
+    fn report_uncaught(&mut self, id: ObjectId, line_number: usize) {
+        let mut stack_trace = vec![format!(
+            "Exception in thread \"main\" {}",
+            self.throwable_string(id)
+        )];
+        stack_trace.extend(
+            self.stack_traces
+                .get(&id)
+                .into_iter()
+                .flatten()
+                .map(|line| format!("\t{}", line)),
+        );
+        let source_line = self.get_source_line(line_number);
+        self.add_execution_step(
+            line_number,
+            &source_line,
+            ExecutionAction::UncaughtException {
+                exception_class: self.class_of(id),
+                object_id: id,
+                stack_trace: stack_trace.clone(),
+            },
+            stack_trace.join("\n"),
+        );
+    }
 
-                self.active_objects
-                    .insert(variable_name.clone(), creation_class.clone());
-                self.record_object_creation(&variable_name);
+    /// `Class.method` for a frame, as stack traces name it.
+    fn frame_method_name(frame: &StackFrame) -> String {
+        match &frame.class_name {
+            Some(class) if !frame.method.contains('.') => format!("{}.{}", class, frame.method),
+            _ => frame.method.clone(),
+        }
+    }
 
-                let body_range = self.find_method_body(&creation_class, "<init>");
-                let has_body =
-                    body_range.is_some() && self.current_call_depth < self.max_call_depth;
+    /// Runtime class of the object `id`.
+    fn class_of(&self, id: ObjectId) -> String {
+        self.heap
+            .get(id)
+            .map(|obj| obj.class_name.clone())
+            .unwrap_or_default()
+    }
 
-                if has_body {
-                    self.current_call_depth += 1;
-                    self.call_stack.push(format!("{}.<init>", creation_class));
-                }
+    /// Message a throwable was constructed with, if any.
+    fn exception_message(&self, id: ObjectId) -> Option<String> {
+        match self.heap.get_field(id, "Throwable", "message")? {
+            Value::Ref(message) => self
+                .heap
+                .get(*message)
+                .and_then(HeapObject::string_value)
+                .map(str::to_string),
+            _ => None,
+        }
+    }
 
-                self.add_execution_step(
+    /// `Throwable.toString()`: the qualified class name, then the message.
+    fn throwable_string(&self, id: ObjectId) -> String {
+        let name = qualified_name(&self.class_of(id));
+        match self.exception_message(id) {
+            Some(message) => format!("{}: {}", name, message),
+            None => name,
+        }
+    }
+
+    /// JDK throwable constructors aren't analyzed; one given a message
+    /// stores it the way `Throwable(String)` does.
+    fn init_builtin_throwable(&mut self, object_id: ObjectId, args: &[Value]) {
+        if let Some(message @ Value::Ref(id)) = args.first()
+            && self
+                .heap
+                .get(*id)
+                .and_then(HeapObject::string_value)
+                .is_some()
+        {
+            self.heap
+                .set_field(object_id, "Throwable", "message", message.clone());
+        }
+    }
+
+    fn analyze_assignment(
+        &mut self,
+        assign_node: &Node,
+        source: &str,
+        line_number: usize,
+        source_line: &str,
+        root_node: &Node,
+    ) {
+        let Some(left) = assign_node.child_by_field_name("left") else {
+            return;
+        };
+        let Some(right) = assign_node.child_by_field_name("right") else {
+            return;
+        };
+        let variable_name = node_text(&left, source).to_string();
+
+        // Compound assignments (+=, -=, ...) combine the old value with the RHS
+        let operator = assign_node
+            .child(1)
+            .map(|op| node_text(&op, source).to_string())
+            .unwrap_or_else(|| "=".to_string());
+
+        if left.kind() == "array_access" {
+            self.assign_array_element(
+                assign_node,
+                &left,
+                &right,
+                &operator,
+                StepContext {
                     line_number,
                     source_line,
-                    ExecutionAction::ObjectCreation {
-                        variable_name: variable_name.clone(),
-                        class_name: creation_class.clone(),
-                        constructor_params: params.clone(),
-                    },
-                    format!("Create new {} object: {}", creation_class, variable_name),
-                );
+                    source,
+                    root_node,
+                },
+            );
+            return;
+        }
 
-                if let Some((start, end)) = body_range
-                    && has_body
-                    && let Some(body_node) = root_node.descendant_for_byte_range(start, end)
-                {
-                    let old_class = self.current_class.take();
-                    self.current_class = Some(creation_class.clone());
+        if let Some((class_name, field_name)) = self.resolve_static_field(&left, source) {
+            let old = self.heap.get_static(&class_name, &field_name).cloned();
+            let field_type = self.field_type(&class_name, &field_name);
+            let rhs = self.evaluate_allocating(
+                &right,
+                &format!("{}.{}", class_name, field_name),
+                &field_type,
+                StepContext {
+                    line_number,
+                    source_line,
+                    source,
+                    root_node,
+                },
+            );
+            let new_value = self
+                .combine_assignment(&operator, old, rhs)
+                .convert_to(&field_type);
+            let new_value = self.store_string(
+                new_value,
+                operator == "=" && Self::is_constant_expression(&right),
+            );
+            let display_node = if operator == "=" { right } else { *assign_node };
+            self.store_static(
+                (&class_name, &field_name),
+                new_value,
+                node_text(&display_node, source),
+                false,
+                line_number,
+                source_line,
+            );
+            return;
+        }
 
-                    let old_params = std::mem::take(&mut self.param_values);
-                    let old_locals = std::mem::take(&mut self.local_variables);
-                    if let Some(class) = self
-                        .analysis_result
-                        .classes
-                        .iter()
-                        .find(|c| c.name == creation_class)
-                        && let Some(ctor) = class.constructors.first()
-                    {
-                        for (i, formal) in ctor.parameters.iter().enumerate() {
-                            if let Some(arg_val) = params.get(i) {
-                                self.param_values
-                                    .insert(formal.name.clone(), arg_val.clone());
-                            }
-                        }
-                    }
+        // Detect field mutation: this.field = ..., obj.field = ... or a bare
+        // field name resolved through the implicit `this`
+        if let Some(FieldTarget {
+            object_id,
+            declaring_class,
+            field_name,
+            display_name,
+        }) = self.resolve_field_target(&left, source)
+        {
+            let old = self
+                .heap
+                .get_field(object_id, &declaring_class, &field_name)
+                .cloned();
+            // this.owner = new Person(...): the new object hangs off the field
+            let field_type = self
+                .analysis_result
+                .class(&declaring_class)
+                .and_then(|c| c.fields.iter().find(|f| f.name == field_name))
+                .map(|f| f.field_type.clone())
+                .unwrap_or_default();
+            let rhs = self.evaluate_allocating(
+                &right,
+                &display_name,
+                &field_type,
+                StepContext {
+                    line_number,
+                    source_line,
+                    source,
+                    root_node,
+                },
+            );
+            let new_value = self
+                .combine_assignment(&operator, old.clone(), rhs)
+                .convert_to(&field_type);
+            let new_value = self.store_string(
+                new_value,
+                operator == "=" && Self::is_constant_expression(&right),
+            );
+            let new_display = if new_value.is_known() {
+                self.describe_value(&new_value)
+            } else {
+                let display_node = if operator == "=" { right } else { *assign_node };
+                node_text(&display_node, source).to_string()
+            };
+            self.heap
+                .set_field(object_id, &declaring_class, &field_name, new_value);
+
+            let class_name = self
+                .heap
+                .get(object_id)
+                .map(|obj| obj.class_name.clone())
+                .unwrap_or_default();
+
+            self.add_execution_step(
+                line_number,
+                source_line,
+                ExecutionAction::FieldMutation {
+                    class_name,
+                    object_id,
+                    field_name,
+                    old_value: old.map(|v| self.describe_value(&v)),
+                    new_value: new_display,
+                },
+                format!("Mutate field: {}", display_name),
+            );
+            return;
+        }
+
+        if right.kind() == "object_creation_expression" {
+            self.execute_object_creation(
+                &right,
+                &variable_name,
+                Some(""),
+                StepContext {
+                    line_number,
+                    source_line,
+                    source,
+                    root_node,
+                },
+            );
+            return;
+        }
+
+        if right.kind() == "array_creation_expression" {
+            let declared_type = self
+                .frames
+                .last()
+                .and_then(|f| f.lookup(&variable_name))
+                .map(|v| v.type_name.clone())
+                .unwrap_or_default();
+            self.execute_array_creation(
+                &right,
+                &variable_name,
+                &declared_type,
+                true,
+                StepContext {
+                    line_number,
+                    source_line,
+                    source,
+                    root_node,
+                },
+            );
+            return;
+        }
+
+        // Evaluate the RHS expression and track the local variable
+        let old = Some(self.resolve_variable_value(&variable_name));
+        let rhs = if right.kind() == "switch_expression" {
+            let value =
+                self.execute_switch(&right, source, line_number, source_line, root_node, true);
+            if self.is_throwing() {
+                return;
+            }
+            value.unwrap_or(Value::Unknown)
+        } else {
+            self.evaluate_expression(&right, source)
+        };
+
+        // `b = a;` and `b = null;` rebind a reference variable
+        let declared_type = self
+            .frames
+            .last()
+            .and_then(|f| f.lookup(&variable_name))
+            .map(|v| v.type_name.clone())
+            .unwrap_or_default();
+        if operator == "=" && self.is_reference_value(&rhs, &declared_type) {
+            // Label the variable with its declared type, else the runtime class
+            let class_name = match (&rhs, declared_type.is_empty()) {
+                (Value::Ref(id), true) => self
+                    .heap
+                    .get(*id)
+                    .map(|obj| obj.class_name.clone())
+                    .unwrap_or_default(),
+                _ => declared_type,
+            };
+            self.assign_variable(&variable_name, rhs.clone());
+            self.emit_reference_assignment(
+                &variable_name,
+                &class_name,
+                &rhs,
+                line_number,
+                source_line,
+            );
+            return;
+        }
+
+        // `x += 0.5` on an int converts back to int, as compound assignment casts
+        let evaluated = self
+            .combine_assignment(&operator, old, rhs)
+            .convert_to(&declared_type);
+        if let Value::Str(text) = &evaluated {
+            let constant = operator == "=" && Self::is_constant_expression(&right);
+            self.bind_new_string(&variable_name, "", text, constant, line_number, source_line);
+            return;
+        }
+        let display_node = if operator == "=" { right } else { *assign_node };
+        let display_value = Self::display_value(&evaluated, &display_node, source);
+        self.assign_variable(&variable_name, evaluated);
+
+        self.add_execution_step(
+            line_number,
+            source_line,
+            ExecutionAction::VariableAssignment {
+                variable_name: variable_name.clone(),
+                value_type: "assigned".to_string(),
+                value: display_value,
+            },
+            format!("Assign value to: {}", variable_name),
+        );
+    }
 
-                    self.analyze_block(&body_node, source, root_node);
+    /// Handle `a[i] = ...` and its compound forms. Targets that can't be
+    /// resolved to an element on the heap are traced like an assignment of
+    /// unknown effect.
+    fn assign_array_element(
+        &mut self,
+        assign_node: &Node,
+        left: &Node,
+        right: &Node,
+        operator: &str,
+        ctx: StepContext,
+    ) {
+        let StepContext {
+            source,
+            line_number,
+            source_line,
+            root_node,
+        } = ctx;
+        let target = node_text(left, source).to_string();
+        let element = left
+            .child_by_field_name("array")
+            .zip(left.child_by_field_name("index"))
+            .map(|(array, index)| {
+                (
+                    node_text(&array, source).to_string(),
+                    self.evaluate_expression(&array, source),
+                    self.evaluate_expression(&index, source).as_int(),
+                )
+            });
+        let Some((array_name, Value::Ref(object_id), Some(index))) = element else {
+            let display_node = if operator == "=" {
+                *right
+            } else {
+                *assign_node
+            };
+            self.add_execution_step(
+                line_number,
+                source_line,
+                ExecutionAction::VariableAssignment {
+                    variable_name: target.clone(),
+                    value_type: "assigned".to_string(),
+                    value: node_text(&display_node, source).to_string(),
+                },
+                format!("Assign value to: {}", target),
+            );
+            return;
+        };
+        let index = usize::try_from(index).unwrap_or(usize::MAX);
+        let old = self.heap.get_element(object_id, index).cloned();
+        let element_type = self
+            .heap
+            .get(object_id)
+            .and_then(|obj| obj.class_name.strip_suffix("[]"))
+            .unwrap_or_default()
+            .to_string();
+        let element_name = format!("{}[{}]", array_name, index);
+
+        let rhs = self.evaluate_allocating(
+            right,
+            &element_name,
+            &element_type,
+            StepContext {
+                line_number,
+                source_line,
+                source,
+                root_node,
+            },
+        );
+        let new_value = self
+            .combine_assignment(operator, old.clone(), rhs)
+            .convert_to(&element_type);
+        let new_value = self.store_string(
+            new_value,
+            operator == "=" && Self::is_constant_expression(right),
+        );
+        let new_display = if new_value.is_known() {
+            self.describe_value(&new_value)
+        } else {
+            let display_node = if operator == "=" {
+                *right
+            } else {
+                *assign_node
+            };
+            node_text(&display_node, source).to_string()
+        };
+        self.heap.set_element(object_id, index, new_value);
 
-                    self.local_variables = old_locals;
-                    self.param_values = old_params;
-                    self.current_class = old_class;
-                }
+        self.add_execution_step(
+            line_number,
+            source_line,
+            ExecutionAction::ArrayElementAssignment {
+                array_name,
+                object_id,
+                index,
+                old_value: old.map(|v| self.describe_value(&v)),
+                new_value: new_display,
+            },
+            format!("Set array element: {}", element_name),
+        );
+    }
 
-                if has_body {
-                    self.call_stack.pop();
-                    self.current_call_depth -= 1;
-                }
-                return;
-            }
+    /// Whether `value`, stored in a variable declared as `declared_type`,
+    /// should be traced as a reference rather than a primitive value.
+    fn is_reference_value(&self, value: &Value, declared_type: &str) -> bool {
+        match value {
+            Value::Ref(_) => true,
+            Value::Null => !matches!(
+                declared_type,
+                "int" | "long" | "short" | "byte" | "float" | "double" | "char" | "boolean"
+            ),
+            _ => false,
+        }
+    }
 
-            value = node_text(&right, source).to_string();
+    /// Point `variable_name` at the object referenced by `value` (or at
+    /// `null`) and emit the matching `ReferenceAssignment` step.
+    fn emit_reference_assignment(
+        &mut self,
+        variable_name: &str,
+        class_name: &str,
+        value: &Value,
+        line_number: usize,
+        source_line: &str,
+    ) {
+        let object_id = match value {
+            Value::Ref(id) => Some(*id),
+            _ => None,
+        };
 
-            // Evaluate the RHS expression and track the local variable
-            let evaluated = self.evaluate_expression(&right, source);
-            if evaluated.is_known() {
-                self.local_variables
-                    .insert(variable_name.clone(), evaluated.to_storage_string());
+        let description = match object_id {
+            Some(id) => {
+                self.record_object_usage(variable_name);
+                let runtime_class = self
+                    .heap
+                    .get(id)
+                    .map(|obj| obj.class_name.clone())
+                    .unwrap_or_else(|| class_name.to_string());
+                format!(
+                    "Copy reference: {} now refers to {}@{}",
+                    variable_name, runtime_class, id
+                )
             }
-        }
-
-        let display_value = self
-            .local_variables
-            .get(&variable_name)
-            .cloned()
-            .unwrap_or_else(|| value.clone());
+            None => format!("Set reference {} to null", variable_name),
+        };
 
         self.add_execution_step(
             line_number,
             source_line,
-            ExecutionAction::VariableAssignment {
-                variable_name: variable_name.clone(),
-                value_type: "assigned".to_string(),
-                value: display_value,
+            ExecutionAction::ReferenceAssignment {
+                variable_name: variable_name.to_string(),
+                class_name: class_name.to_string(),
+                object_id,
             },
-            format!("Assign value to: {}", variable_name),
+            description,
         );
     }
 
-    fn evaluate_compound_assignment(
+    /// Resolve the field an assignment target or expression names:
+    /// `this.f`, `obj.f`, or a bare `f` through the implicit `this`.
+    fn resolve_field_target(&self, node: &Node, source: &str) -> Option<FieldTarget> {
+        match node.kind() {
+            "field_access" => {
+                let object = node.child_by_field_name("object")?;
+                let field = node.child_by_field_name("field")?;
+                let Value::Ref(object_id) = self.evaluate_expression(&object, source) else {
+                    return None;
+                };
+                let field_name = node_text(&field, source).to_string();
+                let static_class = self.static_type_of(&object, source);
+                Some(FieldTarget {
+                    object_id,
+                    declaring_class: self.field_owner(
+                        object_id,
+                        static_class.as_deref(),
+                        &field_name,
+                    ),
+                    field_name,
+                    display_name: node_text(node, source).to_string(),
+                })
+            }
+            "identifier" => {
+                let name = node_text(node, source);
+                let (object_id, declaring_class) = self.implicit_field_owner(name)?;
+                Some(FieldTarget {
+                    object_id,
+                    declaring_class,
+                    field_name: name.to_string(),
+                    display_name: format!("this.{}", name),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Static field named by `Dog.count`, `obj.count`, or a bare `count` in
+    /// code that sees it, as `(declaring class, field name)`.
+    fn resolve_static_field(&self, node: &Node, source: &str) -> Option<(String, String)> {
+        match node.kind() {
+            "field_access" => {
+                let object = node.child_by_field_name("object")?;
+                let field = node.child_by_field_name("field")?;
+                let class_name = self
+                    .class_named_by(&object, source)
+                    .or_else(|| self.static_type_of(&object, source))?;
+                let field_name = node_text(&field, source);
+                let owner = self.static_field_owner(&class_name, field_name)?;
+                Some((owner, field_name.to_string()))
+            }
+            "identifier" => {
+                let name = node_text(node, source);
+                if self.frames.last()?.lookup(name).is_some() {
+                    return None;
+                }
+                let owner = self.static_field_owner(self.current_class()?, name)?;
+                Some((owner, name.to_string()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Class declaring the field `field_name` seen from `class_name`, if
+    /// that field is static.
+    fn static_field_owner(&self, class_name: &str, field_name: &str) -> Option<String> {
+        self.analysis_result
+            .superclass_chain(class_name)
+            .into_iter()
+            .find_map(|class| {
+                let field = class.fields.iter().find(|f| f.name == field_name)?;
+                Some((class, field.is_static))
+            })
+            .and_then(|(class, is_static)| is_static.then(|| class.name.clone()))
+    }
+
+    /// Class a type name such as `Dog` in `Dog.count` refers to: an
+    /// identifier that isn't a variable or field but names a class of the
+    /// analyzed source.
+    fn class_named_by(&self, node: &Node, source: &str) -> Option<String> {
+        if node.kind() != "identifier" {
+            return None;
+        }
+        let name = node_text(node, source);
+        let is_variable = self.frames.last().is_some_and(|f| f.lookup(name).is_some())
+            || self.declared_field_type(name).is_some();
+        if is_variable {
+            return None;
+        }
+        self.analysis_result.class(name).map(|c| c.name.clone())
+    }
+
+    /// If `name` is not a local or parameter but an instance field visible
+    /// from the running class, return the object it implicitly belongs to
+    /// (`this`) and the class declaring the field.
+    fn implicit_field_owner(&self, name: &str) -> Option<(ObjectId, String)> {
+        let frame = self.frames.last()?;
+        if frame.lookup(name).is_some() {
+            return None;
+        }
+        let this_id = frame.this_object?;
+        // Fields are inherited, so look up the whole superclass chain of the
+        // class whose code is running
+        let class_name = frame
+            .class_name
+            .clone()
+            .or_else(|| self.heap.get(this_id).map(|obj| obj.class_name.clone()))?;
+        self.analysis_result
+            .superclass_chain(&class_name)
+            .into_iter()
+            .find(|class| class.fields.iter().any(|f| f.name == name && !f.is_static))
+            .map(|class| (this_id, class.name.clone()))
+    }
+
+    /// Class declaring the field `field_name` of object `object_id` when it
+    /// is reached through an expression of static type `static_class`. Falls
+    /// back to the runtime class for fields the static analysis doesn't know.
+    fn field_owner(
         &self,
-        assign_node: &Node,
-        source: &str,
+        object_id: ObjectId,
+        static_class: Option<&str>,
         field_name: &str,
-        rhs_text: &str,
     ) -> String {
-        let fallback = node_text(assign_node, source).to_string();
-
-        let op_text = match assign_node.child(1) {
-            Some(op) => node_text(&op, source).to_string(),
-            None => return fallback,
+        let Some(obj) = self.heap.get(object_id) else {
+            return static_class.unwrap_or_default().to_string();
         };
+        static_class
+            .and_then(|class| {
+                self.analysis_result
+                    .field_declaring_class(class, field_name)
+            })
+            .or_else(|| {
+                self.analysis_result
+                    .field_declaring_class(&obj.class_name, field_name)
+            })
+            .map(str::to_string)
+            .or_else(|| {
+                obj.slot(field_name)
+                    .map(|slot| slot.declaring_class.clone())
+            })
+            .unwrap_or_else(|| obj.class_name.clone())
+    }
 
-        let class_name = match &self.current_class {
-            Some(c) => c.clone(),
-            None => return fallback,
-        };
+    /// Combine the previous value of an assignment target with the RHS
+    /// according to `operator` (`=`, `+=`, `-=`, ...).
+    fn combine_assignment(&self, operator: &str, old: Option<Value>, rhs: Value) -> Value {
+        if operator == "=" {
+            return rhs;
+        }
+        match old {
+            Some(old) => self
+                .string_operand(old)
+                .apply_binary(operator.trim_end_matches('='), &self.string_operand(rhs)),
+            None => Value::Unknown,
+        }
+    }
 
-        let left_val: f64 = match self
-            .field_values
-            .get(&(class_name, field_name.to_string()))
-            .and_then(|v| v.parse().ok())
-        {
-            Some(v) => v,
-            None => return fallback,
-        };
+    /// `value` with a reference to a `String` object replaced by the
+    /// string's contents, for operations that work on the characters
+    /// (`+`, `equals`, ...) rather than on identity.
+    fn string_operand(&self, value: Value) -> Value {
+        match value {
+            Value::Ref(id) => match self.heap.get(id).and_then(HeapObject::string_value) {
+                Some(text) => Value::Str(text.to_string()),
+                None => value,
+            },
+            other => other,
+        }
+    }
 
-        let right_val: f64 = self
-            .param_values
-            .get(rhs_text)
-            .or_else(|| self.local_variables.get(rhs_text))
-            .and_then(|v| v.parse().ok())
-            .or_else(|| rhs_text.parse().ok())
-            .unwrap_or(f64::NAN);
+    /// Give a string computed by an expression a `String` object, as
+    /// storing it in a variable, field or element does in Java. A
+    /// `constant` string (a literal, or a constant expression such as
+    /// `"a" + "b"`) refers to the pooled object; any other gets a new one.
+    /// Other values are returned unchanged.
+    fn store_string(&mut self, value: Value, constant: bool) -> Value {
+        match value {
+            Value::Str(text) if constant => Value::Ref(self.heap.intern(&text)),
+            Value::Str(text) => Value::Ref(self.heap.allocate_string(&text)),
+            other => other,
+        }
+    }
 
-        if right_val.is_nan() {
-            return fallback;
+    /// Point the local `variable_name` at the String object for `text` and
+    /// emit the step: a new object, or for a `constant` the pooled one,
+    /// which is only created the first time the literal is used.
+    fn bind_new_string(
+        &mut self,
+        variable_name: &str,
+        type_name: &str,
+        text: &str,
+        constant: bool,
+        line_number: usize,
+        source_line: &str,
+    ) {
+        if constant && let Some(object_id) = self.heap.interned(text) {
+            self.record_object_usage(variable_name);
+            self.bind_local(variable_name, type_name, Value::Ref(object_id));
+            self.add_execution_step(
+                line_number,
+                source_line,
+                ExecutionAction::ReferenceAssignment {
+                    variable_name: variable_name.to_string(),
+                    class_name: "String".to_string(),
+                    object_id: Some(object_id),
+                },
+                format!(
+                    "Reuse pooled String \"{}\": {} now refers to String@{}",
+                    text, variable_name, object_id
+                ),
+            );
+            return;
         }
 
-        let result = match op_text.as_str() {
-            "+=" => left_val + right_val,
-            "-=" => left_val - right_val,
-            "*=" => left_val * right_val,
-            "/=" => {
-                if right_val == 0.0 {
-                    return fallback;
-                }
-                left_val / right_val
-            }
-            _ => return fallback,
+        let object_id = if constant {
+            self.heap.intern(text)
+        } else {
+            self.heap.allocate_string(text)
+        };
+        self.record_object_creation(variable_name);
+        self.bind_local(variable_name, type_name, Value::Ref(object_id));
+        let description = if constant {
+            format!("Create pooled String \"{}\": {}", text, variable_name)
+        } else {
+            format!("Create new String object: {}", variable_name)
         };
+        self.add_execution_step(
+            line_number,
+            source_line,
+            ExecutionAction::ObjectCreation {
+                variable_name: variable_name.to_string(),
+                class_name: "String".to_string(),
+                object_id,
+                constructor_params: Vec::new(),
+                signature: None,
+            },
+            description,
+        );
+    }
 
-        if result == result.floor() && result.abs() < 1e15 {
-            format!("{:.1}", result)
+    /// Whether `node` is a constant expression built from literals only,
+    /// such as `"Rex"` or `"a" + 1`. String constants are interned.
+    fn is_constant_expression(node: &Node) -> bool {
+        match node.kind() {
+            "string_literal"
+            | "character_literal"
+            | "decimal_integer_literal"
+            | "hex_integer_literal"
+            | "octal_integer_literal"
+            | "binary_integer_literal"
+            | "decimal_floating_point_literal"
+            | "hex_floating_point_literal"
+            | "true"
+            | "false" => true,
+            "parenthesized_expression" | "unary_expression" | "binary_expression" => {
+                let mut cursor = node.walk();
+                node.named_children(&mut cursor)
+                    .all(|child| Self::is_constant_expression(&child))
+            }
+            _ => false,
+        }
+    }
+
+    /// Store `value` in the variable `name`, preferring an existing local,
+    /// then an existing parameter; otherwise a new local is created.
+    fn assign_variable(&mut self, name: &str, value: Value) {
+        self.bind_local(name, "", value);
+    }
+
+    /// Text for a value in a step. References name the class of the object
+    /// they point to (`Person@2`) so linked objects can be told apart;
+    /// strings show their contents.
+    fn describe_value(&self, value: &Value) -> String {
+        match value {
+            Value::Ref(id) => match self.heap.get(*id) {
+                Some(obj) => match obj.string_value() {
+                    Some(text) => format!("\"{}\"", text),
+                    None => format!("{}@{}", obj.class_name, id),
+                },
+                None => value.to_string(),
+            },
+            _ => value.to_string(),
+        }
+    }
+
+    /// Text shown for an evaluated value in a step; falls back to the raw
+    /// source of `node` when the value could not be determined.
+    fn display_value(value: &Value, node: &Node, source: &str) -> String {
+        if value.is_known() {
+            value.to_string()
         } else {
-            result.to_string()
+            node_text(node, source).to_string()
         }
     }
 
@@ -851,8 +4405,12 @@ impl ExecutionAnalyzer {
     ) {
         let mut condition = String::new();
         let mut branch_taken = true; // default: take true branch if we can't evaluate
+        let mut explanation = None;
 
         if let Some(condition_node) = if_node.child_by_field_name("condition") {
+            if !self.check_expression(&condition_node, source, root_node) {
+                return;
+            }
             condition = node_text(&condition_node, source).to_string();
             // Evaluate the condition expression (unwrap parenthesized_expression)
             let eval_node = if condition_node.kind() == "parenthesized_expression" {
@@ -864,8 +4422,13 @@ impl ExecutionAnalyzer {
             if let Some(b) = value.as_bool() {
                 branch_taken = b;
             }
+            explanation = self.explain_comparison(&eval_node, source);
         }
 
+        let mut description = format!("Evaluate condition: {} = {}", condition, branch_taken);
+        if let Some(explanation) = explanation {
+            description.push_str(&format!(" ({})", explanation));
+        }
         self.add_execution_step(
             line_number,
             source_line,
@@ -873,7 +4436,7 @@ impl ExecutionAnalyzer {
                 condition: condition.clone(),
                 branch_taken,
             },
-            format!("Evaluate condition: {} = {}", condition, branch_taken),
+            description,
         );
 
         if branch_taken {
@@ -885,6 +4448,253 @@ impl ExecutionAnalyzer {
         }
     }
 
+    /// Run the `switch` `switch_node`: the selector picks the first case
+    /// whose label matches, else `default`. Classic `case x:` groups fall
+    /// through into the groups after them until a `break`, while `case x ->`
+    /// rules run only their own body. Returns the value a switch expression
+    /// (`yields`) produces, `None` if no case ran or it produced none.
+    fn execute_switch(
+        &mut self,
+        switch_node: &Node,
+        source: &str,
+        line_number: usize,
+        source_line: &str,
+        root_node: &Node,
+        yields: bool,
+    ) -> Option<Value> {
+        let condition = switch_node.child_by_field_name("condition")?;
+        let body = switch_node.child_by_field_name("body")?;
+        let selector_node = if condition.kind() == "parenthesized_expression" {
+            condition.named_child(0).unwrap_or(condition)
+        } else {
+            condition
+        };
+        let selector = node_text(&selector_node, source).to_string();
+        let value = self.evaluate_expression(&selector_node, source);
+        let (cases, matched) = self.select_switch_case(&body, &value, source);
+
+        let shown = self.describe_value(&value);
+        let matched_label = matched.map(|(_, label)| node_text(&label, source).to_string());
+        let description = match &matched_label {
+            Some(label) => format!("Switch on {} = {}: take {}", selector, shown, label),
+            None if value.is_known() => {
+                format!("Switch on {} = {}: no case matches", selector, shown)
+            }
+            None => format!("Switch on {}: value unknown, no case taken", selector),
+        };
+        self.add_execution_step(
+            line_number,
+            source_line,
+            ExecutionAction::SwitchBranch {
+                selector,
+                value: shown,
+                matched_label,
+            },
+            description,
+        );
+
+        let (start, _) = matched?;
+        let mut result = None;
+        if cases[start].kind() == "switch_rule" {
+            let mut cursor = cases[start].walk();
+            let arm = cases[start]
+                .named_children(&mut cursor)
+                .find(|child| child.kind() != "switch_label")?;
+            self.analyze_statement(&arm, source, root_node);
+            // `case 1 -> "one";` is the value of a switch expression
+            if yields
+                && arm.kind() == "expression_statement"
+                && !self.is_throwing()
+                && let Some(expr) = arm.named_child(0)
+            {
+                result = Some(self.yielded_value(&expr, source));
+            }
+        } else {
+            // The statement groups of a switch block share one scope
+            self.enter_scope();
+            'cases: for case in &cases[start..] {
+                let mut cursor = case.walk();
+                for statement in case.named_children(&mut cursor) {
+                    if statement.kind() == "switch_label" {
+                        continue;
+                    }
+                    self.analyze_statement(&statement, source, root_node);
+                    if self.flow_signal.is_some() {
+                        break 'cases;
+                    }
+                }
+            }
+            self.exit_scope(&body);
+        }
+
+        // `break` ends the switch; `continue` and exceptions go on outward
+        match self.flow_signal.take() {
+            Some(FlowSignal::Yield(value)) => Some(value),
+            Some(FlowSignal::Break) => result,
+            signal => {
+                self.flow_signal = signal;
+                result
+            }
+        }
+    }
+
+    /// The cases of the switch block `body` (statement groups or rules) and
+    /// the one `value` selects, with the label that selected it: the first
+    /// case with a label equal to `value`, else the `default` case. Nothing
+    /// is selected when `value` is unknown.
+    fn select_switch_case<'t>(
+        &self,
+        body: &Node<'t>,
+        value: &Value,
+        source: &str,
+    ) -> (Vec<Node<'t>>, Option<(usize, Node<'t>)>) {
+        let mut cursor = body.walk();
+        let cases: Vec<Node<'t>> = body
+            .named_children(&mut cursor)
+            .filter(|case| matches!(case.kind(), "switch_block_statement_group" | "switch_rule"))
+            .collect();
+        if !value.is_known() {
+            return (cases, None);
+        }
+
+        let selector = self.string_operand(value.clone());
+        let mut default = None;
+        for (index, case) in cases.iter().enumerate() {
+            let mut cursor = case.walk();
+            for label in case.named_children(&mut cursor) {
+                if label.kind() != "switch_label" {
+                    continue;
+                }
+                let mut label_cursor = label.walk();
+                let constants: Vec<Node> = label.named_children(&mut label_cursor).collect();
+                if constants.is_empty() {
+                    default.get_or_insert((index, label));
+                } else if constants.iter().any(|constant| {
+                    let constant = self.string_operand(self.evaluate_expression(constant, source));
+                    constant.is_known() && constant == selector
+                }) {
+                    return (cases, Some((index, label)));
+                }
+            }
+        }
+        (cases, default)
+    }
+
+    /// Value of the expression a switch expression yields. A string
+    /// constant is the pooled object, as it would be anywhere else.
+    fn yielded_value(&mut self, expr: &Node, source: &str) -> Value {
+        let value = self.evaluate_expression(expr, source);
+        if Self::is_constant_expression(expr) {
+            self.store_string(value, true)
+        } else {
+            value
+        }
+    }
+
+    /// Record the `ConditionalBranch` step of the `?:` expression
+    /// `ternary`, naming the operand its condition chose.
+    fn trace_ternary(&mut self, ternary: &Node, branch_taken: bool, source: &str) {
+        let (Some(condition), Some(chosen)) = (
+            ternary.child_by_field_name("condition"),
+            ternary.child_by_field_name(if branch_taken {
+                "consequence"
+            } else {
+                "alternative"
+            }),
+        ) else {
+            return;
+        };
+        let condition = node_text(&condition, source).to_string();
+        let line_number = ternary.start_position().row + 1;
+        let source_line = self.get_source_line(line_number);
+        let description = format!(
+            "Evaluate condition: {} = {} (choose {})",
+            condition,
+            branch_taken,
+            node_text(&chosen, source)
+        );
+        self.add_execution_step(
+            line_number,
+            &source_line,
+            ExecutionAction::ConditionalBranch {
+                condition,
+                branch_taken,
+            },
+            description,
+        );
+    }
+
+    /// Why a condition comparing objects came out the way it did: `==`
+    /// compares references while `equals` compares contents. `None` for
+    /// anything else, including `==` on primitives.
+    fn explain_comparison(&self, node: &Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "parenthesized_expression" | "unary_expression" => node
+                .named_child(node.named_child_count().checked_sub(1)?)
+                .and_then(|inner| self.explain_comparison(&inner, source)),
+            "binary_expression" => {
+                let left = node.child_by_field_name("left")?;
+                let right = node.child_by_field_name("right")?;
+                let op = node_text(&node.child(1)?, source);
+                if !matches!(op, "==" | "!=") {
+                    return None;
+                }
+                let left_value = self.evaluate_expression(&left, source);
+                let right_value = self.evaluate_expression(&right, source);
+                let is_reference =
+                    |v: &Value| matches!(v, Value::Ref(_) | Value::Str(_) | Value::Null);
+                if !is_reference(&left_value) && !is_reference(&right_value) {
+                    return None;
+                }
+                Some(format!(
+                    "{} compares references: {} is {}, {} is {}",
+                    op,
+                    node_text(&left, source),
+                    self.describe_identity(&left, &left_value),
+                    node_text(&right, source),
+                    self.describe_identity(&right, &right_value),
+                ))
+            }
+            "method_invocation" => {
+                let name = node_text(&node.child_by_field_name("name")?, source);
+                if !matches!(name, "equals" | "equalsIgnoreCase") {
+                    return None;
+                }
+                let object = node.child_by_field_name("object")?;
+                let receiver = self.string_operand(self.evaluate_expression(&object, source));
+                let argument = self.evaluate_arguments(node, source).into_iter().next()?;
+                let argument = self.string_operand(argument);
+                if !matches!(receiver, Value::Str(_)) {
+                    return None;
+                }
+                Some(format!(
+                    "{} compares contents: {} and {}",
+                    name, receiver, argument
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Which object an operand of `==` refers to, e.g. `String@2 (pooled)`.
+    fn describe_identity(&self, node: &Node, value: &Value) -> String {
+        match value {
+            Value::Ref(id) => match self.heap.get(*id) {
+                Some(obj) if obj.interned => format!("{}@{} (pooled)", obj.class_name, id),
+                Some(obj) => format!("{}@{}", obj.class_name, id),
+                None => value.to_string(),
+            },
+            Value::Str(text) if Self::is_constant_expression(node) => {
+                match self.heap.interned(text) {
+                    Some(id) => format!("String@{} (pooled)", id),
+                    None => format!("the pooled {}", value),
+                }
+            }
+            Value::Str(_) => "a new String".to_string(),
+            _ => value.to_string(),
+        }
+    }
+
     fn analyze_loop_statement(
         &mut self,
         loop_node: &Node,
@@ -893,11 +4703,12 @@ impl ExecutionAnalyzer {
         source_line: &str,
         root_node: &Node,
     ) {
-        let loop_type = loop_node.kind().to_string();
-
         match loop_node.kind() {
             "for_statement" => {
+                // Variables declared in the init clause live as long as the loop
+                self.enter_scope();
                 self.analyze_for_statement(loop_node, source, line_number, source_line, root_node);
+                self.exit_scope(loop_node);
             }
             "while_statement" => {
                 self.analyze_while_statement(
@@ -909,24 +4720,16 @@ impl ExecutionAnalyzer {
                 );
             }
             "enhanced_for_statement" => {
-                // Enhanced for-each: we can't know the collection size, run body once
-                let mut condition = String::new();
-                if let Some(condition_node) = loop_node.child_by_field_name("value") {
-                    condition = node_text(&condition_node, source).to_string();
-                }
-                self.add_execution_step(
+                // The loop variable lives as long as the loop
+                self.enter_scope();
+                self.analyze_enhanced_for_statement(
+                    loop_node,
+                    source,
                     line_number,
                     source_line,
-                    ExecutionAction::LoopIteration {
-                        loop_type,
-                        condition: condition.clone(),
-                        iteration: 1,
-                    },
-                    format!("Enter for-each loop over: {}", condition),
+                    root_node,
                 );
-                if let Some(body) = loop_node.child_by_field_name("body") {
-                    self.analyze_statement(&body, source, root_node);
-                }
+                self.exit_scope(loop_node);
             }
             _ => {}
         }
@@ -952,6 +4755,11 @@ impl ExecutionAnalyzer {
             }
 
             // Evaluate condition
+            if let Some(n) = condition_node
+                && !self.check_expression(&n, source, root_node)
+            {
+                break;
+            }
             let cond_val = condition_node.and_then(|n| {
                 let inner = if n.kind() == "parenthesized_expression" {
                     n.child(1).unwrap_or(n)
@@ -1002,10 +4810,10 @@ impl ExecutionAnalyzer {
             }
 
             // Check flow signal after body
-            match self.flow_signal.take() {
+            match self.take_loop_signal() {
                 Some(FlowSignal::Break) => break,
                 Some(FlowSignal::Continue) => continue,
-                None => {}
+                _ => {}
             }
         }
     }
@@ -1046,13 +4854,18 @@ impl ExecutionAnalyzer {
             }
 
             // Check flow signal after body
-            match self.flow_signal.take() {
+            match self.take_loop_signal() {
                 Some(FlowSignal::Break) => break,
                 Some(FlowSignal::Continue) => {} // continue to condition check
-                None => {}
+                _ => {}
             }
 
             // Evaluate condition after body execution
+            if let Some(n) = condition_node
+                && !self.check_expression(&n, source, root_node)
+            {
+                break;
+            }
             let cond_val = condition_node.and_then(|n| {
                 let inner = if n.kind() == "parenthesized_expression" {
                     n.child(1).unwrap_or(n)
@@ -1079,18 +4892,18 @@ impl ExecutionAnalyzer {
         root_node: &Node,
     ) {
         // Execute init clause
-        if let Some(init_node) = for_node.child_by_field_name("init") {
-            if init_node.kind() == "local_variable_declaration" {
-                let init_line = init_node.start_position().row + 1;
-                let init_source = self.get_source_line(init_line);
-                self.analyze_variable_declaration(
-                    &init_node,
-                    source,
-                    init_line,
-                    &init_source,
-                    root_node,
-                );
-            }
+        if let Some(init_node) = for_node.child_by_field_name("init")
+            && init_node.kind() == "local_variable_declaration"
+        {
+            let init_line = init_node.start_position().row + 1;
+            let init_source = self.get_source_line(init_line);
+            self.analyze_variable_declaration(
+                &init_node,
+                source,
+                init_line,
+                &init_source,
+                root_node,
+            );
         }
 
         let condition_node = for_node.child_by_field_name("condition");
@@ -1105,6 +4918,11 @@ impl ExecutionAnalyzer {
             }
 
             // Evaluate condition
+            if let Some(n) = condition_node
+                && !self.check_expression(&n, source, root_node)
+            {
+                break;
+            }
             let cond_val =
                 condition_node.and_then(|n| self.evaluate_expression(&n, source).as_bool());
 
@@ -1129,6 +4947,9 @@ impl ExecutionAnalyzer {
                             if let Some(body) = for_node.child_by_field_name("body") {
                                 self.analyze_statement(&body, source, root_node);
                             }
+                            if self.take_loop_signal() == Some(FlowSignal::Break) {
+                                break;
+                            }
                         }
                     } else {
                         // Can't determine iterations; run body once
@@ -1151,32 +4972,225 @@ impl ExecutionAnalyzer {
             }
 
             iteration += 1;
-            self.add_execution_step(
+            self.add_execution_step(
+                line_number,
+                source_line,
+                ExecutionAction::LoopIteration {
+                    loop_type: "for_statement".to_string(),
+                    condition: condition_text.clone(),
+                    iteration,
+                },
+                format!("Loop iteration {}", iteration),
+            );
+
+            if let Some(body) = for_node.child_by_field_name("body") {
+                self.analyze_statement(&body, source, root_node);
+            }
+
+            // Check flow signal
+            match self.take_loop_signal() {
+                Some(FlowSignal::Break) => break,
+                Some(FlowSignal::Continue) => {}
+                _ => {}
+            }
+
+            // Execute update clause (i++, i--, i+=1, etc.)
+            if let Some(update_node) = for_node.child_by_field_name("update") {
+                self.execute_update_expression(&update_node, source);
+            }
+        }
+    }
+
+    /// Run an enhanced `for`: over an array element by element, or over an
+    /// `Iterable` declared in the source through its `iterator()`,
+    /// `hasNext()` and `next()` methods. The loop variable is bound before
+    /// each iteration's step. When what is iterated is unknown, the body
+    /// runs once.
+    fn analyze_enhanced_for_statement(
+        &mut self,
+        loop_node: &Node,
+        source: &str,
+        line_number: usize,
+        source_line: &str,
+        root_node: &Node,
+    ) {
+        let (Some(name_node), Some(value_node)) = (
+            loop_node.child_by_field_name("name"),
+            loop_node.child_by_field_name("value"),
+        ) else {
+            return;
+        };
+        let variable = node_text(&name_node, source).to_string();
+        let type_name = loop_node
+            .child_by_field_name("type")
+            .map(|type_node| self.extract_type_name(&type_node, source))
+            .unwrap_or_default();
+        let iterable = node_text(&value_node, source).to_string();
+        if !self.check_expression(&value_node, source, root_node) {
+            return;
+        }
+
+        let mut iteration = 0;
+        let mut run_iteration = |this: &mut Self, element: Value| {
+            iteration += 1;
+            this.declare_local(&variable, &type_name, element);
+            let bound = this
+                .frames
+                .last()
+                .and_then(|frame| frame.lookup(&variable))
+                .map(|v| this.describe_value(&v.value))
+                .unwrap_or_default();
+            this.add_execution_step(
                 line_number,
                 source_line,
                 ExecutionAction::LoopIteration {
-                    loop_type: "for_statement".to_string(),
-                    condition: condition_text.clone(),
+                    loop_type: "enhanced_for_statement".to_string(),
+                    condition: iterable.clone(),
                     iteration,
                 },
-                format!("Loop iteration {}", iteration),
+                format!("Loop iteration {}: {} = {}", iteration, variable, bound),
             );
-
-            if let Some(body) = for_node.child_by_field_name("body") {
-                self.analyze_statement(&body, source, root_node);
+            if let Some(body) = loop_node.child_by_field_name("body") {
+                this.analyze_statement(&body, source, root_node);
             }
+            this.take_loop_signal() != Some(FlowSignal::Break)
+        };
 
-            // Check flow signal
-            match self.flow_signal.take() {
-                Some(FlowSignal::Break) => break,
-                Some(FlowSignal::Continue) => {}
-                None => {}
+        let value = if Self::allocates(&value_node) {
+            let target_type = self.static_type_of(&value_node, source).unwrap_or_default();
+            self.evaluate_allocating(
+                &value_node,
+                &iterable,
+                &target_type,
+                StepContext {
+                    line_number,
+                    source_line,
+                    source,
+                    root_node,
+                },
+            )
+        } else {
+            self.evaluate_expression(&value_node, source)
+        };
+        match value {
+            Value::Null => {
+                let action = match self.static_type_of(&value_node, source) {
+                    Some(t) if t.ends_with("[]") => "Cannot read the array length".to_string(),
+                    Some(t) => format!("Cannot invoke \"{}.iterator()\"", t),
+                    None => "Cannot iterate".to_string(),
+                };
+                let fault = self.null_pointer(action, &value_node, source);
+                self.raise(fault, line_number, source_line);
+            }
+            Value::Ref(array) if self.heap.get(array).is_some_and(|obj| obj.is_array()) => {
+                // The length is read once; each element when its turn comes
+                let length = self.heap.array_length(array).unwrap_or(0);
+                self.hidden_roots.push(array);
+                for index in 0..length.min(MAX_LOOP_ITERATIONS) {
+                    let element = self
+                        .heap
+                        .get_element(array, index)
+                        .cloned()
+                        .unwrap_or(Value::Unknown);
+                    if !run_iteration(self, element) {
+                        break;
+                    }
+                }
+                self.hidden_roots.pop();
+            }
+            Value::Ref(collection)
+                if self.collection_of(collection).is_some_and(|c| !c.is_map()) =>
+            {
+                // The iterator walks the live contents, as the JDK's does
+                self.hidden_roots.push(collection);
+                for index in 0..MAX_LOOP_ITERATIONS {
+                    let Some(element) = self
+                        .collection_contents(collection)
+                        .and_then(|contents| contents.elements.get(index).cloned())
+                    else {
+                        break;
+                    };
+                    if !run_iteration(self, element) {
+                        break;
+                    }
+                }
+                self.hidden_roots.pop();
             }
+            Value::Ref(collection)
+                if let Some(Value::Ref(iterator)) = self.call_implicitly(
+                    collection,
+                    iterable.clone(),
+                    "iterator",
+                    StepContext {
+                        line_number,
+                        source_line,
+                        source,
+                        root_node,
+                    },
+                ) =>
+            {
+                let caller = self.describe_value(&Value::Ref(iterator));
+                let call = |this: &mut Self, method: &str| {
+                    let result = this.call_implicitly(
+                        iterator,
+                        caller.clone(),
+                        method,
+                        StepContext {
+                            line_number,
+                            source_line,
+                            source,
+                            root_node,
+                        },
+                    );
+                    result.filter(|_| !this.is_throwing())
+                };
+                self.hidden_roots.push(iterator);
+                for _ in 0..MAX_LOOP_ITERATIONS {
+                    if call(self, "hasNext") != Some(Value::Bool(true)) {
+                        break;
+                    }
+                    let Some(element) = call(self, "next") else {
+                        break;
+                    };
+                    if !run_iteration(self, element) {
+                        break;
+                    }
+                }
+                self.hidden_roots.pop();
+            }
+            _ if self.is_throwing() => {}
+            _ => {
+                // Nothing to step through: run the body once
+                self.add_execution_step(
+                    line_number,
+                    source_line,
+                    ExecutionAction::LoopIteration {
+                        loop_type: "enhanced_for_statement".to_string(),
+                        condition: iterable.clone(),
+                        iteration: 1,
+                    },
+                    format!("Enter for-each loop over: {}", iterable),
+                );
+                if let Some(body) = loop_node.child_by_field_name("body") {
+                    self.analyze_statement(&body, source, root_node);
+                }
+                self.take_loop_signal();
+            }
+        }
+    }
 
-            // Execute update clause (i++, i--, i+=1, etc.)
-            if let Some(update_node) = for_node.child_by_field_name("update") {
-                self.execute_update_expression(&update_node, source);
+    /// Take the `break`/`continue` a loop body left behind. A `return`, a
+    /// propagating exception, or a `yield` out of an enclosing switch
+    /// expression stays pending and ends the loop like a `break`.
+    fn take_loop_signal(&mut self) -> Option<FlowSignal> {
+        match self.flow_signal.take() {
+            Some(
+                signal @ (FlowSignal::Return(_) | FlowSignal::Throw(_) | FlowSignal::Yield(_)),
+            ) => {
+                self.flow_signal = Some(signal);
+                Some(FlowSignal::Break)
             }
+            signal => signal,
         }
     }
 
@@ -1187,9 +5201,9 @@ impl ExecutionAnalyzer {
         let init_text = node_text(&init_node, source);
         let cond_text = node_text(&condition_node, source);
 
-        let init_val = self.extract_for_init_value(&init_text)?;
+        let init_val = self.extract_for_init_value(init_text)?;
 
-        let (cmp_op, bound_text) = self.extract_for_condition(&cond_text)?;
+        let (cmp_op, bound_text) = self.extract_for_condition(cond_text)?;
         let bound_val = self.resolve_numeric(&bound_text)?;
 
         let iterations = match cmp_op.as_str() {
@@ -1252,13 +5266,7 @@ impl ExecutionAnalyzer {
         if let Ok(v) = trimmed.parse::<f64>() {
             return Some(v);
         }
-        if let Some(v) = self.param_values.get(trimmed) {
-            return v.parse().ok();
-        }
-        if let Some(v) = self.local_variables.get(trimmed) {
-            return v.parse().ok();
-        }
-        None
+        self.resolve_variable_value(trimmed).as_f64()
     }
 
     fn analyze_return_statement(
@@ -1267,46 +5275,72 @@ impl ExecutionAnalyzer {
         source: &str,
         line_number: usize,
         source_line: &str,
-        _root_node: &Node,
+        root_node: &Node,
     ) {
-        let mut return_value = None;
+        if !self.check_expression(return_node, source, root_node) {
+            return;
+        }
+        let returned = match return_node.named_child(0) {
+            Some(value_node) if Self::allocates(&value_node) => {
+                let value_type = self.static_type_of(&value_node, source).unwrap_or_default();
+                self.evaluate_allocating(
+                    &value_node,
+                    "return value",
+                    &value_type,
+                    StepContext {
+                        line_number,
+                        source_line,
+                        source,
+                        root_node,
+                    },
+                )
+            }
+            Some(value_node) => {
+                let value = self.evaluate_expression(&value_node, source);
+                self.store_string(value, Self::is_constant_expression(&value_node))
+            }
+            None => Value::Unknown,
+        };
 
-        if let Some(value_node) = return_node.child(1) {
-            return_value = Some(node_text(&value_node, source).to_string());
+        let return_value = return_node.named_child(0).map(|value_node| match returned {
+            Value::Unknown => node_text(&value_node, source).to_string(),
+            _ => self.describe_value(&returned),
+        });
 
+        if let Some(value_node) = return_node.child(1) {
             // Detect field access: return this.field
-            if value_node.kind() == "field_access"
-                && let Some(object) = value_node.child_by_field_name("object")
-                && node_text(&object, source) == "this"
-                && let Some(field) = value_node.child_by_field_name("field")
+            if let Some(FieldTarget {
+                object_id,
+                declaring_class,
+                field_name,
+                display_name,
+            }) = self.resolve_field_target(&value_node, source)
             {
-                let field_name = node_text(&field, source).to_string();
-
-                if let Some(class_name) = self.current_class.clone() {
-                    let field_value = self
-                        .field_values
-                        .get(&(class_name.clone(), field_name.clone()))
-                        .cloned();
+                let field_value = self
+                    .heap
+                    .get_field(object_id, &declaring_class, &field_name)
+                    .map(|v| self.describe_value(v));
+                let class_name = self
+                    .heap
+                    .get(object_id)
+                    .map(|obj| obj.class_name.clone())
+                    .unwrap_or_default();
 
-                    self.add_execution_step(
-                        line_number,
-                        source_line,
-                        ExecutionAction::FieldAccess {
-                            class_name,
-                            field_name: field_name.clone(),
-                            value: field_value,
-                        },
-                        format!("Access field: this.{}", field_name),
-                    );
-                }
+                self.add_execution_step(
+                    line_number,
+                    source_line,
+                    ExecutionAction::FieldAccess {
+                        class_name,
+                        object_id,
+                        field_name,
+                        value: field_value,
+                    },
+                    format!("Access field: {}", display_name),
+                );
             }
         }
 
-        let method_name = self
-            .call_stack
-            .last()
-            .unwrap_or(&"unknown".to_string())
-            .clone();
+        let method_name = self.current_method_name();
 
         self.add_execution_step(
             line_number,
@@ -1315,8 +5349,15 @@ impl ExecutionAnalyzer {
                 method_name,
                 return_value,
             },
-            "Return from method".to_string(),
+            match returned {
+                Value::Unknown => "Return from method".to_string(),
+                _ => format!(
+                    "Return from method with value {}",
+                    self.describe_value(&returned)
+                ),
+            },
         );
+        self.flow_signal = Some(FlowSignal::Return(returned));
     }
 
     fn add_execution_step(
@@ -1333,14 +5374,31 @@ impl ExecutionAnalyzer {
             line_number,
             source_line: source_line.to_string(),
             action,
-            call_stack: self.call_stack.clone(),
-            active_objects: self.active_objects.keys().cloned().collect(),
+            call_stack: self.frames.iter().map(|f| f.method.clone()).collect(),
+            active_objects: self.live_reference_names(),
             description,
+            heap: self.heap.snapshot(),
+            statics: self.heap.statics_snapshot(),
+            frames: self.frames.clone(),
+            console: self.console.clone(),
         };
 
         self.steps.push(step);
     }
 
+    /// Names of the variables on the stack that currently reference an object.
+    fn live_reference_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for frame in &self.frames {
+            for variable in frame.parameters.iter().chain(&frame.locals) {
+                if matches!(variable.value, Value::Ref(_)) && !names.contains(&variable.name) {
+                    names.push(variable.name.clone());
+                }
+            }
+        }
+        names
+    }
+
     fn extract_type_name(&self, type_node: &Node, source: &str) -> String {
         node_text(type_node, source).to_string()
     }
@@ -1368,29 +5426,32 @@ impl ExecutionAnalyzer {
         arguments
     }
 
-    fn clear_field_values_for_class(&mut self, class_name: &str) {
-        self.field_values.retain(|(cls, _), _| cls != class_name);
+    /// Evaluate the arguments of a call that is about to run, giving string
+    /// arguments their `String` objects.
+    fn evaluate_call_arguments(&mut self, call_node: &Node, source: &str) -> Vec<Value> {
+        let Some(args_node) = call_node.child_by_field_name("arguments") else {
+            return Vec::new();
+        };
+        let mut cursor = args_node.walk();
+        let arg_nodes: Vec<Node> = args_node.named_children(&mut cursor).collect();
+        arg_nodes
+            .iter()
+            .map(|arg| {
+                let value = self.evaluate_expression(arg, source);
+                self.store_string(value, Self::is_constant_expression(arg))
+            })
+            .collect()
     }
 
-    fn resolve_value(&self, text: &str) -> String {
-        if let Some(v) = self.local_variables.get(text) {
-            return v.clone();
-        }
-        if let Some(v) = self.param_values.get(text) {
-            return v.clone();
-        }
-        if text.starts_with("this.") {
-            let field_name = &text[5..];
-            if let Some(class_name) = &self.current_class {
-                if let Some(v) = self
-                    .field_values
-                    .get(&(class_name.clone(), field_name.to_string()))
-                {
-                    return v.clone();
-                }
-            }
-        }
-        text.to_string()
+    fn evaluate_arguments(&self, call_node: &Node, source: &str) -> Vec<Value> {
+        let Some(args_node) = call_node.child_by_field_name("arguments") else {
+            return Vec::new();
+        };
+        let mut cursor = args_node.walk();
+        args_node
+            .named_children(&mut cursor)
+            .map(|arg| self.evaluate_expression(&arg, source))
+            .collect()
     }
 
     fn record_object_creation(&mut self, object_name: &str) {
@@ -1415,15 +5476,17 @@ impl ExecutionAnalyzer {
     }
 
     fn resolve_object_class_enhanced(&self, object_name: &str) -> String {
-        if object_name == "this" {
-            if let Some(class_name) = &self.current_class {
-                return class_name.clone();
-            }
+        if object_name == "this"
+            && let Some(class_name) = self.current_class()
+        {
+            return class_name.clone();
         }
 
-        // First check active objects (runtime)
-        if let Some(class_name) = self.active_objects.get(object_name) {
-            return class_name.clone();
+        // First check the object the variable refers to at runtime
+        if let Value::Ref(id) = self.resolve_variable_value(object_name)
+            && let Some(obj) = self.heap.get(id)
+        {
+            return obj.class_name.clone();
         }
 
         if self.enhanced_object_tracking {
@@ -1459,39 +5522,112 @@ impl ExecutionAnalyzer {
         map
     }
 
-    fn collect_method_bodies(node: &Node, source: &str, map: &mut MethodBodyMap) {
-        if node.kind() == "class_declaration" {
-            let mut class_name = String::new();
+    /// Collect the field initializers and initializer blocks of every
+    /// class, instance and static ones apart, keyed by class name.
+    fn collect_initializers(
+        node: &Node,
+        source: &str,
+        instance: &mut HashMap<String, Vec<Initializer>>,
+        statics: &mut HashMap<String, Vec<Initializer>>,
+    ) {
+        if node.kind() == "class_declaration"
+            && let Some(name_node) = node.child_by_field_name("name")
+            && let Some(body_node) = node.child_by_field_name("body")
+        {
+            let class_name = node_text(&name_node, source).to_string();
+            let mut instance_initializers = Vec::new();
+            let mut static_initializers = Vec::new();
+
+            let mut cursor = body_node.walk();
+            for member in body_node.named_children(&mut cursor) {
+                match member.kind() {
+                    "field_declaration" => {
+                        let is_static = member
+                            .child(0)
+                            .filter(|m| m.kind() == "modifiers")
+                            .is_some_and(|m| node_text(&m, source).contains("static"));
+                        let initializers = if is_static {
+                            &mut static_initializers
+                        } else {
+                            &mut instance_initializers
+                        };
+                        let mut declarators = member.walk();
+                        for declarator in
+                            member.children_by_field_name("declarator", &mut declarators)
+                        {
+                            if let Some(name) = declarator.child_by_field_name("name")
+                                && let Some(value) = declarator.child_by_field_name("value")
+                            {
+                                initializers.push(Initializer::Field {
+                                    name: node_text(&name, source).to_string(),
+                                    value: (value.start_byte(), value.end_byte()),
+                                });
+                            }
+                        }
+                    }
+                    "block" => {
+                        instance_initializers
+                            .push(Initializer::Block((member.start_byte(), member.end_byte())));
+                    }
+                    "static_initializer" => {
+                        if let Some(block) = member.named_child(0) {
+                            static_initializers
+                                .push(Initializer::Block((block.start_byte(), block.end_byte())));
+                        }
+                    }
+                    _ => {}
+                }
+            }
 
-            // Extract class name from the `name` child
-            if let Some(name_node) = node.child_by_field_name("name") {
-                class_name = node_text(&name_node, source).to_string();
+            instance.insert(class_name.clone(), instance_initializers);
+            statics.insert(class_name, static_initializers);
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            Self::collect_initializers(&child, source, instance, statics);
+        }
+    }
+
+    fn collect_method_bodies(node: &Node, source: &str, map: &mut MethodBodyMap) {
+        Self::for_each_method_declaration(node, source, &mut |class_name, name, declaration| {
+            if let Some(body) = declaration.child_by_field_name("body") {
+                map.insert(
+                    (
+                        class_name.to_string(),
+                        Self::declared_signature(declaration, name, source),
+                    ),
+                    (body.start_byte(), body.end_byte()),
+                );
             }
+        });
+    }
+
+    /// Call `visit` with the class name, method name (`<init>` for
+    /// constructors) and declaration of every method and constructor
+    /// declared in a class or interface under `node`.
+    fn for_each_method_declaration<'a>(
+        node: &Node<'a>,
+        source: &str,
+        visit: &mut impl FnMut(&str, &str, &Node<'a>),
+    ) {
+        // Interfaces contribute their default and static methods
+        if matches!(node.kind(), "class_declaration" | "interface_declaration") {
+            let class_name = node
+                .child_by_field_name("name")
+                .map(|name_node| node_text(&name_node, source))
+                .unwrap_or_default();
 
-            // Find the class_body child and iterate its children
             if let Some(body_node) = node.child_by_field_name("body") {
                 let mut cursor = body_node.walk();
                 for child in body_node.children(&mut cursor) {
                     match child.kind() {
                         "method_declaration" => {
                             if let Some(name_node) = child.child_by_field_name("name") {
-                                let method_name = node_text(&name_node, source).to_string();
-                                if let Some(body) = child.child_by_field_name("body") {
-                                    map.insert(
-                                        (class_name.clone(), method_name),
-                                        (body.start_byte(), body.end_byte()),
-                                    );
-                                }
-                            }
-                        }
-                        "constructor_declaration" => {
-                            if let Some(body) = child.child_by_field_name("body") {
-                                map.insert(
-                                    (class_name.clone(), "<init>".to_string()),
-                                    (body.start_byte(), body.end_byte()),
-                                );
+                                visit(class_name, node_text(&name_node, source), &child);
                             }
                         }
+                        "constructor_declaration" => visit(class_name, "<init>", &child),
                         _ => {}
                     }
                 }
@@ -1501,74 +5637,405 @@ impl ExecutionAnalyzer {
         // Recurse into children to find nested/sibling class declarations
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            Self::collect_method_bodies(&child, source, map);
+            Self::for_each_method_declaration(&child, source, visit);
+        }
+    }
+
+    /// Signature of a method or constructor declaration, built from the
+    /// types in its `formal_parameters`.
+    fn declared_signature(declaration: &Node, name: &str, source: &str) -> String {
+        let mut types = Vec::new();
+        if let Some(params) = declaration.child_by_field_name("parameters") {
+            let mut cursor = params.walk();
+            for param in params.named_children(&mut cursor) {
+                if param.kind() == "formal_parameter"
+                    && let Some(type_node) = param.child_by_field_name("type")
+                {
+                    types.push(node_text(&type_node, source));
+                }
+            }
+        }
+        method_signature(name, types)
+    }
+
+    /// Names and types of the `formal_parameters` of a method or
+    /// constructor declaration.
+    fn declared_parameters(declaration: &Node, source: &str) -> Vec<JavaParameter> {
+        let mut parameters = Vec::new();
+        if let Some(params) = declaration.child_by_field_name("parameters") {
+            let mut cursor = params.walk();
+            for param in params.named_children(&mut cursor) {
+                if param.kind() == "formal_parameter"
+                    && let Some(type_node) = param.child_by_field_name("type")
+                    && let Some(name_node) = param.child_by_field_name("name")
+                {
+                    parameters.push(JavaParameter {
+                        name: node_text(&name_node, source).to_string(),
+                        param_type: node_text(&type_node, source).to_string(),
+                    });
+                }
+            }
         }
+        parameters
     }
 
-    /// Look up the byte range of a method body by class and method name.
-    pub fn find_method_body(&self, class_name: &str, method_name: &str) -> Option<(usize, usize)> {
+    /// Look up the byte range of a method body by class and signature.
+    pub fn find_method_body(&self, class_name: &str, signature: &str) -> Option<(usize, usize)> {
         self.method_bodies
-            .get(&(class_name.to_string(), method_name.to_string()))
+            .get(&(class_name.to_string(), signature.to_string()))
             .copied()
     }
 
+    /// Find the body that runs when `method_name` is invoked on an instance
+    /// of `class_name` with arguments of static types `arg_types`.
+    ///
+    /// Candidates come from the class and its superclasses, then from the
+    /// default methods of the interfaces they implement, an override hiding
+    /// the same signature further along. Among the applicable ones the
+    /// overload needing the cheapest argument conversions wins, the most
+    /// derived class breaking ties. Constructors (`"<init>"`) are not
+    /// inherited, so only `class_name` itself is searched for them.
+    fn resolve_method_body(
+        &self,
+        class_name: &str,
+        method_name: &str,
+        arg_types: &[Option<String>],
+    ) -> Option<ResolvedMethod> {
+        let prefix = format!("{}(", method_name);
+        let mut seen_signatures = Vec::new();
+        let mut best: Option<(u32, ResolvedMethod)> = None;
+
+        let mut search: Vec<String> = Vec::new();
+        let mut current = Some(class_name);
+        // Bounded by the number of classes in case of a cyclic hierarchy
+        for _ in 0..=self.analysis_result.classes.len() {
+            let Some(class) = current else {
+                break;
+            };
+            search.push(class.to_string());
+            if method_name == "<init>" {
+                break;
+            }
+            current = self.superclass_of(class);
+        }
+        if method_name != "<init>" {
+            let mut index = 0;
+            while index < search.len() {
+                let interfaces: Vec<String> = self
+                    .analysis_result
+                    .class(&search[index])
+                    .map(|c| c.implements.iter().map(|i| erase_type(i)).collect())
+                    .unwrap_or_default();
+                for interface in interfaces {
+                    if !search.contains(&interface) {
+                        search.push(interface);
+                    }
+                }
+                index += 1;
+            }
+        }
+
+        for class in &search {
+            let mut candidates: Vec<(&String, &(usize, usize))> = self
+                .method_bodies
+                .iter()
+                .filter(|((owner, signature), _)| owner == class && signature.starts_with(&prefix))
+                .map(|((_, signature), body)| (signature, body))
+                .collect();
+            // Deterministic choice between equally good overloads
+            candidates.sort();
+
+            for (signature, body) in candidates {
+                if seen_signatures.contains(signature) {
+                    continue;
+                }
+                seen_signatures.push(signature.clone());
+                let Some(cost) = self.overload_cost(signature, arg_types) else {
+                    continue;
+                };
+                if best.as_ref().is_none_or(|(best_cost, _)| cost < *best_cost) {
+                    best = Some((
+                        cost,
+                        ResolvedMethod {
+                            class_name: class.to_string(),
+                            signature: signature.clone(),
+                            body: *body,
+                        },
+                    ));
+                }
+            }
+        }
+
+        best.map(|(_, method)| method)
+    }
+
+    /// Total conversion cost of passing `arg_types` to the overload
+    /// `signature`, or `None` if the overload is not applicable.
+    fn overload_cost(&self, signature: &str, arg_types: &[Option<String>]) -> Option<u32> {
+        let param_types = signature_parameter_types(signature);
+        if param_types.len() != arg_types.len() {
+            return None;
+        }
+        param_types
+            .iter()
+            .zip(arg_types)
+            .map(|(param, arg)| self.conversion_cost(arg.as_deref(), param))
+            .sum()
+    }
+
+    /// How far an argument of static type `arg` is from the parameter type
+    /// `param`: 0 for an exact match, higher for widening, subclassing and
+    /// boxing. `None` means the argument cannot be passed. An unknown type
+    /// (or `null`) is accepted anywhere, but never beats a known match.
+    fn conversion_cost(&self, arg: Option<&str>, param: &str) -> Option<u32> {
+        let Some(arg) = arg else {
+            return Some(8);
+        };
+        let arg = erase_type(arg);
+        if arg == param {
+            return Some(0);
+        }
+
+        let widening_rank = |t: &str| NUMERIC_WIDENING.iter().position(|p| *p == t);
+        let boxed = |primitive: &str| {
+            BOXED_TYPES
+                .iter()
+                .find(|(p, _)| *p == primitive)
+                .map(|(_, b)| *b)
+        };
+        let unboxed = |wrapper: &str| {
+            BOXED_TYPES
+                .iter()
+                .find(|(_, b)| *b == wrapper)
+                .map(|(p, _)| *p)
+        };
+
+        // Primitive widening: int -> long -> float -> double, char -> int
+        let from = if arg == "char" {
+            Some(2)
+        } else {
+            widening_rank(&arg)
+        };
+        if let (Some(from), Some(to)) = (from, widening_rank(param)) {
+            return (to >= from).then(|| 1 + (to - from) as u32);
+        }
+
+        // Boxing and unboxing
+        if let Some(wrapper) = boxed(&arg) {
+            return match param {
+                _ if param == wrapper => Some(10),
+                "Number" if arg != "boolean" && arg != "char" => Some(11),
+                "Object" => Some(12),
+                _ => None,
+            };
+        }
+        if let Some(primitive) = unboxed(&arg) {
+            if let (Some(from), Some(to)) = (widening_rank(primitive), widening_rank(param)) {
+                return (to >= from).then(|| 10 + (to - from) as u32);
+            }
+            return match param {
+                _ if param == primitive => Some(10),
+                "Number" if primitive != "boolean" && primitive != "char" => Some(1),
+                "Object" => Some(2),
+                _ => None,
+            };
+        }
+        if boxed(param).is_some() {
+            return None;
+        }
+
+        if let Some(distance) = self.subtype_distance(&arg, param) {
+            return Some(distance);
+        }
+        if param == "Object" {
+            return Some(7);
+        }
+        // Library types the analysis knows nothing about may still be related
+        let is_user_class = |t: &str| self.analysis_result.classes.iter().any(|c| c.name == t);
+        if is_user_class(&arg) || is_user_class(param) {
+            None
+        } else {
+            Some(9)
+        }
+    }
+
+    /// Number of `extends` / `implements` hops from `class_name` up to
+    /// `ancestor`, if `class_name` is a subtype of it.
+    fn subtype_distance(&self, class_name: &str, ancestor: &str) -> Option<u32> {
+        let mut level = vec![class_name.to_string()];
+        for distance in 0..=self.analysis_result.classes.len() as u32 {
+            if level.iter().any(|c| c == ancestor) {
+                return Some(distance);
+            }
+            level = level
+                .iter()
+                .filter_map(|c| self.analysis_result.classes.iter().find(|jc| &jc.name == c))
+                .flat_map(|jc| jc.extends.iter().chain(jc.implements.iter()).cloned())
+                .collect();
+            if level.is_empty() {
+                return None;
+            }
+        }
+        None
+    }
+
+    /// Static types of the arguments of a call or `new` expression.
+    fn argument_types(&self, call_node: &Node, source: &str) -> Vec<Option<String>> {
+        let Some(args_node) = call_node.child_by_field_name("arguments") else {
+            return Vec::new();
+        };
+        let mut cursor = args_node.walk();
+        args_node
+            .named_children(&mut cursor)
+            .map(|arg| self.static_type_of(&arg, source))
+            .collect()
+    }
+
+    /// Compile-time type of `node` as far as it can be told: declared types
+    /// for variables and fields, literal types, and otherwise the type of the
+    /// evaluated value. `None` for `null` or anything unknown.
+    fn static_type_of(&self, node: &Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "identifier" => {
+                let name = node_text(node, source);
+                if let Some(variable) = self.frames.last().and_then(|f| f.lookup(name)) {
+                    if !variable.type_name.is_empty() {
+                        return Some(variable.type_name.clone());
+                    }
+                } else if let Some(field_type) = self.declared_field_type(name) {
+                    return Some(field_type);
+                }
+            }
+            "this" => return self.current_class().cloned(),
+            "null_literal" => return None,
+            "decimal_integer_literal"
+            | "hex_integer_literal"
+            | "octal_integer_literal"
+            | "binary_integer_literal" => {
+                let text = node_text(node, source);
+                let is_long = text.ends_with('l') || text.ends_with('L');
+                return Some(if is_long { "long" } else { "int" }.to_string());
+            }
+            "decimal_floating_point_literal" | "hex_floating_point_literal" => {
+                let text = node_text(node, source);
+                let is_float = text.ends_with('f') || text.ends_with('F');
+                return Some(if is_float { "float" } else { "double" }.to_string());
+            }
+            "character_literal" => return Some("char".to_string()),
+            "string_literal" => return Some("String".to_string()),
+            "true" | "false" => return Some("boolean".to_string()),
+            "object_creation_expression" | "cast_expression" => {
+                return node
+                    .child_by_field_name("type")
+                    .map(|t| node_text(&t, source).to_string());
+            }
+            "parenthesized_expression" => {
+                return node
+                    .named_child(0)
+                    .and_then(|inner| self.static_type_of(&inner, source));
+            }
+            "field_access" => {
+                if let Some(target) = self.resolve_field_target(node, source)
+                    && let Some(field) = self
+                        .analysis_result
+                        .class(&target.declaring_class)
+                        .and_then(|c| c.fields.iter().find(|f| f.name == target.field_name))
+                {
+                    return Some(field.field_type.clone());
+                }
+            }
+            "array_access" => {
+                if let Some(element_type) = node
+                    .child_by_field_name("array")
+                    .and_then(|array| self.static_type_of(&array, source))
+                    .and_then(|t| t.strip_suffix("[]").map(str::to_string))
+                {
+                    return Some(element_type);
+                }
+            }
+            _ => {}
+        }
+
+        match self.evaluate_expression(node, source) {
+            Value::Byte(_) => Some("byte".to_string()),
+            Value::Short(_) => Some("short".to_string()),
+            Value::Char(_) => Some("char".to_string()),
+            Value::Int(_) => Some("int".to_string()),
+            Value::Long(_) => Some("long".to_string()),
+            Value::Float(_) => Some("float".to_string()),
+            Value::Double(_) => Some("double".to_string()),
+            Value::Bool(_) => Some("boolean".to_string()),
+            Value::Str(_) => Some("String".to_string()),
+            Value::Ref(id) => self.heap.get(id).map(|obj| obj.class_name.clone()),
+            Value::Null | Value::Unknown => None,
+        }
+    }
+
+    /// Declared type of the field `name` that a bare identifier resolves to
+    /// through the implicit `this`.
+    fn declared_field_type(&self, name: &str) -> Option<String> {
+        self.analysis_result
+            .superclass_chain(self.current_class()?)
+            .into_iter()
+            .find_map(|class| class.fields.iter().find(|f| f.name == name))
+            .map(|field| field.field_type.clone())
+    }
+
+    /// Superclass named in the `extends` clause of `class_name`, if any.
+    fn superclass_of(&self, class_name: &str) -> Option<&str> {
+        self.analysis_result
+            .class(class_name)
+            .and_then(|c| c.extends.as_deref())
+    }
+
+    /// Whether `class_name` is `ancestor` or extends it, following the
+    /// superclasses declared in the source and then the JDK throwables.
+    fn is_subclass_of(&self, class_name: &str, ancestor: &str) -> bool {
+        let mut seen: Vec<&str> = Vec::new();
+        let mut current = Some(class_name);
+        while let Some(class) = current {
+            if class == ancestor {
+                return true;
+            }
+            if seen.contains(&class) {
+                return false;
+            }
+            seen.push(class);
+            current = self
+                .superclass_of(class)
+                .or_else(|| builtin_superclass(class));
+        }
+        false
+    }
+
     /// Evaluate a tree-sitter expression node to a Value.
     /// This is a read-only operation: it does not mutate variable state.
+    /// Operands [`Self::run_operands`] has evaluated keep the value they had.
     fn evaluate_expression(&self, node: &Node, source: &str) -> Value {
+        if let Some(value) = self.operand_values.get(&(self.frames.len(), node.id())) {
+            return value.clone();
+        }
         match node.kind() {
-            "decimal_integer_literal" => {
-                let text = node_text(node, source);
-                text.parse::<i64>()
-                    .map(Value::Int)
-                    .unwrap_or(Value::Unknown)
-            }
-            "hex_integer_literal" | "octal_integer_literal" | "binary_integer_literal" => {
-                let text = node_text(node, source);
-                // Strip prefix (0x, 0, 0b) and parse
-                let stripped = text
-                    .trim_start_matches("0x")
-                    .trim_start_matches("0X")
-                    .trim_start_matches("0b")
-                    .trim_start_matches("0B");
-                i64::from_str_radix(
-                    stripped,
-                    match node.kind() {
-                        "hex_integer_literal" => 16,
-                        "octal_integer_literal" => 8,
-                        "binary_integer_literal" => 2,
-                        _ => 10,
-                    },
-                )
-                .map(Value::Int)
-                .unwrap_or(Value::Unknown)
+            "decimal_integer_literal"
+            | "hex_integer_literal"
+            | "octal_integer_literal"
+            | "binary_integer_literal" => {
+                integer_literal(node_text(node, source)).unwrap_or(Value::Unknown)
             }
             "decimal_floating_point_literal" | "hex_floating_point_literal" => {
-                let text = node_text(node, source);
-                // Strip trailing f/F/d/D
-                let cleaned = text.trim_end_matches(['f', 'F', 'd', 'D']);
-                cleaned
-                    .parse::<f64>()
-                    .map(Value::Float)
-                    .unwrap_or(Value::Unknown)
+                floating_literal(node_text(node, source)).unwrap_or(Value::Unknown)
             }
             "true" => Value::Bool(true),
             "false" => Value::Bool(false),
             "null_literal" => Value::Null,
+            "this" => self.this_object().map(Value::Ref).unwrap_or(Value::Unknown),
             "string_literal" => {
                 let text = node_text(node, source);
                 // Strip surrounding quotes
                 let inner = text.trim_start_matches('"').trim_end_matches('"');
                 Value::Str(inner.to_string())
             }
-            "character_literal" => {
-                let text = node_text(node, source);
-                let inner = text.trim_start_matches('\'').trim_end_matches('\'');
-                inner
-                    .chars()
-                    .next()
-                    .map(|c| Value::Int(c as i64))
-                    .unwrap_or(Value::Unknown)
-            }
+            "character_literal" => char_literal(node_text(node, source)).unwrap_or(Value::Unknown),
             "identifier" => {
                 let name = node_text(node, source);
                 self.resolve_variable_value(name)
@@ -1592,21 +6059,14 @@ impl ExecutionAnalyzer {
                         let left = self.evaluate_expression(&l, source);
                         let op_text = node_text(&op, source);
                         let right = self.evaluate_expression(&r, source);
-                        match op_text {
-                            "+" => left.add(&right),
-                            "-" => left.sub(&right),
-                            "*" => left.mul(&right),
-                            "/" => left.div(&right),
-                            "%" => left.rem(&right),
-                            "<" => left.lt(&right),
-                            "<=" => left.le(&right),
-                            ">" => left.gt(&right),
-                            ">=" => left.ge(&right),
-                            "==" => left.eq_val(&right),
-                            "!=" => left.ne_val(&right),
-                            "&&" => left.and(&right),
-                            "||" => left.or(&right),
-                            _ => Value::Unknown,
+                        // `==` compares references; everything else sees
+                        // the contents of String objects
+                        if matches!(op_text, "==" | "!=") {
+                            let equal = self.reference_equality((&l, left), (&r, right));
+                            if op_text == "==" { equal } else { equal.not() }
+                        } else {
+                            self.string_operand(left)
+                                .apply_binary(op_text, &self.string_operand(right))
                         }
                     }
                     _ => Value::Unknown,
@@ -1622,6 +6082,7 @@ impl ExecutionAnalyzer {
                     match op_text {
                         "!" => operand.not(),
                         "-" => operand.negate(),
+                        "~" => operand.bit_not(),
                         "+" => operand,
                         _ => Value::Unknown,
                     }
@@ -1630,29 +6091,174 @@ impl ExecutionAnalyzer {
                 }
             }
             "field_access" => {
-                // Handle this.field
+                // `arr.length` is the one field every array has
                 if let Some(object) = node.child_by_field_name("object")
                     && let Some(field) = node.child_by_field_name("field")
+                    && node_text(&field, source) == "length"
+                    && let Value::Ref(id) = self.evaluate_expression(&object, source)
+                    && let Some(length) = self.heap.array_length(id)
                 {
-                    if node_text(&object, source) == "this" {
-                        let field_name = node_text(&field, source).to_string();
-                        if let Some(class_name) = &self.current_class {
-                            if let Some(val) =
-                                self.field_values.get(&(class_name.clone(), field_name))
-                            {
-                                return self.parse_stored_value(val);
-                            }
+                    return Value::Int(length as i32);
+                }
+                if let Some((class_name, field_name)) = self.resolve_static_field(node, source) {
+                    return self
+                        .heap
+                        .get_static(&class_name, &field_name)
+                        .cloned()
+                        .unwrap_or(Value::Unknown);
+                }
+                // `Integer.MAX_VALUE` and the other wrapper bounds
+                if let Some(object) = node.child_by_field_name("object")
+                    && let Some(field) = node.child_by_field_name("field")
+                    && self.class_named_by(&object, source).is_none()
+                    && self.evaluate_expression(&object, source) == Value::Unknown
+                    && let Some(value) = Value::wrapper_constant(
+                        node_text(&object, source),
+                        node_text(&field, source),
+                    )
+                {
+                    return value;
+                }
+                // Handle this.field and obj.field
+                if let Some(target) = self.resolve_field_target(node, source)
+                    && let Some(val) = self.heap.get_field(
+                        target.object_id,
+                        &target.declaring_class,
+                        &target.field_name,
+                    )
+                {
+                    return val.clone();
+                }
+                Value::Unknown
+            }
+            "method_invocation" => {
+                // Calls to the analyzed source have run by now and left
+                // their result; built-in String and Scanner methods are
+                // modeled
+                if let Some(value) = self.input_reads.get(&node.id()) {
+                    return value.clone();
+                }
+                if let Some(object) = node.child_by_field_name("object")
+                    && let Some(name) = node.child_by_field_name("name")
+                    && node_text(&name, source) == "getMessage"
+                    && let Value::Ref(id) = self.evaluate_expression(&object, source)
+                    && let Some(message) = self.heap.get_field(id, "Throwable", "message")
+                {
+                    return message.clone();
+                }
+                if let Some(stdin) = &self.stdin
+                    && let Some(object) = node.child_by_field_name("object")
+                    && let Some(name) = node.child_by_field_name("name")
+                    && self.is_scanner(&object, source)
+                    && let Some(value) = stdin.peek(node_text(&name, source))
+                {
+                    return value;
+                }
+                // Modeled collection methods that leave the collection as it is
+                if let Some(id) = self.collection_receiver(node, source) {
+                    if let Some(collection) = self.collection_of(id)
+                        && let Some(contents) = self.collection_contents(id)
+                        && let Some(name) = node.child_by_field_name("name")
+                    {
+                        let args = self.evaluate_arguments(node, source);
+                        let mut after = contents.clone();
+                        if let Some(Ok(Some(value))) = call_collection_method(
+                            collection,
+                            &mut after,
+                            node_text(&name, source),
+                            &args,
+                            |a, b| self.java_equals(a, b),
+                        ) && after == contents
+                        {
+                            return value;
                         }
                     }
+                    return Value::Unknown;
+                }
+                if let Some(object) = node.child_by_field_name("object")
+                    && let Some(name) = node.child_by_field_name("name")
+                    && let Value::Str(text) =
+                        self.string_operand(self.evaluate_expression(&object, source))
+                {
+                    let args: Vec<Value> = self
+                        .evaluate_arguments(node, source)
+                        .into_iter()
+                        .map(|arg| self.string_operand(arg))
+                        .collect();
+                    return call_string_method(&text, node_text(&name, source), &args)
+                        .unwrap_or(Value::Unknown);
+                }
+                Value::Unknown
+            }
+            "array_access" => {
+                if let Some(array) = node.child_by_field_name("array")
+                    && let Some(index) = node.child_by_field_name("index")
+                    && let Value::Ref(id) = self.evaluate_expression(&array, source)
+                    && let Some(i) = self.evaluate_expression(&index, source).as_int()
+                    && let Ok(i) = usize::try_from(i)
+                    && let Some(value) = self.heap.get_element(id, i)
+                {
+                    return value.clone();
                 }
                 Value::Unknown
             }
+            "cast_expression" => {
+                match (
+                    node.child_by_field_name("type"),
+                    node.child_by_field_name("value"),
+                ) {
+                    (Some(type_node), Some(value)) => self
+                        .evaluate_expression(&value, source)
+                        .convert_to(node_text(&type_node, source)),
+                    _ => Value::Unknown,
+                }
+            }
+            "ternary_expression" => {
+                let branch = match node
+                    .child_by_field_name("condition")
+                    .and_then(|c| self.evaluate_expression(&c, source).as_bool())
+                {
+                    Some(true) => node.child_by_field_name("consequence"),
+                    Some(false) => node.child_by_field_name("alternative"),
+                    None => None,
+                };
+                branch.map_or(Value::Unknown, |b| self.evaluate_expression(&b, source))
+            }
+            "switch_expression" => {
+                // Only a case that computes its value directly has one here;
+                // cases with statements run in `execute_switch`
+                let (Some(condition), Some(body)) = (
+                    node.child_by_field_name("condition"),
+                    node.child_by_field_name("body"),
+                ) else {
+                    return Value::Unknown;
+                };
+                let value = self.evaluate_expression(&condition, source);
+                let (cases, matched) = self.select_switch_case(&body, &value, source);
+                let Some((index, _)) = matched else {
+                    return Value::Unknown;
+                };
+                let mut cursor = cases[index].walk();
+                let arm = cases[index]
+                    .named_children(&mut cursor)
+                    .find(|child| child.kind() != "switch_label");
+                match arm {
+                    Some(arm)
+                        if matches!(arm.kind(), "expression_statement" | "yield_statement") =>
+                    {
+                        arm.named_child(0).map_or(Value::Unknown, |expr| {
+                            self.evaluate_expression(&expr, source)
+                        })
+                    }
+                    _ => Value::Unknown,
+                }
+            }
             "update_expression" => {
                 // i++, i--, ++i, --i -- just return the current value
-                if let Some(id_node) = node.named_child(0) {
-                    if id_node.kind() == "identifier" {
-                        return self.resolve_variable_value(node_text(&id_node, source));
-                    }
+                if let Some(id_node) = node.named_child(0)
+                    && id_node.kind() == "identifier"
+                {
+                    return self.resolve_variable_value(node_text(&id_node, source));
                 }
                 Value::Unknown
             }
@@ -1660,33 +6266,337 @@ impl ExecutionAnalyzer {
         }
     }
 
-    /// Look up a variable name in local_variables, param_values, or field_values.
-    fn resolve_variable_value(&self, name: &str) -> Value {
-        if let Some(v) = self.local_variables.get(name) {
-            return self.parse_stored_value(v);
+    /// Whether `node` evaluates to a `java.util.Scanner`, which reads the
+    /// stdin script whatever it was constructed from.
+    fn is_scanner(&self, node: &Node, source: &str) -> bool {
+        match self.evaluate_expression(node, source) {
+            Value::Ref(id) => self.heap.get(id).is_some_and(|obj| {
+                matches!(obj.class_name.as_str(), "Scanner" | "java.util.Scanner")
+            }),
+            _ => false,
         }
-        if let Some(v) = self.param_values.get(name) {
-            return self.parse_stored_value(v);
+    }
+
+    /// Perform the side effects of evaluating `node` that the read-only
+    /// [`Self::evaluate_expression`] can't, in Java's left-to-right order:
+    /// `Scanner` reads, recorded in `input_reads`, calls to methods of the
+    /// analyzed source, which run with their steps, and nested assignments
+    /// and increments, which write their target. The value of each
+    /// operand is recorded in `operand_values` as soon as it is evaluated.
+    /// `?:` records which operand it chose.
+    /// Operands that aren't evaluated are skipped: the right side of a
+    /// decided `&&`/`||` and the `?:` branch not taken. A call, assignment
+    /// or increment making up a whole statement is left to the statement,
+    /// and nested blocks and bodies are left for when they run.
+    fn run_operands(&mut self, node: &Node, source: &str, root_node: &Node) {
+        if self.is_throwing() {
+            return;
+        }
+        match node.kind() {
+            "block" | "lambda_expression" | "class_body" | "switch_block" => return,
+            "ternary_expression" => {
+                let Some(condition) = node.child_by_field_name("condition") else {
+                    return;
+                };
+                self.run_operands(&condition, source, root_node);
+                if let Some(taken) = self.evaluate_expression(&condition, source).as_bool()
+                    && !self.is_throwing()
+                {
+                    self.trace_ternary(node, taken, source);
+                    let branch = if taken { "consequence" } else { "alternative" };
+                    if let Some(branch) = node.child_by_field_name(branch) {
+                        self.run_operands(&branch, source, root_node);
+                    }
+                }
+            }
+            "binary_expression"
+                if let Some(left) = node.child_by_field_name("left")
+                    && let Some(right) = node.child_by_field_name("right")
+                    && let Some(op) = node.child_by_field_name("operator")
+                    && matches!(node_text(&op, source), "&&" | "||") =>
+            {
+                self.run_operands(&left, source, root_node);
+                let runs_right = self.evaluate_expression(&left, source).as_bool()
+                    == Some(node_text(&op, source) == "&&");
+                if runs_right {
+                    self.run_operands(&right, source, root_node);
+                }
+            }
+            // The target of an assignment or update is written, not read:
+            // only its object and index are evaluated. `x = ...;` and `x++;`
+            // are written by their statement, nested ones here, where they
+            // evaluate to the new value (the old one for postfix `x++`).
+            "assignment_expression" => {
+                let Some(left) = node.child_by_field_name("left") else {
+                    return;
+                };
+                self.run_target_operands(&left, source, root_node);
+                if let Some(right) = node.child_by_field_name("right") {
+                    self.run_operands(&right, source, root_node);
+                }
+                if Self::is_whole_statement(node) || self.is_throwing() {
+                    return;
+                }
+                let line_number = node.start_position().row + 1;
+                let source_line = self.get_source_line(line_number);
+                self.analyze_assignment(node, source, line_number, &source_line, root_node);
+                let value = self.evaluate_expression(&left, source);
+                self.operand_values
+                    .insert((self.frames.len(), node.id()), value);
+                return;
+            }
+            "update_expression" => {
+                let Some(target) = node.named_child(0) else {
+                    return;
+                };
+                self.run_target_operands(&target, source, root_node);
+                if Self::is_whole_statement(node) || self.is_throwing() {
+                    return;
+                }
+                let line_number = node.start_position().row + 1;
+                let source_line = self.get_source_line(line_number);
+                let old = self.evaluate_expression(&target, source);
+                self.analyze_update(node, source, line_number, &source_line);
+                let prefix = node.child(0).is_some_and(|op| !op.is_named());
+                let value = if prefix {
+                    self.evaluate_expression(&target, source)
+                } else {
+                    old
+                };
+                self.operand_values
+                    .insert((self.frames.len(), node.id()), value);
+                return;
+            }
+            _ => {
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    self.run_operands(&child, source, root_node);
+                }
+            }
+        }
+        if self.is_throwing() {
+            return;
+        }
+        if node.kind() == "method_invocation" {
+            // `a.f();` and `T x = a.f();` run the call themselves, and leave
+            // its value then
+            if !self.run_invocation(node, source, root_node) || self.is_throwing() {
+                return;
+            }
+        }
+        // `Dog.count` initializes `Dog` first
+        if node.kind() == "field_access"
+            && let Some(object) = node.child_by_field_name("object")
+            && let Some(class_name) = self.class_named_by(&object, source)
+        {
+            self.initialize_class(&class_name, source, root_node);
+        }
+        let kind = node.kind();
+        if Self::allocates(node) {
+            // `new` making up what a declaration, assignment, `return` or
+            // `throw` stores is allocated by the statement, and the rows of
+            // an array initializer by the array
+            let allocated_by_parent = node.parent().is_some_and(|p| {
+                matches!(
+                    p.kind(),
+                    "variable_declarator"
+                        | "assignment_expression"
+                        | "return_statement"
+                        | "throw_statement"
+                        | "expression_statement"
+                        | "array_creation_expression"
+                        | "array_initializer"
+                )
+            });
+            if allocated_by_parent {
+                return;
+            }
+            let line_number = node.start_position().row + 1;
+            let source_line = self.get_source_line(line_number);
+            let target_type = self.static_type_of(node, source).unwrap_or_default();
+            let value = self.evaluate_allocating(
+                node,
+                node_text(node, source),
+                &target_type,
+                StepContext {
+                    line_number,
+                    source_line: &source_line,
+                    source,
+                    root_node,
+                },
+            );
+            self.operand_values
+                .insert((self.frames.len(), node.id()), value);
+            return;
+        }
+        if !(kind.ends_with("_statement")
+            || kind.ends_with("_declaration")
+            || matches!(kind, "variable_declarator" | "argument_list"))
+        {
+            // Objects concatenated to a string are converted with their
+            // `toString()`, called here in evaluation order
+            let value = if kind == "binary_expression"
+                && node
+                    .child_by_field_name("operator")
+                    .is_some_and(|op| node_text(&op, source) == "+")
+            {
+                let line_number = node.start_position().row + 1;
+                let source_line = self.get_source_line(line_number);
+                self.printable_value(node, line_number, &source_line, source, root_node)
+            } else {
+                self.evaluate_expression(node, source)
+            };
+            self.operand_values
+                .insert((self.frames.len(), node.id()), value);
+        }
+    }
+
+    /// Evaluate the operands of the assignment target `target`: the object
+    /// of a field access, the array and index of an element.
+    fn run_target_operands(&mut self, target: &Node, source: &str, root_node: &Node) {
+        let fields: &[&str] = match target.kind() {
+            "field_access" => &["object"],
+            "array_access" => &["array", "index"],
+            "parenthesized_expression" => {
+                if let Some(inner) = target.named_child(0) {
+                    self.run_target_operands(&inner, source, root_node);
+                }
+                return;
+            }
+            _ => return,
+        };
+        for field in fields {
+            if let Some(operand) = target.child_by_field_name(field) {
+                self.run_operands(&operand, source, root_node);
+            }
+        }
+    }
+
+    /// Whether the expression `node` makes up an expression statement.
+    fn is_whole_statement(node: &Node) -> bool {
+        node.parent()
+            .is_some_and(|p| p.kind() == "expression_statement")
+    }
+
+    /// Drop the recorded values of `node` and the operands in it, before
+    /// it is evaluated again.
+    fn forget_operands(&mut self, node: &Node) {
+        let depth = self.frames.len();
+        let mut pending = vec![*node];
+        while let Some(current) = pending.pop() {
+            self.operand_values.remove(&(depth, current.id()));
+            let mut cursor = current.walk();
+            pending.extend(current.named_children(&mut cursor));
         }
-        Value::Unknown
     }
 
-    /// Parse a stored string value into a Value.
-    fn parse_stored_value(&self, s: &str) -> Value {
-        if s == "true" {
-            return Value::Bool(true);
+    /// Side effect of the invocation `node` within an expression, once its
+    /// receiver and arguments have been evaluated. Returns `false` for a
+    /// call left to the statement it makes up.
+    fn run_invocation(&mut self, node: &Node, source: &str, root_node: &Node) -> bool {
+        let Some(name) = node.child_by_field_name("name") else {
+            return true;
+        };
+        let method_name = node_text(&name, source);
+        let object = node.child_by_field_name("object");
+        // `a.f();` and `T x = a.f();` run the call themselves; a null
+        // receiver raises instead of running. Field initializers don't.
+        let whole_statement = node.parent().is_some_and(|p| match p.kind() {
+            "expression_statement" => true,
+            "variable_declarator" => p
+                .parent()
+                .is_some_and(|d| d.kind() == "local_variable_declaration"),
+            _ => false,
+        });
+        if let Some(object) = object
+            && Stdin::is_read(method_name)
+            && self.is_scanner(&object, source)
+        {
+            let line_number = node.start_position().row + 1;
+            let source_line = self.get_source_line(line_number);
+            if let Some(stdin) = self.stdin.as_mut() {
+                match stdin.read(method_name) {
+                    Ok(value) => {
+                        self.input_reads.insert(node.id(), value);
+                    }
+                    Err(fault) => {
+                        let fault = RuntimeFault {
+                            exception_class: fault.exception_class,
+                            message: fault.message.map(str::to_string),
+                            origin: None,
+                        };
+                        if let Some(frame) = self.frames.last_mut() {
+                            frame.line = line_number;
+                        }
+                        self.raise(fault, line_number, &source_line);
+                        return true;
+                    }
+                }
+            }
+            // Reads within larger expressions get the step a statement's own
+            // call gets
+            if !whole_statement {
+                self.analyze_method_invocation(node, source, line_number, &source_line, root_node);
+            }
+            return true;
         }
-        if s == "false" {
-            return Value::Bool(false);
+        if whole_statement {
+            return false;
+        }
+        let null_receiver =
+            object.is_some_and(|o| self.evaluate_expression(&o, source) == Value::Null);
+        let modeled = self.call_target(node, source).resolved.is_some()
+            || self.collection_receiver(node, source).is_some();
+        if !null_receiver && modeled {
+            let line_number = node.start_position().row + 1;
+            let source_line = self.get_source_line(line_number);
+            self.analyze_method_invocation(node, source, line_number, &source_line, root_node);
+        }
+        true
+    }
+
+    /// `==` on two evaluated operands. Strings that haven't been stored yet
+    /// are told apart by where they come from: a constant denotes its pooled
+    /// object (which may not exist yet), anything else a new object.
+    fn reference_equality(&self, left: (&Node, Value), right: (&Node, Value)) -> Value {
+        let identity = |(node, value): (&Node, Value)| match value {
+            Value::Str(text) if Self::is_constant_expression(node) => {
+                match self.heap.interned(&text) {
+                    Some(id) => (Value::Ref(id), true),
+                    None => (Value::Str(text), true),
+                }
+            }
+            other => (other, false),
+        };
+        match (identity(left), identity(right)) {
+            ((Value::Str(a), true), (Value::Str(b), true)) => Value::Bool(a == b),
+            ((Value::Str(_), _), (other, _)) | ((other, _), (Value::Str(_), _)) => {
+                if other.is_known() {
+                    Value::Bool(false)
+                } else {
+                    Value::Unknown
+                }
+            }
+            ((a, _), (b, _)) => a.eq_val(&b),
         }
-        if s == "null" {
-            return Value::Null;
+    }
+
+    /// Look up a variable name in the running frame's locals and
+    /// parameters, or the fields of the current `this` object.
+    fn resolve_variable_value(&self, name: &str) -> Value {
+        if let Some(v) = self.frames.last().and_then(|f| f.lookup(name)) {
+            return v.value.clone();
         }
-        if let Ok(i) = s.parse::<i64>() {
-            return Value::Int(i);
+        if let Some((id, declaring_class)) = self.implicit_field_owner(name)
+            && let Some(v) = self.heap.get_field(id, &declaring_class, name)
+        {
+            return v.clone();
         }
-        if let Ok(f) = s.parse::<f64>() {
-            return Value::Float(f);
+        if let Some(class_name) = self.current_class()
+            && let Some(owner) = self.static_field_owner(class_name, name)
+            && let Some(v) = self.heap.get_static(&owner, name)
+        {
+            return v.clone();
         }
         Value::Unknown
     }
@@ -1694,21 +6604,20 @@ impl ExecutionAnalyzer {
     /// Execute an update expression (i++, i--, ++i, --i) with side effects.
     fn execute_update_expression(&mut self, node: &Node, source: &str) {
         let text = node_text(node, source);
-        if let Some(id_node) = node.named_child(0) {
-            if id_node.kind() == "identifier" {
-                let var_name = node_text(&id_node, source).to_string();
-                let current = self.resolve_variable_value(&var_name);
-                let new_val = if text.contains("++") {
-                    current.add(&Value::Int(1))
-                } else if text.contains("--") {
-                    current.sub(&Value::Int(1))
-                } else {
-                    return;
-                };
-                if new_val.is_known() {
-                    self.local_variables
-                        .insert(var_name, new_val.to_storage_string());
-                }
+        if let Some(id_node) = node.named_child(0)
+            && id_node.kind() == "identifier"
+        {
+            let var_name = node_text(&id_node, source).to_string();
+            let current = self.resolve_variable_value(&var_name);
+            let new_val = if text.contains("++") {
+                current.add(&Value::Int(1))
+            } else if text.contains("--") {
+                current.sub(&Value::Int(1))
+            } else {
+                return;
+            };
+            if new_val.is_known() {
+                self.assign_variable(&var_name, new_val);
             }
         }
     }
@@ -1768,22 +6677,23 @@ mod tests {
             );
         }
 
-        // Without MethodEntry/MethodExit steps, we expect 11 steps:
+        // Without MethodEntry/MethodExit steps, we expect 12 steps:
         // 1: ObjectCreation(calc) -- call_stack includes Calculator.<init>
-        // 2: FieldMutation(value = 0.0)
-        // 3: MethodCall(calc.add(5)) -- call_stack includes Calculator.add
-        // 4: FieldMutation(value += amount)
-        // 5: MethodCall(calc.add(3))
-        // 6: FieldMutation(value += amount)
-        // 7: MethodCall(calc.getResult()) -- call_stack includes Calculator.getResult
-        // 8: FieldAccess(Calculator.value)
-        // 9: MethodReturn(Calculator.getResult)
-        // 10: VariableAssignment(result = calc.getResult())
-        // 11: MethodCall(println)
+        // 2: FieldDefaults(calc) -- value starts at 0.0
+        // 3: FieldMutation(value = 0.0)
+        // 4: MethodCall(calc.add(5)) -- call_stack includes Calculator.add
+        // 5: FieldMutation(value += amount)
+        // 6: MethodCall(calc.add(3))
+        // 7: FieldMutation(value += amount)
+        // 8: MethodCall(calc.getResult()) -- call_stack includes Calculator.getResult
+        // 9: FieldAccess(Calculator.value)
+        // 10: MethodReturn(Calculator.getResult)
+        // 11: VariableAssignment(result = calc.getResult())
+        // 12: MethodCall(println)
         assert_eq!(
             flow.steps.len(),
-            11,
-            "Expected 11 steps without MethodEntry/MethodExit"
+            12,
+            "Expected 12 steps without MethodEntry/MethodExit"
         );
 
         // Step 1: ObjectCreation -- call stack already includes constructor
@@ -1792,7 +6702,9 @@ mod tests {
             ExecutionAction::ObjectCreation {
                 variable_name: "calc".into(),
                 class_name: "Calculator".into(),
-                constructor_params: vec![]
+                object_id: 1,
+                constructor_params: vec![],
+                signature: Some("<init>()".into()),
             }
         );
         assert!(
@@ -1802,138 +6714,154 @@ mod tests {
             "ObjectCreation step should already have constructor on call stack"
         );
 
-        // Step 2: FieldMutation for this.value = 0.0
+        // Step 2: the field starts at its default value
         assert_eq!(
             flow.steps[1].action,
+            ExecutionAction::FieldDefaults {
+                class_name: "Calculator".into(),
+                object_id: 1,
+            }
+        );
+
+        // Step 3: FieldMutation for this.value = 0.0 (the field already
+        // holds its default value)
+        assert_eq!(
+            flow.steps[2].action,
             ExecutionAction::FieldMutation {
                 class_name: "Calculator".into(),
+                object_id: 1,
                 field_name: "value".into(),
-                old_value: None,
+                old_value: Some("0.0".into()),
                 new_value: "0.0".into()
             }
         );
 
-        // Step 3: MethodCall for calc.add(5) -- call stack includes Calculator.add
+        // Step 4: MethodCall for calc.add(5) -- call stack includes Calculator.add
         assert_eq!(
-            flow.steps[2].action,
+            flow.steps[3].action,
             ExecutionAction::MethodCall {
                 caller: Some("calc".into()),
                 method_name: "add".into(),
                 target_class: "Calculator".into(),
-                parameters: vec!["5".into()]
+                parameters: vec!["5".into()],
+                signature: Some("add(double)".into()),
             }
         );
         assert!(
-            flow.steps[2]
+            flow.steps[3]
                 .call_stack
                 .contains(&"Calculator.add".to_string()),
             "MethodCall step should already have method on call stack"
         );
 
-        // Step 4: FieldMutation for this.value += amount (first call: 0.0 + 5 = 5.0)
+        // Step 5: FieldMutation for this.value += amount (first call: 0.0 + 5 = 5.0)
         assert_eq!(
-            flow.steps[3].action,
+            flow.steps[4].action,
             ExecutionAction::FieldMutation {
                 class_name: "Calculator".into(),
+                object_id: 1,
                 field_name: "value".into(),
                 old_value: Some("0.0".into()),
                 new_value: "5.0".into(),
             }
         );
 
-        // Step 5: MethodCall for calc.add(3)
+        // Step 6: MethodCall for calc.add(3)
         assert_eq!(
-            flow.steps[4].action,
+            flow.steps[5].action,
             ExecutionAction::MethodCall {
                 caller: Some("calc".into()),
                 method_name: "add".into(),
                 target_class: "Calculator".into(),
-                parameters: vec!["3".into()]
+                parameters: vec!["3".into()],
+                signature: Some("add(double)".into()),
             }
         );
 
-        // Step 6: FieldMutation for second add call (5.0 + 3 = 8.0)
+        // Step 7: FieldMutation for second add call (5.0 + 3 = 8.0)
         assert_eq!(
-            flow.steps[5].action,
+            flow.steps[6].action,
             ExecutionAction::FieldMutation {
                 class_name: "Calculator".into(),
+                object_id: 1,
                 field_name: "value".into(),
                 old_value: Some("5.0".into()),
                 new_value: "8.0".into(),
             }
         );
 
-        // Step 7: MethodCall for calc.getResult()
+        // Step 8: MethodCall for calc.getResult()
         assert_eq!(
-            flow.steps[6].action,
+            flow.steps[7].action,
             ExecutionAction::MethodCall {
                 caller: Some("calc".into()),
                 method_name: "getResult".into(),
                 target_class: "Calculator".into(),
-                parameters: vec![]
+                parameters: vec![],
+                signature: Some("getResult()".into()),
             }
         );
 
-        // Step 8: FieldAccess for this.value in getResult
+        // Step 9: FieldAccess for this.value in getResult
         if let ExecutionAction::FieldAccess {
             class_name,
             field_name,
             ..
-        } = &flow.steps[7].action
+        } = &flow.steps[8].action
         {
             assert_eq!(class_name, "Calculator");
             assert_eq!(field_name, "value");
         } else {
             panic!(
-                "Step 8 should be FieldAccess, got {:?}",
-                flow.steps[7].action
+                "Step 9 should be FieldAccess, got {:?}",
+                flow.steps[8].action
             );
         }
 
-        // Step 9: MethodReturn from getResult
+        // Step 10: MethodReturn from getResult
         if let ExecutionAction::MethodReturn {
             method_name,
             return_value,
-        } = &flow.steps[8].action
+        } = &flow.steps[9].action
         {
             assert_eq!(method_name, "Calculator.getResult");
-            assert_eq!(return_value, &Some("this.value".to_string()));
+            assert_eq!(return_value, &Some("8.0".to_string()));
         } else {
             panic!(
-                "Step 9 should be MethodReturn, got {:?}",
-                flow.steps[8].action
+                "Step 10 should be MethodReturn, got {:?}",
+                flow.steps[9].action
             );
         }
 
-        // Step 10: VariableAssignment for result = calc.getResult()
+        // Step 11: VariableAssignment for result = calc.getResult()
         if let ExecutionAction::VariableAssignment {
             variable_name,
             value,
             ..
-        } = &flow.steps[9].action
+        } = &flow.steps[10].action
         {
             assert_eq!(variable_name, "result");
-            assert_eq!(value, "calc.getResult()");
+            assert_eq!(value, "8.0");
         } else {
             panic!(
-                "Step 10 should be VariableAssignment, got {:?}",
-                flow.steps[9].action
+                "Step 11 should be VariableAssignment, got {:?}",
+                flow.steps[10].action
             );
         }
 
-        // Step 11: MethodCall for println (JDK method, NOT stepped into)
+        // Step 12: MethodCall for println (JDK method, NOT stepped into)
         if let ExecutionAction::MethodCall {
             method_name,
             target_class,
             ..
-        } = &flow.steps[10].action
+        } = &flow.steps[11].action
         {
             assert_eq!(method_name, "println");
             assert_eq!(target_class, "System.out");
         } else {
             panic!(
-                "Step 11 should be MethodCall for println, got {:?}",
-                flow.steps[10].action
+                "Step 12 should be MethodCall for println, got {:?}",
+                flow.steps[11].action
             );
         }
 
@@ -1945,6 +6873,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn method_signature_erases_generics() {
+        assert_eq!(
+            method_signature("put", ["Map<String, List<Integer>>", "int"]),
+            "put(Map,int)"
+        );
+        assert_eq!(method_signature("<init>", []), "<init>()");
+        assert_eq!(
+            signature_parameter_types("put(Map,int)"),
+            vec!["Map", "int"]
+        );
+        assert!(signature_parameter_types("run()").is_empty());
+    }
+
+    #[test]
+    fn literals_carry_their_java_type() {
+        assert_eq!(integer_literal("1_000"), Some(Value::Int(1000)));
+        assert_eq!(integer_literal("0xFFFFFFFF"), Some(Value::Int(-1)));
+        assert_eq!(integer_literal("017"), Some(Value::Int(15)));
+        assert_eq!(integer_literal("0b101"), Some(Value::Int(5)));
+        assert_eq!(integer_literal("0x0b1"), Some(Value::Int(0xb1)));
+        assert_eq!(integer_literal("10L"), Some(Value::Long(10)));
+        assert_eq!(floating_literal("2.5f"), Some(Value::Float(2.5)));
+        assert_eq!(floating_literal("2.5"), Some(Value::Double(2.5)));
+        assert_eq!(floating_literal("1e3d"), Some(Value::Double(1000.0)));
+        assert_eq!(char_literal("'a'"), Some(Value::Char('a' as u16)));
+        assert_eq!(char_literal("'\\n'"), Some(Value::Char('\n' as u16)));
+        assert_eq!(char_literal("'\\''"), Some(Value::Char('\'' as u16)));
+        assert_eq!(char_literal("'\\u0041'"), Some(Value::Char('A' as u16)));
+        assert_eq!(char_literal("'\\101'"), Some(Value::Char('A' as u16)));
+    }
+
     #[test]
     fn method_body_map_contains_all_methods() {
         let java_code = r#"
@@ -1983,20 +6943,20 @@ mod tests {
 
         // Verify all expected entries exist
         assert!(
-            map.contains_key(&("Calculator".to_string(), "add".to_string())),
-            "Map should contain Calculator.add"
+            map.contains_key(&("Calculator".to_string(), "add(double)".to_string())),
+            "Map should contain Calculator.add(double)"
         );
         assert!(
-            map.contains_key(&("Calculator".to_string(), "getResult".to_string())),
+            map.contains_key(&("Calculator".to_string(), "getResult()".to_string())),
             "Map should contain Calculator.getResult"
         );
         assert!(
-            map.contains_key(&("Calculator".to_string(), "<init>".to_string())),
-            "Map should contain Calculator.<init>"
+            map.contains_key(&("Calculator".to_string(), "<init>()".to_string())),
+            "Map should contain Calculator.<init>()"
         );
         assert!(
-            map.contains_key(&("TestExecution".to_string(), "main".to_string())),
-            "Map should contain TestExecution.main"
+            map.contains_key(&("TestExecution".to_string(), "main(String[])".to_string())),
+            "Map should contain TestExecution.main(String[])"
         );
 
         // Verify byte ranges are valid (start < end, both > 0)
@@ -2106,4 +7066,101 @@ public class Dog {
             );
         }
     }
+
+    #[test]
+    fn methods_mutate_their_own_receiver() {
+        let java_code = r#"
+public class Dog {
+    int age;
+
+    public Dog(int anAge) {
+        this.age = anAge;
+    }
+
+    public void birthday() {
+        this.age += 1;
+    }
+
+    public static void main(String[] args) {
+        Dog a = new Dog(3);
+        Dog b = new Dog(7);
+        b.birthday();
+    }
+}
+        "#;
+
+        let mut parser = JavaParser::new().unwrap();
+        let tree = parser.parse(java_code).unwrap();
+        let root = parser.get_root_node(&tree);
+
+        let mut analyzer = JavaAnalyzer::new();
+        let analysis = analyzer.analyze(&root, java_code);
+
+        let mut exec_analyzer = ExecutionAnalyzer::new(analysis);
+        let flow = exec_analyzer.analyze_execution_flow(&root, java_code);
+
+        let birthday = flow
+            .steps
+            .iter()
+            .find(|s| matches!(&s.action, ExecutionAction::FieldMutation { new_value, .. } if new_value == "8"))
+            .expect("b.birthday() should bump b's age to 8");
+        if let ExecutionAction::FieldMutation {
+            object_id,
+            old_value,
+            ..
+        } = &birthday.action
+        {
+            assert_eq!(*object_id, 2, "birthday should run with this = b");
+            assert_eq!(old_value.as_deref(), Some("7"));
+        }
+
+        let heap = &flow.steps.last().unwrap().heap;
+        assert_eq!(heap.len(), 2, "each new should allocate its own object");
+        assert_eq!(heap[0].field("age"), Some(&Value::Int(3)));
+        assert_eq!(heap[1].field("age"), Some(&Value::Int(8)));
+    }
+
+    #[test]
+    fn methods_run_on_a_receiver_created_in_place() {
+        let java_code = r#"
+public class Dog {
+    int age;
+
+    public Dog(int anAge) {
+        this.age = anAge;
+    }
+
+    public int older() {
+        return this.age + 1;
+    }
+
+    public static void main(String[] args) {
+        int next = new Dog(1).older();
+    }
+}
+        "#;
+
+        let mut parser = JavaParser::new().unwrap();
+        let tree = parser.parse(java_code).unwrap();
+        let root = parser.get_root_node(&tree);
+
+        let mut analyzer = JavaAnalyzer::new();
+        let analysis = analyzer.analyze(&root, java_code);
+
+        let mut exec_analyzer = ExecutionAnalyzer::new(analysis);
+        let flow = exec_analyzer.analyze_execution_flow(&root, java_code);
+
+        let in_older = flow
+            .steps
+            .iter()
+            .find_map(|s| s.frames.get(1).filter(|f| f.method == "Dog.older"))
+            .expect("older() should get a frame");
+        assert_eq!(
+            in_older.this_object,
+            Some(1),
+            "older should run with this = the new Dog"
+        );
+        let last = flow.steps.last().unwrap();
+        assert_eq!(last.frames[0].lookup("next").unwrap().value, Value::Int(2));
+    }
 }
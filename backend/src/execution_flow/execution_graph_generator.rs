@@ -1,16 +1,20 @@
 use super::{
-    ExecutionFlow,
+    ExecutionFlow, Value,
     execution_analyzer::{ExecutionAction, ExecutionStep},
+    frame::Variable,
+    heap::{HeapObject, ObjectId},
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+
+/// Cells drawn for an array before the rest are elided.
+const MAX_RENDERED_ELEMENTS: usize = 20;
 
 /// Controls what is rendered into each step's DOT document.
 #[derive(Debug, Clone)]
 pub struct ExecutionGraphConfig {
-    /// Render the call-stack side panel
+    /// Render the call stack with each frame's parameters and locals
     pub show_call_stack: bool,
-    /// Render the active-object panel with field values
+    /// Render the heap panel with each object's field values
     pub show_object_states: bool,
 }
 
@@ -34,6 +38,8 @@ pub struct ExecutionGraphStep {
     pub dot_code: String,
     /// Aggregate counters describing execution up through this step
     pub execution_state: ExecutionState,
+    /// Console output written so far, for the frontend's console pane
+    pub console: String,
 }
 
 /// Summary counters describing the program at a particular step.
@@ -91,6 +97,7 @@ impl ExecutionGraphGenerator {
                 description: step.description.clone(),
                 dot_code,
                 execution_state,
+                console: step.console.clone(),
             });
         }
 
@@ -111,11 +118,21 @@ impl ExecutionGraphGenerator {
         dot.push_str("    node [fontname=\"Arial\"];\n");
         dot.push_str("    edge [fontname=\"Arial\", fontsize=10];\n");
         dot.push_str("    compound=true;\n\n");
-        dot.push_str(&format!(
-            "    label=\"Step {} | {}\";\n",
-            step_number,
-            self.escape_label(&current_step.source_line)
-        ));
+        // An exception points at the line that raised it, in red
+        if matches!(current_step.action, ExecutionAction::ExceptionThrown { .. }) {
+            dot.push_str(&format!(
+                "    label=<Step {} | <FONT COLOR=\"red\">Line {}: {}</FONT>>;\n",
+                step_number,
+                current_step.line_number,
+                self.escape_html(&current_step.source_line)
+            ));
+        } else {
+            dot.push_str(&format!(
+                "    label=\"Step {} | {}\";\n",
+                step_number,
+                self.escape_label(&current_step.source_line)
+            ));
+        }
         dot.push_str("    labelloc=top;\n");
         dot.push_str("    fontsize=16;\n\n");
 
@@ -124,6 +141,13 @@ impl ExecutionGraphGenerator {
             dot.push_str(&self.generate_object_state_subgraph(steps));
         }
 
+        // Static fields belong to their class, not to any object on the heap
+        if let Some(step) = steps.last()
+            && !step.statics.is_empty()
+        {
+            dot.push_str(&self.generate_static_area_subgraph(step));
+        }
+
         // Call stack is shown as a secondary panel to the side.
         if self.config.show_call_stack && !steps.is_empty() {
             dot.push_str(&self.generate_call_stack_subgraph(steps.last().unwrap()));
@@ -133,31 +157,61 @@ impl ExecutionGraphGenerator {
         dot
     }
 
+    /// Render the call stack as one box per frame, outermost first. Each box
+    /// lists `this`, the parameters and the locals of its frame; primitives
+    /// show their value and references get an arrow into the heap panel.
     fn generate_call_stack_subgraph(&self, current_step: &ExecutionStep) -> String {
         let mut subgraph = String::new();
+        let mut reference_edges = Vec::new();
 
         subgraph.push_str("    subgraph cluster_callstack {\n");
         subgraph.push_str("        label=\"Call Stack\";\n");
         subgraph.push_str("        style=filled;\n");
         subgraph.push_str("        fillcolor=lightblue;\n");
 
-        if current_step.call_stack.is_empty() {
+        if current_step.frames.is_empty() {
             subgraph.push_str("        empty_stack [label=\"(empty)\", style=dashed];\n");
         } else {
-            for (i, method) in current_step.call_stack.iter().enumerate() {
+            for (i, frame) in current_step.frames.iter().enumerate() {
                 let node_id = format!("stack_{}", i);
-                let style = if i == current_step.call_stack.len() - 1 {
-                    // yellow for last call in call stack
-                    "filled, fillcolor=yellow"
+                // yellow for the running frame
+                let bgcolor = if i == current_step.frames.len() - 1 {
+                    "yellow"
                 } else {
-                    "filled, fillcolor=white"
+                    "white"
                 };
 
+                let mut rows = String::new();
+                if let Some(this_id) = frame.this_object {
+                    rows.push_str(&format!(
+                        "<TR><TD ALIGN=\"LEFT\">{} this</TD><TD PORT=\"this\" WIDTH=\"60\">&#9679;</TD></TR>",
+                        self.escape_html(frame.class_name.as_deref().unwrap_or("")),
+                    ));
+                    reference_edges.push((
+                        this_id,
+                        format!(
+                            "    {node_id}:this -> obj_{this_id} [arrowhead=normal, style=dashed];\n"
+                        ),
+                    ));
+                }
+                for (variable, is_parameter) in frame
+                    .parameters
+                    .iter()
+                    .map(|v| (v, true))
+                    .chain(frame.locals.iter().map(|v| (v, false)))
+                {
+                    let (row, edge) = self.frame_variable_row(&node_id, variable, is_parameter);
+                    rows.push_str(&row);
+                    reference_edges.extend(edge);
+                }
+
                 subgraph.push_str(&format!(
-                    "        {} [label=\"{}\", style=\"{}\"];\n",
-                    node_id,
-                    self.escape_label(method),
-                    style
+                    "        {node_id} [label=<\
+                    <TABLE BORDER=\"0\" CELLBORDER=\"1\" CELLSPACING=\"0\" CELLPADDING=\"4\" BGCOLOR=\"{bgcolor}\">\
+                    <TR><TD COLSPAN=\"2\"><B>{method}</B></TD></TR>\
+                    {rows}\
+                    </TABLE>>, shape=none];\n",
+                    method = self.escape_html(&frame.method),
                 ));
 
                 if i > 0 {
@@ -171,87 +225,132 @@ impl ExecutionGraphGenerator {
         }
 
         subgraph.push_str("    }\n\n");
-        subgraph
-    }
 
-    /// Java primitive types that should render as value boxes, not object references.
-    const PRIMITIVE_TYPES: &'static [&'static str] = &[
-        "int", "long", "short", "byte", "float", "double", "char", "boolean",
-    ];
+        // Arrows into the heap only make sense when the heap panel is drawn
+        if self.config.show_object_states {
+            for (target, edge) in reference_edges {
+                if current_step.heap.iter().any(|obj| obj.id == target) {
+                    subgraph.push_str(&edge);
+                }
+            }
+            subgraph.push('\n');
+        }
 
-    fn is_primitive_type(type_name: &str) -> bool {
-        Self::PRIMITIVE_TYPES.contains(&type_name)
+        subgraph
     }
 
-    /// Render active objects and primitives using memory diagram notation:
-    /// - Primitive variable = labeled box containing its value
-    /// - Object variable = box with reference arrow to class ellipse
-    /// - Class ellipse shows field names and their current runtime values
-    fn generate_object_state_subgraph(&self, steps: &[ExecutionStep]) -> String {
+    /// Render the static area: one box per initialized class with its
+    /// static fields. Reference fields get an arrow into the heap panel.
+    fn generate_static_area_subgraph(&self, current_step: &ExecutionStep) -> String {
         let mut subgraph = String::new();
+        let mut reference_edges = Vec::new();
 
-        // Collect object variables: var_name -> class_name
-        let mut active_objects: HashMap<String, String> = HashMap::new();
-        // Collect primitive variables: var_name -> (type, current_value)
-        let mut primitives: HashMap<String, (String, String)> = HashMap::new();
-        // Collect per-object field values: (var_name, field_name) -> value
-        let mut object_fields: HashMap<String, Vec<(String, String)>> = HashMap::new();
-        // Track which variable was most recently created per class_name
-        let mut last_created: HashMap<String, String> = HashMap::new();
-
-        for step in steps {
-            match &step.action {
-                ExecutionAction::ObjectCreation {
-                    variable_name,
-                    class_name,
-                    ..
-                } => {
-                    active_objects.insert(variable_name.clone(), class_name.clone());
-                    // Track which instance was most recently created per class
-                    last_created.insert(class_name.clone(), variable_name.clone());
-                }
-                ExecutionAction::VariableAssignment {
-                    variable_name,
-                    value_type,
-                    value,
-                } => {
-                    // Only track primitive declarations in main scope (not "declared" or "assigned")
-                    if Self::is_primitive_type(value_type) && value != "declared" {
-                        primitives
-                            .insert(variable_name.clone(), (value_type.clone(), value.clone()));
+        subgraph.push_str("    subgraph cluster_statics {\n");
+        subgraph.push_str("        label=\"Static Area\";\n");
+        subgraph.push_str("        style=filled;\n");
+        subgraph.push_str("        fillcolor=\"#e6f4ea\";\n");
+
+        for class in &current_step.statics {
+            let node_id = format!("static_{}", self.sanitize_name(&class.class_name));
+            let mut rows = String::new();
+            for slot in &class.fields {
+                let name = self.escape_html(&slot.name);
+                let port = format!("f_{}", self.sanitize_name(&slot.name));
+                match &slot.value {
+                    Value::Ref(target) => {
+                        rows.push_str(&format!(
+                            "<TR><TD ALIGN=\"LEFT\">{name}</TD><TD PORT=\"{port}\" WIDTH=\"60\">&#9679;</TD></TR>"
+                        ));
+                        reference_edges.push((
+                            *target,
+                            format!("    {node_id}:{port} -> obj_{target} [arrowhead=normal];\n"),
+                        ));
                     }
+                    value => rows.push_str(&format!(
+                        "<TR><TD ALIGN=\"LEFT\">{name}</TD><TD WIDTH=\"60\" BGCOLOR=\"white\">{}</TD></TR>",
+                        self.escape_html(&value.to_string()),
+                    )),
                 }
-                ExecutionAction::FieldMutation {
-                    class_name,
-                    field_name,
-                    new_value,
-                    ..
-                } => {
-                    // Attribute mutation to the most recently created instance of this class.
-                    // Falls back to updating all instances if no creation was tracked.
-                    let target_var = last_created.get(class_name).cloned();
-                    for (var_name, cls) in &active_objects {
-                        if cls != class_name {
-                            continue;
-                        }
-                        if let Some(ref target) = target_var {
-                            if var_name != target {
-                                continue;
-                            }
-                        }
-                        let fields = object_fields.entry(var_name.clone()).or_default();
-                        if let Some(entry) = fields.iter_mut().find(|(n, _)| n == field_name) {
-                            entry.1 = new_value.clone();
-                        } else {
-                            fields.push((field_name.clone(), new_value.clone()));
-                        }
-                    }
+            }
+            subgraph.push_str(&format!(
+                "        {node_id} [label=<\
+                <TABLE BORDER=\"0\" CELLBORDER=\"1\" CELLSPACING=\"0\" CELLPADDING=\"4\" BGCOLOR=\"white\">\
+                <TR><TD COLSPAN=\"2\"><B>{class_name}</B> <I>(static)</I></TD></TR>\
+                {rows}\
+                </TABLE>>, shape=none];\n",
+                class_name = self.escape_html(&class.class_name),
+            ));
+        }
+
+        subgraph.push_str("    }\n\n");
+
+        if self.config.show_object_states {
+            for (target, edge) in reference_edges {
+                if current_step.heap.iter().any(|obj| obj.id == target) {
+                    subgraph.push_str(&edge);
                 }
-                _ => {}
             }
+            subgraph.push('\n');
         }
 
-        if active_objects.is_empty() && primitives.is_empty() {
+        subgraph
+    }
+
+    /// One `type name | value` row of a stack frame box, plus the edge to the
+    /// referenced heap object if the variable holds a reference.
+    fn frame_variable_row(
+        &self,
+        node_id: &str,
+        variable: &Variable,
+        is_parameter: bool,
+    ) -> (String, Option<(ObjectId, String)>) {
+        let name = self.escape_html(&variable.name);
+        let mut label = if variable.type_name.is_empty() {
+            name
+        } else {
+            format!("{} {}", self.escape_html(&variable.type_name), name)
+        };
+        if is_parameter {
+            label = format!("<I>{}</I>", label);
+        }
+
+        let port_name = self.sanitize_name(&variable.name);
+        match &variable.value {
+            Value::Ref(target) => (
+                format!(
+                    "<TR><TD ALIGN=\"LEFT\">{label}</TD><TD PORT=\"var_{port_name}\" WIDTH=\"60\">&#9679;</TD></TR>"
+                ),
+                Some((
+                    *target,
+                    format!("    {node_id}:var_{port_name} -> obj_{target} [arrowhead=normal];\n"),
+                )),
+            ),
+            Value::Null => (
+                format!(
+                    "<TR><TD ALIGN=\"LEFT\">{label}</TD><TD PORT=\"var_{port_name}\" WIDTH=\"60\">null</TD></TR>"
+                ),
+                None,
+            ),
+            value => (
+                format!(
+                    "<TR><TD ALIGN=\"LEFT\">{label}</TD><TD PORT=\"prim_{port_name}\" WIDTH=\"60\" BGCOLOR=\"white\">{}</TD></TR>",
+                    self.escape_html(&value.to_string()),
+                ),
+                None,
+            ),
+        }
+    }
+
+    /// Render the heap: one ellipse per object with the class name and that
+    /// instance's current field values, taken from the step's heap snapshot.
+    /// Variables pointing at these objects live in the stack frames. Objects
+    /// that just became unreachable are greyed out.
+    fn generate_object_state_subgraph(&self, steps: &[ExecutionStep]) -> String {
+        let mut subgraph = String::new();
+
+        let heap: &[HeapObject] = steps.last().map(|s| s.heap.as_slice()).unwrap_or(&[]);
+
+        if heap.is_empty() {
             return subgraph;
         }
 
@@ -260,38 +359,106 @@ impl ExecutionGraphGenerator {
         subgraph.push_str("        style=filled;\n");
         subgraph.push_str("        fillcolor=\"#f0f0f0\";\n");
 
-        // Render object variables
-        for (var_name, class_name) in &active_objects {
-            let var_id = format!("var_{}", self.sanitize_name(var_name));
-            let obj_id = format!("obj_{}", self.sanitize_name(var_name));
+        // Render every object on the heap, one ellipse per instance.
+        // Reference-valued fields get a port so an edge can leave the slot.
+        let mut field_edges = Vec::new();
+        let mut pool_nodes = String::new();
+        for object in heap {
+            let obj_id = format!("obj_{}", object.id);
+
+            // Strings are immutable, so the contents are all there is to show.
+            // Pooled strings are collected into their own area of the heap.
+            if let Some(text) = object.string_value() {
+                let nodes = if object.interned {
+                    &mut pool_nodes
+                } else {
+                    &mut subgraph
+                };
+                nodes.push_str(&format!(
+                    "        {obj_id} [label=<\
+                    <TABLE BORDER=\"0\" CELLBORDER=\"0\" CELLSPACING=\"0\" CELLPADDING=\"4\">\
+                    <TR><TD><B>String</B></TD></TR>\
+                    <TR><TD BORDER=\"1\" BGCOLOR=\"lightyellow\">{}</TD></TR>\
+                    </TABLE>>, shape=ellipse, style=filled, fillcolor=white];\n",
+                    self.escape_html(&Value::Str(text.to_string()).to_string()),
+                ));
+                continue;
+            }
 
-            // Variable reference box
-            subgraph.push_str(&format!(
-                "        {var_id} [label=<\
-                <TABLE BORDER=\"0\" CELLBORDER=\"0\" CELLSPACING=\"0\" CELLPADDING=\"2\">\
-                <TR><TD><FONT POINT-SIZE=\"10\">{class_name} {var_name}</FONT></TD></TR>\
-                <TR><TD BORDER=\"1\" WIDTH=\"60\" HEIGHT=\"20\"> </TD></TR>\
-                </TABLE>>, shape=none];\n",
-                var_name = self.escape_html(var_name),
-                class_name = self.escape_html(class_name),
-            ));
+            if let Some(elements) = &object.elements {
+                subgraph.push_str(&self.array_node(&obj_id, object, elements, heap));
+                for (i, element) in elements.iter().enumerate().take(MAX_RENDERED_ELEMENTS) {
+                    if let Value::Ref(target) = element
+                        && heap.iter().any(|obj| obj.id == *target)
+                    {
+                        field_edges.push(format!(
+                            "        {obj_id}:e_{i} -> obj_{target} [arrowhead=normal];\n"
+                        ));
+                    }
+                }
+                continue;
+            }
 
-            // Object ellipse with class name and field values
-            let fields = object_fields.get(var_name);
-            let field_rows = fields
-                .map(|flds| {
-                    flds.iter()
-                        .map(|(name, val)| {
-                            format!(
-                                "<TR><TD ALIGN=\"LEFT\" BORDER=\"1\" BGCOLOR=\"lightyellow\">{} = {}</TD></TR>",
-                                self.escape_html(name),
-                                self.escape_html(val),
-                            )
-                        })
-                        .collect::<Vec<_>>()
-                        .join("")
-                })
-                .unwrap_or_default();
+            // Inherited fields are grouped under the class declaring them;
+            // objects whose fields all come from their own class stay flat
+            let grouped = object
+                .fields
+                .iter()
+                .any(|slot| slot.declaring_class != object.class_name);
+            let mut field_rows = String::new();
+            let mut current_group: Option<&str> = None;
+
+            for (i, slot) in object.fields.iter().enumerate() {
+                if grouped && current_group != Some(slot.declaring_class.as_str()) {
+                    current_group = Some(&slot.declaring_class);
+                    field_rows.push_str(&format!(
+                        "<TR><TD ALIGN=\"LEFT\"><FONT POINT-SIZE=\"10\"><I>{}</I></FONT></TD></TR>",
+                        self.escape_html(&slot.declaring_class),
+                    ));
+                }
+
+                // A subclass slot with the same name further down hides this one
+                let hidden = object.fields[i + 1..]
+                    .iter()
+                    .any(|later| later.name == slot.name);
+                let (port, label, bgcolor) = if hidden {
+                    (
+                        format!(
+                            "f_{}_{}",
+                            self.sanitize_name(&slot.declaring_class),
+                            self.sanitize_name(&slot.name)
+                        ),
+                        format!(
+                            "{}.{}",
+                            self.escape_html(&slot.declaring_class),
+                            self.escape_html(&slot.name)
+                        ),
+                        "#e0e0e0",
+                    )
+                } else {
+                    (
+                        format!("f_{}", self.sanitize_name(&slot.name)),
+                        self.escape_html(&slot.name),
+                        "lightyellow",
+                    )
+                };
+                let hidden_note = if hidden { " (hidden)" } else { "" };
+
+                match &slot.value {
+                    Value::Ref(target) if heap.iter().any(|obj| obj.id == *target) => {
+                        field_edges.push(format!(
+                            "        {obj_id}:{port} -> obj_{target} [arrowhead=normal];\n"
+                        ));
+                        field_rows.push_str(&format!(
+                            "<TR><TD ALIGN=\"LEFT\" BORDER=\"1\" BGCOLOR=\"{bgcolor}\" PORT=\"{port}\">{label} = &#9679;{hidden_note}</TD></TR>",
+                        ));
+                    }
+                    value => field_rows.push_str(&format!(
+                        "<TR><TD ALIGN=\"LEFT\" BORDER=\"1\" BGCOLOR=\"{bgcolor}\">{label} = {}{hidden_note}</TD></TR>",
+                        self.escape_html(&value.to_string()),
+                    )),
+                }
+            }
 
             subgraph.push_str(&format!(
                 "        {obj_id} [label=<\
@@ -299,28 +466,32 @@ impl ExecutionGraphGenerator {
                 <TR><TD><B>{class_name}</B></TD></TR>\
                 {field_rows}\
                 </TABLE>>, shape=ellipse, style=filled, fillcolor=white];\n",
-                class_name = self.escape_html(class_name),
+                class_name = self.escape_html(&object.class_name),
             ));
+        }
 
-            subgraph.push_str(&format!(
-                "        {var_id} -> {obj_id} [arrowhead=normal];\n",
-            ));
+        if !pool_nodes.is_empty() {
+            subgraph.push_str("        subgraph cluster_string_pool {\n");
+            subgraph.push_str("            label=\"String Pool\";\n");
+            subgraph.push_str("            style=\"filled,dashed\";\n");
+            subgraph.push_str("            fillcolor=\"#e8f0fe\";\n");
+            subgraph.push_str(&pool_nodes);
+            subgraph.push_str("        }\n");
         }
 
-        // Render primitive variables as value boxes
-        for (var_name, (type_name, value)) in &primitives {
-            let var_id = format!("prim_{}", self.sanitize_name(var_name));
+        // Object-to-object references, drawn from the field's slot
+        for edge in &field_edges {
+            subgraph.push_str(edge);
+        }
 
-            subgraph.push_str(&format!(
-                "        {var_id} [label=<\
-                <TABLE BORDER=\"0\" CELLBORDER=\"0\" CELLSPACING=\"0\" CELLPADDING=\"2\">\
-                <TR><TD><FONT POINT-SIZE=\"10\">{type_name} {var_name}</FONT></TD></TR>\
-                <TR><TD BORDER=\"1\" WIDTH=\"60\" HEIGHT=\"25\" BGCOLOR=\"white\">{value}</TD></TR>\
-                </TABLE>>, shape=none];\n",
-                var_name = self.escape_html(var_name),
-                type_name = self.escape_html(type_name),
-                value = self.escape_html(value),
-            ));
+        if let Some(ExecutionAction::EligibleForGc { object_ids }) = steps.last().map(|s| &s.action)
+        {
+            for id in object_ids {
+                subgraph.push_str(&format!(
+                    "        obj_{id} [style=\"filled,dashed\", fillcolor=\"#d9d9d9\", \
+                    color=gray50, fontcolor=gray50, xlabel=\"eligible for GC\"];\n"
+                ));
+            }
         }
 
         subgraph.push_str("    }\n\n");
@@ -328,6 +499,57 @@ impl ExecutionGraphGenerator {
         subgraph
     }
 
+    /// Node for an array: a row of index labels over a row of cells.
+    /// Reference cells get a port `e_{index}` for the edge to their object.
+    /// Past [`MAX_RENDERED_ELEMENTS`] the rest is summed up in one cell.
+    fn array_node(
+        &self,
+        obj_id: &str,
+        object: &HeapObject,
+        elements: &[Value],
+        heap: &[HeapObject],
+    ) -> String {
+        let mut indices = String::new();
+        let mut cells = String::new();
+        for (i, element) in elements.iter().enumerate().take(MAX_RENDERED_ELEMENTS) {
+            indices.push_str(&format!("<TD><FONT POINT-SIZE=\"10\">{i}</FONT></TD>"));
+            match element {
+                Value::Ref(target) if heap.iter().any(|obj| obj.id == *target) => {
+                    cells.push_str(&format!(
+                        "<TD BORDER=\"1\" BGCOLOR=\"lightyellow\" PORT=\"e_{i}\">&#9679;</TD>"
+                    ));
+                }
+                value => cells.push_str(&format!(
+                    "<TD BORDER=\"1\" BGCOLOR=\"lightyellow\">{}</TD>",
+                    self.escape_html(&value.to_string()),
+                )),
+            }
+        }
+        let hidden = elements.len().saturating_sub(MAX_RENDERED_ELEMENTS);
+        if hidden > 0 {
+            indices.push_str("<TD><FONT POINT-SIZE=\"10\">&#8230;</FONT></TD>");
+            cells.push_str(&format!(
+                "<TD BORDER=\"1\" BGCOLOR=\"lightyellow\">&#8230; {hidden} more</TD>"
+            ));
+        }
+        // An empty array says so instead of showing a cell it doesn't have
+        if elements.is_empty() {
+            indices.push_str("<TD></TD>");
+            cells.push_str("<TD><I>length 0</I></TD>");
+        }
+
+        format!(
+            "        {obj_id} [label=<\
+            <TABLE BORDER=\"0\" CELLBORDER=\"0\" CELLSPACING=\"0\" CELLPADDING=\"4\">\
+            <TR><TD COLSPAN=\"{span}\"><B>{class_name}</B></TD></TR>\
+            <TR>{indices}</TR>\
+            <TR>{cells}</TR>\
+            </TABLE>>, shape=none];\n",
+            span = elements.len().clamp(1, MAX_RENDERED_ELEMENTS + 1),
+            class_name = self.escape_html(&object.class_name),
+        )
+    }
+
     fn calculate_execution_state(&self, steps: &[ExecutionStep]) -> ExecutionState {
         let mut objects_created = 0;
         let mut method_calls_made = 0;
@@ -336,7 +558,9 @@ impl ExecutionGraphGenerator {
 
         for step in steps {
             match &step.action {
-                ExecutionAction::ObjectCreation { .. } => objects_created += 1,
+                ExecutionAction::ObjectCreation { .. } | ExecutionAction::ArrayCreation { .. } => {
+                    objects_created += 1
+                }
                 ExecutionAction::MethodCall { .. } => method_calls_made += 1,
                 _ => {}
             }
@@ -377,7 +601,9 @@ impl ExecutionGraphGenerator {
 #[cfg(test)]
 mod generator_tests {
     use super::super::execution_analyzer::{ExecutionAction, ExecutionStep};
+    use super::super::frame::StackFrame;
     use super::*;
+    use std::collections::HashMap;
 
     #[test]
     fn execution_graph_generation() {
@@ -388,11 +614,28 @@ mod generator_tests {
             action: ExecutionAction::ObjectCreation {
                 variable_name: "calc".to_string(),
                 class_name: "Calculator".to_string(),
+                object_id: 1,
                 constructor_params: vec![],
+                signature: None,
             },
             call_stack: vec!["main".to_string()],
             active_objects: vec!["calc".to_string()],
             description: "Create Calculator object".to_string(),
+            heap: vec![HeapObject {
+                id: 1,
+                class_name: "Calculator".to_string(),
+                fields: vec![],
+                elements: None,
+                text: None,
+                interned: false,
+            }],
+            statics: vec![],
+            frames: vec![{
+                let mut frame = StackFrame::new("main", None, None);
+                frame.declare_local("calc", "Calculator", Value::Ref(1));
+                frame
+            }],
+            console: String::new(),
         }];
 
         let flow = ExecutionFlow {
@@ -408,6 +651,7 @@ mod generator_tests {
         assert_eq!(graphs.len(), 1);
         assert!(graphs[0].dot_code.contains("ExecutionStep_1"));
         assert!(graphs[0].dot_code.contains("Calculator"));
+        assert!(graphs[0].dot_code.contains("stack_0:var_calc -> obj_1"));
     }
 
     // -- End-to-end integration tests --
@@ -416,8 +660,35 @@ mod generator_tests {
     // as an empty `value_type`, so only full-pipeline tests live here.
 
     fn run_full_pipeline(java_code: &str) -> Vec<String> {
-        crate::execution_flow_gen(java_code)
+        crate::execution_flow_gen(java_code, None)
+    }
+
+    /// Find the definition line of the heap object that `var_name` points to.
+    fn object_def_for<'a>(dot: &'a str, var_name: &str) -> Option<&'a str> {
+        let arrow = format!(":var_{} -> ", var_name);
+        let obj_id = dot
+            .lines()
+            .find_map(|l| l.split_once(arrow.as_str()).map(|(_, rest)| rest))?
+            .split_whitespace()
+            .next()?;
+        let def = format!("{} [", obj_id);
+        dot.lines().find(|l| l.trim().starts_with(&def))
+    }
+
+    /// Find the definition line of the heap object that the field behind
+    /// `port` of the object defined by `object_def` points to.
+    fn field_target_def<'a>(dot: &'a str, object_def: &str, port: &str) -> Option<&'a str> {
+        let obj_id = object_def.split_whitespace().next()?;
+        let arrow = format!("{}:{} -> ", obj_id, port);
+        let target = dot
+            .lines()
+            .find_map(|l| l.split_once(arrow.as_str()).map(|(_, rest)| rest))?
+            .split_whitespace()
+            .next()?;
+        let def = format!("{} [", target);
+        dot.lines().find(|l| l.trim().starts_with(&def))
     }
+
     #[test]
     fn e2e_primitive_int_renders_as_box_with_value() {
         // Reproduces screenshot bug: int num = 7 was missing from the visualization
@@ -478,21 +749,23 @@ public class Dog {
         let dots = run_full_pipeline(java);
         let last_dot = dots.last().expect("should have steps");
 
-        // Extract only the casper object definition
-        let casper_def = last_dot
-            .lines()
-            .find(|l| l.contains("obj_casper ["))
-            .expect("should have obj_casper definition");
-        let harvey_def = last_dot
-            .lines()
-            .find(|l| l.contains("obj_harvey ["))
-            .expect("should have obj_harvey definition");
+        // Extract only the object definitions casper and harvey point to
+        let casper_def =
+            object_def_for(last_dot, "casper").expect("should have casper's object definition");
+        let harvey_def =
+            object_def_for(last_dot, "harvey").expect("should have harvey's object definition");
+
+        // String fields point at String objects of their own
+        let casper_talk = field_target_def(last_dot, casper_def, "f_talk")
+            .expect("casper's talk should point at a String");
+        let harvey_talk = field_target_def(last_dot, harvey_def, "f_talk")
+            .expect("harvey's talk should point at a String");
 
         // casper must show its own values
         assert!(
-            casper_def.contains("arf"),
+            casper_talk.contains("arf"),
             "casper should show 'arf', got:\n{}",
-            casper_def
+            casper_talk
         );
         assert!(
             casper_def.contains("= 5"),
@@ -500,9 +773,9 @@ public class Dog {
             casper_def
         );
         assert!(
-            !casper_def.contains("ruff"),
+            !casper_talk.contains("ruff"),
             "casper should NOT show harvey's value 'ruff', got:\n{}",
-            casper_def
+            casper_talk
         );
         assert!(
             !casper_def.contains("= 10"),
@@ -512,9 +785,9 @@ public class Dog {
 
         // harvey must show its own values
         assert!(
-            harvey_def.contains("ruff"),
+            harvey_talk.contains("ruff"),
             "harvey should show 'ruff', got:\n{}",
-            harvey_def
+            harvey_talk
         );
         assert!(
             harvey_def.contains("= 10"),
@@ -555,10 +828,13 @@ public class Dog {
             .join("\n");
 
         // The field values must be resolved, not literal param names
+        let casper_def = object_def_for(last_dot, "casper").expect("casper's object");
+        let talk = field_target_def(last_dot, casper_def, "f_talk")
+            .expect("talk should point at the String passed in");
         assert!(
-            dot_no_title.contains("talk = \"arf\""),
-            "DOT must contain resolved talk value, got:\n{}",
-            dot_no_title
+            talk.contains("\"arf\""),
+            "talk must point at the resolved String value, got:\n{}",
+            talk
         );
         assert!(
             dot_no_title.contains("age = 5"),
@@ -612,7 +888,8 @@ public class Dog {
         // All three requirements from the screenshot must be met:
         // 1. Both Dog instances visible with correct per-instance values
         assert!(
-            dot_no_title.contains("obj_casper") && dot_no_title.contains("obj_harvey"),
+            object_def_for(&dot_no_title, "casper").is_some()
+                && object_def_for(&dot_no_title, "harvey").is_some(),
             "Both Dog instances should be visible. DOT:\n{}",
             dot_no_title
         );
@@ -629,4 +906,543 @@ public class Dog {
             dot_no_title
         );
     }
+
+    #[test]
+    fn e2e_aliased_variables_share_one_object() {
+        let java = r#"
+public class Dog {
+    int age;
+
+    public static void main(String[] args) {
+        Dog a = new Dog();
+        Dog b = a;
+        b.age = 4;
+    }
+}
+        "#;
+
+        let dots = run_full_pipeline(java);
+        let last_dot = dots.last().expect("should have steps");
+
+        assert!(
+            last_dot.contains("var_a -> obj_1") && last_dot.contains("var_b -> obj_1"),
+            "both variables should point at the same heap node, got:\n{}",
+            last_dot
+        );
+        assert!(
+            !last_dot.contains("obj_2"),
+            "copying a reference must not create a second object, got:\n{}",
+            last_dot
+        );
+        let def = object_def_for(last_dot, "a").expect("a's object should be rendered");
+        assert!(
+            def.contains("age = 4"),
+            "mutation through b should be visible through a, got:\n{}",
+            def
+        );
+    }
+
+    #[test]
+    fn e2e_reference_fields_draw_edges_between_objects() {
+        let java = r#"
+public class Dog {
+    Person owner;
+    Collar collar;
+
+    public Dog(Person owner) {
+        this.owner = owner;
+        this.collar = new Collar();
+    }
+
+    public static void main(String[] args) {
+        Person p = new Person();
+        Dog d = new Dog(p);
+    }
+}
+
+class Person {}
+
+class Collar {}
+        "#;
+
+        let dots = run_full_pipeline(java);
+        let last_dot = dots.last().expect("should have steps");
+
+        assert!(
+            last_dot.contains("obj_2:f_owner -> obj_1"),
+            "owner field should point at the Person object, got:\n{}",
+            last_dot
+        );
+        assert!(
+            last_dot.contains("obj_2:f_collar -> obj_3"),
+            "collar field should point at the Collar created in the constructor, got:\n{}",
+            last_dot
+        );
+        assert!(
+            !last_dot.contains("owner = person") && !last_dot.contains("var_this"),
+            "reference fields should not render as text or as variables, got:\n{}",
+            last_dot
+        );
+    }
+
+    #[test]
+    fn e2e_parameter_receives_copy_of_callers_reference() {
+        let java = r#"
+public class Vet {
+    public void treat(Dog patient) {
+        patient.age = 3;
+    }
+
+    public static void main(String[] args) {
+        Dog dog = new Dog();
+        Vet vet = new Vet();
+        vet.treat(dog);
+    }
+}
+
+class Dog {
+    int age;
+}
+        "#;
+
+        let dots = run_full_pipeline(java);
+        let during_call = dots
+            .iter()
+            .find(|dot| dot.contains("Vet.treat"))
+            .expect("some step should show the treat frame");
+
+        assert!(
+            during_call.contains("stack_0:var_dog -> obj_1")
+                && during_call.contains("stack_1:var_patient -> obj_1"),
+            "caller's local and callee's parameter should point at the same Dog, got:\n{}",
+            during_call
+        );
+        assert!(
+            during_call.contains("stack_1:this -> obj_2"),
+            "callee frame should show its receiver, got:\n{}",
+            during_call
+        );
+    }
+
+    #[test]
+    fn e2e_parameter_bound_to_object_created_in_the_call() {
+        let java = r#"
+public class Main {
+    static void helper(Dog patient) {
+        patient.age = 5;
+    }
+
+    public static void main(String[] args) {
+        helper(new Dog());
+    }
+}
+
+class Dog {
+    int age;
+}
+        "#;
+
+        let dots = run_full_pipeline(java);
+        let during_call = dots
+            .iter()
+            .find(|dot| dot.contains("Main.helper"))
+            .expect("some step should show the helper frame");
+
+        assert!(
+            during_call.contains("stack_1:var_patient -> obj_1"),
+            "the parameter should point at the new Dog, got:\n{}",
+            during_call
+        );
+    }
+
+    #[test]
+    fn e2e_inherited_fields_grouped_by_declaring_class() {
+        let java = r#"
+public class Main {
+    public static void main(String[] args) {
+        Dog d = new Dog();
+    }
+}
+
+class Animal {
+    String name = "animal";
+    int legs = 4;
+}
+
+class Dog extends Animal {
+    String name = "dog";
+    boolean loyal;
+}
+        "#;
+
+        let dots = run_full_pipeline(java);
+        let last_dot = dots.last().expect("should have steps");
+        let def = object_def_for(last_dot, "d").expect("d's object should be rendered");
+
+        let animal_header = def.find("<I>Animal</I>").expect("Animal group header");
+        let dog_header = def.find("<I>Dog</I>").expect("Dog group header");
+        assert!(
+            animal_header < dog_header,
+            "inherited group should come first, got:\n{}",
+            def
+        );
+        assert!(
+            def.contains("Animal.name = &#9679; (hidden)"),
+            "hidden superclass field should be marked, got:\n{}",
+            def
+        );
+        let hidden = field_target_def(last_dot, def, "f_Animal_name").expect("Animal.name");
+        let own = field_target_def(last_dot, def, "f_name").expect("Dog.name");
+        assert!(hidden.contains("\"animal\""), "got:\n{}", hidden);
+        assert!(own.contains("\"dog\""), "got:\n{}", own);
+        assert!(def.contains("legs = 4"), "got:\n{}", def);
+    }
+
+    #[test]
+    fn e2e_arrays_render_as_row_of_cells() {
+        let java = r#"
+public class Main {
+    public static void main(String[] args) {
+        int[] a = {7, 8, 9};
+        Dog[] dogs = new Dog[2];
+        dogs[1] = new Dog();
+    }
+}
+
+class Dog {}
+        "#;
+
+        let dots = run_full_pipeline(java);
+        let last_dot = dots.last().expect("should have steps");
+
+        assert!(
+            last_dot.contains("obj_1 [label=<") && last_dot.contains("<B>int[]</B>"),
+            "int array should be a heap node labelled with its type, got:\n{}",
+            last_dot
+        );
+        assert!(
+            last_dot.contains(
+                "<TD BORDER=\"1\" BGCOLOR=\"lightyellow\">7</TD>\
+                 <TD BORDER=\"1\" BGCOLOR=\"lightyellow\">8</TD>\
+                 <TD BORDER=\"1\" BGCOLOR=\"lightyellow\">9</TD>"
+            ),
+            "elements should sit side by side in one row, got:\n{}",
+            last_dot
+        );
+        assert!(
+            last_dot.contains("stack_0:var_dogs -> obj_2"),
+            "dogs should point at the Dog[] array, got:\n{}",
+            last_dot
+        );
+        assert!(
+            last_dot.contains(">null</TD>") && last_dot.contains("obj_2:e_1 -> obj_3"),
+            "unset slot should show null and the filled slot should point at the Dog, got:\n{}",
+            last_dot
+        );
+    }
+
+    #[test]
+    fn e2e_empty_and_argument_arrays_render_on_the_heap() {
+        let java = r#"
+public class Main {
+    static int sum(int[] values) {
+        int total = 0;
+        for (int v : values) {
+            total += v;
+        }
+        return total;
+    }
+
+    public static void main(String[] args) {
+        int[] none = new int[0];
+        int s = sum(new int[]{4, 5});
+    }
+}
+        "#;
+
+        let dots = run_full_pipeline(java);
+        let first = dots.first().expect("should have steps");
+        assert!(
+            first.contains("<B>int[]</B>")
+                && first.contains("<TD><I>length 0</I></TD>")
+                && !first.contains("BGCOLOR=\"lightyellow\">"),
+            "an empty array should be marked as length 0 with no cells, got:\n{}",
+            first
+        );
+        let during_call = dots
+            .iter()
+            .find(|dot| dot.contains("Main.sum"))
+            .expect("some step should show the sum frame");
+        assert!(
+            during_call.contains("stack_1:var_values -> obj_2")
+                && during_call.contains("BGCOLOR=\"lightyellow\">4</TD>"),
+            "the argument array should be drawn with its elements, got:\n{}",
+            during_call
+        );
+    }
+
+    #[test]
+    fn e2e_long_arrays_elide_cells_past_the_limit() {
+        let java = r#"
+public class Main {
+    public static void main(String[] args) {
+        int[] a = new int[50];
+        int[] huge = new int[100000];
+    }
+}
+        "#;
+
+        let dots = run_full_pipeline(java);
+        let last_dot = dots.last().expect("should have steps");
+
+        assert!(
+            last_dot.contains("<FONT POINT-SIZE=\"10\">19</FONT>")
+                && !last_dot.contains("<FONT POINT-SIZE=\"10\">20</FONT>"),
+            "only the first 20 cells should be drawn, got:\n{}",
+            last_dot
+        );
+        assert!(
+            last_dot.contains(">&#8230; 30 more</TD>"),
+            "the other cells should be summed up, got:\n{}",
+            last_dot
+        );
+        assert!(
+            !last_dot.contains("obj_2"),
+            "an array too large to track should not be allocated, got:\n{}",
+            last_dot
+        );
+    }
+
+    #[test]
+    fn e2e_jagged_array_rows_are_separate_objects() {
+        let java = r#"
+public class Main {
+    public static void main(String[] args) {
+        int[][] m = {{1, 2}, {3}};
+    }
+}
+        "#;
+
+        let dots = run_full_pipeline(java);
+        let last_dot = dots.last().expect("should have steps");
+
+        assert!(
+            last_dot.contains("<B>int[][]</B>")
+                && last_dot.contains("obj_1:e_0 -> obj_2")
+                && last_dot.contains("obj_1:e_1 -> obj_3"),
+            "outer array should hold references to its rows, got:\n{}",
+            last_dot
+        );
+        assert!(
+            last_dot.contains("obj_2 [label=<") && last_dot.contains("obj_3 [label=<"),
+            "each row should be its own heap node, got:\n{}",
+            last_dot
+        );
+        assert!(
+            last_dot.contains("<TR><TD BORDER=\"1\" BGCOLOR=\"lightyellow\">3</TD></TR>"),
+            "the short row should have a single cell, got:\n{}",
+            last_dot
+        );
+    }
+
+    #[test]
+    fn e2e_string_literals_live_in_the_pool() {
+        let java = r#"
+public class Main {
+    public static void main(String[] args) {
+        String a = "hi";
+        String b = "hi";
+        String c = new String("hi");
+    }
+}
+        "#;
+
+        let dots = run_full_pipeline(java);
+        let last_dot = dots.last().expect("should have steps");
+
+        let pool_start = last_dot
+            .find("subgraph cluster_string_pool")
+            .expect("pooled strings should get their own area");
+        let pool = &last_dot[pool_start..];
+        let pool = &pool[..pool.find("        }\n").unwrap()];
+        assert!(
+            pool.contains("obj_1 [label=<") && !pool.contains("obj_2 [label=<"),
+            "only the literal's object belongs in the pool, got:\n{}",
+            pool
+        );
+        assert!(
+            last_dot.contains("stack_0:var_a -> obj_1")
+                && last_dot.contains("stack_0:var_b -> obj_1")
+                && last_dot.contains("stack_0:var_c -> obj_2"),
+            "a and b share the pooled object, c has its own, got:\n{}",
+            last_dot
+        );
+    }
+
+    #[test]
+    fn e2e_runtime_error_highlights_the_offending_line() {
+        let java = r#"
+public class Main {
+    public static void main(String[] args) {
+        int[] a = new int[2];
+        a[2] = 1;
+    }
+}
+        "#;
+
+        let dots = run_full_pipeline(java);
+        let raised = dots
+            .iter()
+            .find(|dot| dot.contains("ArrayIndexOutOfBoundsException"))
+            .expect("the exception object should be on the heap");
+        assert!(
+            raised.contains("<FONT COLOR=\"red\">Line 5: a[2] = 1;</FONT>"),
+            "got:\n{}",
+            raised
+        );
+    }
+
+    #[test]
+    fn e2e_static_fields_render_in_the_static_area() {
+        let java = r#"
+class Dog {
+    static int count = 0;
+    static Dog first;
+    Dog() {
+        count++;
+        if (first == null) {
+            first = this;
+        }
+    }
+}
+public class Main {
+    public static void main(String[] args) {
+        Dog a = new Dog();
+        Dog b = new Dog();
+    }
+}
+        "#;
+
+        let dots = run_full_pipeline(java);
+        let last_dot = dots.last().expect("should have steps");
+        let area_start = last_dot
+            .find("subgraph cluster_statics")
+            .expect("static fields should get their own area");
+        let area = &last_dot[area_start..];
+        let area = &area[..area.find("    }\n").unwrap()];
+        assert!(
+            area.contains("static_Dog [label=<") && area.contains(">2</TD>"),
+            "Dog.count is shown once, with both constructions counted:\n{}",
+            area
+        );
+        assert!(
+            last_dot.contains("static_Dog:f_first -> obj_1"),
+            "the static reference points at the first Dog:\n{}",
+            last_dot
+        );
+        let dog_def = object_def_for(last_dot, "b").unwrap();
+        assert!(
+            !dog_def.contains("count"),
+            "instances don't carry static fields: {}",
+            dog_def
+        );
+    }
+
+    #[test]
+    fn e2e_unreachable_objects_are_greyed_out_then_removed() {
+        let java = r#"
+class Dog {
+    Dog friend;
+}
+public class Main {
+    public static void main(String[] args) {
+        Dog a = new Dog();
+        a.friend = new Dog();
+        a.friend.friend = a;
+        a = new Dog();
+        int done = 1;
+    }
+}
+        "#;
+
+        let dots = run_full_pipeline(java);
+        let gc_step = dots
+            .iter()
+            .position(|dot| dot.contains("eligible for GC"))
+            .expect("dropping the only reference to a cycle makes it collectable");
+        let greyed: Vec<&str> = dots[gc_step]
+            .lines()
+            .filter(|l| l.contains("eligible for GC"))
+            .collect();
+        assert_eq!(greyed.len(), 2, "both objects of the cycle: {:?}", greyed);
+        assert!(greyed[0].trim().starts_with("obj_1 [") && greyed[1].trim().starts_with("obj_2 ["));
+
+        let after = &dots[gc_step + 1];
+        assert!(
+            !after.contains("obj_1 [") && !after.contains("obj_2 [") && after.contains("obj_3 ["),
+            "collected objects leave the heap, the new one stays:\n{}",
+            after
+        );
+    }
+
+    #[test]
+    fn e2e_block_locals_leave_the_frame_when_the_block_ends() {
+        let java = r#"
+public class Main {
+    public static void main(String[] args) {
+        int total = 0;
+        if (total == 0) {
+            int bonus = 5;
+            total = total + bonus;
+        }
+        total = total * 2;
+    }
+}
+        "#;
+
+        let dots = run_full_pipeline(java);
+        assert!(
+            dots.iter().any(|dot| dot.contains("prim_bonus")),
+            "bonus is shown while its block runs"
+        );
+        let last = dots.last().unwrap();
+        assert!(
+            last.contains("prim_total") && !last.contains("prim_bonus"),
+            "only total is left in the frame:\n{}",
+            last
+        );
+    }
+
+    #[test]
+    fn e2e_collections_render_their_element_arrays() {
+        let java = r#"
+import java.util.ArrayList;
+class Dog {}
+public class Main {
+    public static void main(String[] args) {
+        ArrayList<Dog> dogs = new ArrayList<>();
+        dogs.add(new Dog());
+        dogs.add(new Dog());
+        int n = dogs.size();
+    }
+}
+        "#;
+
+        let dots = run_full_pipeline(java);
+        let last = dots.last().unwrap();
+        let list = object_def_for(last, "dogs").expect("dogs points at the list");
+        assert!(list.contains("<B>ArrayList</B>") && list.contains("size = 2"));
+        let array = field_target_def(last, list, "f_elementData")
+            .expect("the list refers to its element array");
+        assert!(array.contains("<B>Object[]</B>"), "{}", array);
+        assert!(
+            last.contains("obj_2:e_0 -> obj_3") && last.contains("obj_2:e_1 -> obj_4"),
+            "each slot refers to its Dog:\n{}",
+            last
+        );
+        assert!(
+            dots.iter().all(|dot| !dot.contains("ArrayList.add")),
+            "JDK methods are modeled, not stepped into"
+        );
+    }
 }
@@ -0,0 +1,147 @@
+use super::{evaluator::Value, heap::ObjectId};
+use serde::{Deserialize, Serialize};
+
+/// A named slot in a [`StackFrame`]: a parameter or a local variable.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Variable {
+    /// Name of the variable as written in the source
+    pub name: String,
+    /// Declared type, or an empty string if it is not known
+    pub type_name: String,
+    /// Current value; references hold the id of a heap object
+    pub value: Value,
+}
+
+/// One activation record on the simulated call stack.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StackFrame {
+    /// Display name of the running method, e.g. `main` or `Dog.bark`
+    pub method: String,
+    /// Class whose code is running, if known
+    pub class_name: Option<String>,
+    /// Object `this` refers to; `None` in static methods
+    pub this_object: Option<ObjectId>,
+    /// Formal parameters, bound to copies of the call-site arguments
+    pub parameters: Vec<Variable>,
+    /// Local variables in scope, in declaration order
+    pub locals: Vec<Variable>,
+    /// Number of locals declared before each enclosing block was entered,
+    /// innermost last
+    #[serde(skip)]
+    pub scopes: Vec<usize>,
+    /// Line of the statement the frame is executing, 0 before the first
+    pub line: usize,
+}
+
+impl StackFrame {
+    /// Create an empty frame for `method`.
+    pub fn new(method: &str, class_name: Option<String>, this_object: Option<ObjectId>) -> Self {
+        StackFrame {
+            method: method.to_string(),
+            class_name,
+            this_object,
+            parameters: Vec::new(),
+            locals: Vec::new(),
+            scopes: Vec::new(),
+            line: 0,
+        }
+    }
+
+    /// Bind a formal parameter.
+    pub fn bind_parameter(&mut self, name: &str, type_name: &str, value: Value) {
+        self.parameters.push(Variable {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            value,
+        });
+    }
+
+    /// Declare a local variable in the innermost block. Redeclaring a name
+    /// in the same block replaces the old slot.
+    pub fn declare_local(&mut self, name: &str, type_name: &str, value: Value) {
+        let start = self.scopes.last().copied().unwrap_or(0);
+        if let Some(i) = self.locals[start..].iter().position(|v| v.name == name) {
+            self.locals.remove(start + i);
+        }
+        self.locals.push(Variable {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            value,
+        });
+    }
+
+    /// Find a variable by name, locals shadowing parameters.
+    pub fn lookup(&self, name: &str) -> Option<&Variable> {
+        self.locals
+            .iter()
+            .rev()
+            .find(|v| v.name == name)
+            .or_else(|| self.parameters.iter().find(|v| v.name == name))
+    }
+
+    /// Mutable counterpart of [`StackFrame::lookup`].
+    pub fn lookup_mut(&mut self, name: &str) -> Option<&mut Variable> {
+        if let Some(idx) = self.locals.iter().rposition(|v| v.name == name) {
+            return self.locals.get_mut(idx);
+        }
+        self.parameters.iter_mut().find(|v| v.name == name)
+    }
+
+    /// Enter a block: locals declared from now on belong to it.
+    pub fn enter_scope(&mut self) {
+        self.scopes.push(self.locals.len());
+    }
+
+    /// Leave the innermost block, removing the locals declared in it and
+    /// returning them in declaration order.
+    pub fn exit_scope(&mut self) -> Vec<Variable> {
+        let Some(start) = self.scopes.pop() else {
+            return Vec::new();
+        };
+        self.locals.split_off(start.min(self.locals.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locals_shadow_parameters() {
+        let mut frame = StackFrame::new("Dog.bark", Some("Dog".into()), Some(1));
+        frame.bind_parameter("n", "int", Value::Int(1));
+        frame.declare_local("n", "int", Value::Int(2));
+        assert_eq!(frame.lookup("n").unwrap().value, Value::Int(2));
+    }
+
+    #[test]
+    fn block_locals_end_with_their_block() {
+        let mut frame = StackFrame::new("main", None, None);
+        frame.declare_local("total", "int", Value::Int(0));
+        frame.enter_scope();
+        frame.declare_local("x", "int", Value::Int(1));
+        frame.declare_local("x", "int", Value::Int(2));
+        assert_eq!(frame.locals.len(), 2, "same block: the slot is replaced");
+        let dropped = frame.exit_scope();
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].value, Value::Int(2));
+        assert!(frame.lookup("x").is_none());
+
+        // a sibling block can reuse the name
+        frame.enter_scope();
+        frame.declare_local("x", "String", Value::Null);
+        assert_eq!(frame.lookup("x").unwrap().type_name, "String");
+        frame.exit_scope();
+        assert_eq!(frame.locals.len(), 1);
+        assert!(frame.exit_scope().is_empty(), "no block left to leave");
+    }
+
+    #[test]
+    fn lookup_mut_updates_parameter() {
+        let mut frame = StackFrame::new("main", None, None);
+        frame.bind_parameter("d", "Dog", Value::Ref(3));
+        frame.lookup_mut("d").unwrap().value = Value::Null;
+        assert_eq!(frame.lookup("d").unwrap().value, Value::Null);
+        assert!(frame.lookup("missing").is_none());
+    }
+}
@@ -0,0 +1,501 @@
+use super::evaluator::Value;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Stable identity of an object allocated during the trace.
+/// Ids are handed out in allocation order starting at 1.
+pub type ObjectId = usize;
+
+/// Storage for one instance field. A subclass that redeclares an inherited
+/// field name gets a second slot, so slots are told apart by declaring class.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FieldSlot {
+    /// Class whose declaration this slot belongs to
+    pub declaring_class: String,
+    /// Name of the field
+    pub name: String,
+    /// Current value; references hold the id of a heap object
+    pub value: Value,
+}
+
+/// One object living on the simulated heap.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HeapObject {
+    /// Identity of the object, unique for the whole trace
+    pub id: ObjectId,
+    /// Runtime class the object was instantiated from
+    pub class_name: String,
+    /// Instance fields in the order they were first written; inherited
+    /// fields come first since defaults are laid out superclass first
+    pub fields: Vec<FieldSlot>,
+    /// Indexed slots when the object is an array (`class_name` is then the
+    /// array type, e.g. `int[]`); `None` for ordinary instances
+    pub elements: Option<Vec<Value>>,
+    /// Characters of a `String` object; `None` for every other class
+    pub text: Option<String>,
+    /// Whether this is the String pool's instance for its contents, i.e.
+    /// the object every evaluation of an equal string literal refers to
+    pub interned: bool,
+}
+
+impl HeapObject {
+    /// Slot that `field_name` names from the object's runtime class: the
+    /// most derived declaration when a field is hidden.
+    pub fn slot(&self, field_name: &str) -> Option<&FieldSlot> {
+        self.fields
+            .iter()
+            .rev()
+            .find(|slot| slot.name == field_name)
+    }
+
+    /// Current value of `field_name` as seen from the runtime class, if it
+    /// has been written.
+    pub fn field(&self, field_name: &str) -> Option<&Value> {
+        self.slot(field_name).map(|slot| &slot.value)
+    }
+
+    /// Current value of the field `field_name` declared in `declaring_class`.
+    pub fn field_in(&self, declaring_class: &str, field_name: &str) -> Option<&Value> {
+        self.fields
+            .iter()
+            .find(|slot| slot.declaring_class == declaring_class && slot.name == field_name)
+            .map(|slot| &slot.value)
+    }
+
+    /// Whether the object is an array.
+    pub fn is_array(&self) -> bool {
+        self.elements.is_some()
+    }
+
+    /// Contents of the object if it is a `String`.
+    pub fn string_value(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+}
+
+/// Static fields of one class. They belong to the class rather than to an
+/// instance, so every object of the class sees the same slots.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClassStatics {
+    /// Class declaring the fields
+    pub class_name: String,
+    /// Static fields in the order they were first written
+    pub fields: Vec<FieldSlot>,
+}
+
+/// Object store used by the [`super::ExecutionAnalyzer`].
+/// Every `new` allocates a fresh [`HeapObject`]; objects are never moved,
+/// so an [`ObjectId`] stays valid until the object is collected, and ids
+/// are not reused.
+#[derive(Debug, Clone, Default)]
+pub struct Heap {
+    objects: BTreeMap<ObjectId, HeapObject>,
+    next_id: ObjectId,
+    /// String pool: contents of each interned string -> its object
+    pool: BTreeMap<String, ObjectId>,
+    /// Static fields of each initialized class, in initialization order
+    statics: Vec<ClassStatics>,
+}
+
+impl Heap {
+    /// Create an empty heap.
+    pub fn new() -> Self {
+        Heap {
+            objects: BTreeMap::new(),
+            next_id: 1,
+            pool: BTreeMap::new(),
+            statics: Vec::new(),
+        }
+    }
+
+    /// Allocate a new instance of `class_name` with no fields set.
+    pub fn allocate(&mut self, class_name: &str) -> ObjectId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.objects.insert(
+            id,
+            HeapObject {
+                id,
+                class_name: class_name.to_string(),
+                fields: Vec::new(),
+                elements: None,
+                text: None,
+                interned: false,
+            },
+        );
+        id
+    }
+
+    /// Allocate an array of `element_type` holding `elements`.
+    pub fn allocate_array(&mut self, element_type: &str, elements: Vec<Value>) -> ObjectId {
+        let id = self.allocate(&format!("{}[]", element_type));
+        if let Some(obj) = self.objects.get_mut(&id) {
+            obj.elements = Some(elements);
+        }
+        id
+    }
+
+    /// Allocate a `String` object holding `text`. Strings are immutable, so
+    /// the contents are never changed afterwards.
+    pub fn allocate_string(&mut self, text: &str) -> ObjectId {
+        let id = self.allocate("String");
+        if let Some(obj) = self.objects.get_mut(&id) {
+            obj.text = Some(text.to_string());
+        }
+        id
+    }
+
+    /// Pooled `String` object holding `text`, allocating and pooling it on
+    /// first use, the way the JVM interns string literals.
+    pub fn intern(&mut self, text: &str) -> ObjectId {
+        if let Some(id) = self.interned(text) {
+            return id;
+        }
+        let id = self.allocate_string(text);
+        if let Some(obj) = self.objects.get_mut(&id) {
+            obj.interned = true;
+        }
+        self.pool.insert(text.to_string(), id);
+        id
+    }
+
+    /// Pooled `String` object holding `text`, if it has been interned.
+    pub fn interned(&self, text: &str) -> Option<ObjectId> {
+        self.pool.get(text).copied()
+    }
+
+    /// Look up an object by id.
+    pub fn get(&self, id: ObjectId) -> Option<&HeapObject> {
+        self.objects.get(&id)
+    }
+
+    /// Read the field `field_name` declared in `declaring_class` of the
+    /// object `id`. Returns `None` if either the object or the field does
+    /// not exist.
+    pub fn get_field(
+        &self,
+        id: ObjectId,
+        declaring_class: &str,
+        field_name: &str,
+    ) -> Option<&Value> {
+        self.objects
+            .get(&id)
+            .and_then(|obj| obj.field_in(declaring_class, field_name))
+    }
+
+    /// Write the field `field_name` declared in `declaring_class` of the
+    /// object `id`, returning the previous value. Writes to unknown objects
+    /// are ignored.
+    pub fn set_field(
+        &mut self,
+        id: ObjectId,
+        declaring_class: &str,
+        field_name: &str,
+        value: Value,
+    ) -> Option<Value> {
+        let obj = self.objects.get_mut(&id)?;
+        if let Some(slot) = obj
+            .fields
+            .iter_mut()
+            .find(|slot| slot.declaring_class == declaring_class && slot.name == field_name)
+        {
+            Some(std::mem::replace(&mut slot.value, value))
+        } else {
+            obj.fields.push(FieldSlot {
+                declaring_class: declaring_class.to_string(),
+                name: field_name.to_string(),
+                value,
+            });
+            None
+        }
+    }
+
+    /// Read element `index` of the array `id`. Returns `None` if `id` is not
+    /// an array or the index is out of bounds.
+    pub fn get_element(&self, id: ObjectId, index: usize) -> Option<&Value> {
+        self.objects
+            .get(&id)
+            .and_then(|obj| obj.elements.as_ref())
+            .and_then(|elements| elements.get(index))
+    }
+
+    /// Write element `index` of the array `id`, returning the previous
+    /// value. Returns `None`, writing nothing, if `id` is not an array or
+    /// the index is out of bounds.
+    pub fn set_element(&mut self, id: ObjectId, index: usize, value: Value) -> Option<Value> {
+        let slot = self
+            .objects
+            .get_mut(&id)
+            .and_then(|obj| obj.elements.as_mut())
+            .and_then(|elements| elements.get_mut(index))?;
+        Some(std::mem::replace(slot, value))
+    }
+
+    /// Replace all the slots of the array `id`, changing its length. Java
+    /// arrays can't be resized; this is for the backing arrays of modeled
+    /// collections, which are shown at the size of their contents.
+    pub fn set_elements(&mut self, id: ObjectId, elements: Vec<Value>) {
+        if let Some(slots) = self
+            .objects
+            .get_mut(&id)
+            .and_then(|obj| obj.elements.as_mut())
+        {
+            *slots = elements;
+        }
+    }
+
+    /// Length of the array `id`, or `None` if it is not an array.
+    pub fn array_length(&self, id: ObjectId) -> Option<usize> {
+        self.objects
+            .get(&id)
+            .and_then(|obj| obj.elements.as_ref())
+            .map(Vec::len)
+    }
+
+    /// Give `class_name` its (empty) static area as its initialization
+    /// starts. Returns `false` if it already has one: a class is
+    /// initialized once, however many times it is used.
+    pub fn initialize_class(&mut self, class_name: &str) -> bool {
+        if self.is_initialized(class_name) {
+            return false;
+        }
+        self.statics.push(ClassStatics {
+            class_name: class_name.to_string(),
+            fields: Vec::new(),
+        });
+        true
+    }
+
+    /// Whether `class_name` has been initialized, or is being initialized.
+    pub fn is_initialized(&self, class_name: &str) -> bool {
+        self.statics.iter().any(|c| c.class_name == class_name)
+    }
+
+    /// Read the static field `field_name` of `class_name`. Returns `None`
+    /// if the class isn't initialized or the field hasn't been written.
+    pub fn get_static(&self, class_name: &str, field_name: &str) -> Option<&Value> {
+        self.statics
+            .iter()
+            .find(|c| c.class_name == class_name)?
+            .fields
+            .iter()
+            .find(|slot| slot.name == field_name)
+            .map(|slot| &slot.value)
+    }
+
+    /// Write the static field `field_name` of `class_name`, returning the
+    /// previous value. Writes to classes that aren't initialized are ignored.
+    pub fn set_static(
+        &mut self,
+        class_name: &str,
+        field_name: &str,
+        value: Value,
+    ) -> Option<Value> {
+        let statics = self
+            .statics
+            .iter_mut()
+            .find(|c| c.class_name == class_name)?;
+        if let Some(slot) = statics.fields.iter_mut().find(|s| s.name == field_name) {
+            Some(std::mem::replace(&mut slot.value, value))
+        } else {
+            statics.fields.push(FieldSlot {
+                declaring_class: class_name.to_string(),
+                name: field_name.to_string(),
+                value,
+            });
+            None
+        }
+    }
+
+    /// Copy of the static fields of every initialized class that has any,
+    /// in initialization order.
+    pub fn statics_snapshot(&self) -> Vec<ClassStatics> {
+        self.statics
+            .iter()
+            .filter(|c| !c.fields.is_empty())
+            .cloned()
+            .collect()
+    }
+
+    /// Objects that can't be reached from `roots` (the references held on
+    /// the stack), the static fields or the String pool, ordered by id.
+    pub fn unreachable(&self, roots: impl IntoIterator<Item = ObjectId>) -> Vec<ObjectId> {
+        let statics = self.statics.iter().flat_map(|c| &c.fields);
+        let mut pending: Vec<ObjectId> = roots
+            .into_iter()
+            .chain(statics.filter_map(|slot| slot.value.as_object()))
+            .chain(self.pool.values().copied())
+            .collect();
+        let mut reached = BTreeSet::new();
+        while let Some(id) = pending.pop() {
+            let Some(obj) = self.objects.get(&id) else {
+                continue;
+            };
+            if !reached.insert(id) {
+                continue;
+            }
+            let fields = obj.fields.iter().map(|slot| &slot.value);
+            let elements = obj.elements.iter().flatten();
+            pending.extend(fields.chain(elements).filter_map(Value::as_object));
+        }
+        self.objects
+            .keys()
+            .filter(|id| !reached.contains(id))
+            .copied()
+            .collect()
+    }
+
+    /// Remove the object `id`, as the garbage collector reclaims it.
+    pub fn free(&mut self, id: ObjectId) {
+        self.objects.remove(&id);
+    }
+
+    /// Copy of every object currently on the heap, ordered by id.
+    pub fn snapshot(&self) -> Vec<HeapObject> {
+        self.objects.values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allocate_hands_out_increasing_ids() {
+        let mut heap = Heap::new();
+        let a = heap.allocate("Dog");
+        let b = heap.allocate("Dog");
+        assert_eq!(a, 1);
+        assert_eq!(b, 2);
+        assert_eq!(heap.get(b).unwrap().class_name, "Dog");
+    }
+
+    #[test]
+    fn fields_are_stored_per_object() {
+        let mut heap = Heap::new();
+        let a = heap.allocate("Dog");
+        let b = heap.allocate("Dog");
+        heap.set_field(a, "Dog", "age", Value::Int(5));
+        heap.set_field(b, "Dog", "age", Value::Int(10));
+        assert_eq!(heap.get_field(a, "Dog", "age"), Some(&Value::Int(5)));
+        assert_eq!(heap.get_field(b, "Dog", "age"), Some(&Value::Int(10)));
+    }
+
+    #[test]
+    fn set_field_returns_previous_value() {
+        let mut heap = Heap::new();
+        let a = heap.allocate("Counter");
+        assert_eq!(heap.set_field(a, "Counter", "count", Value::Int(0)), None);
+        assert_eq!(
+            heap.set_field(a, "Counter", "count", Value::Int(1)),
+            Some(Value::Int(0))
+        );
+    }
+
+    #[test]
+    fn hidden_fields_get_separate_slots() {
+        let mut heap = Heap::new();
+        let d = heap.allocate("Dog");
+        heap.set_field(d, "Animal", "name", Value::Str("animal".into()));
+        heap.set_field(d, "Dog", "name", Value::Str("dog".into()));
+        let obj = heap.get(d).unwrap();
+        assert_eq!(obj.fields.len(), 2);
+        assert_eq!(obj.field("name"), Some(&Value::Str("dog".into())));
+        assert_eq!(
+            obj.field_in("Animal", "name"),
+            Some(&Value::Str("animal".into()))
+        );
+    }
+
+    #[test]
+    fn arrays_store_indexed_elements() {
+        let mut heap = Heap::new();
+        let a = heap.allocate_array("int", vec![Value::Int(0); 3]);
+        assert_eq!(heap.get(a).unwrap().class_name, "int[]");
+        assert_eq!(heap.array_length(a), Some(3));
+        assert_eq!(heap.set_element(a, 1, Value::Int(7)), Some(Value::Int(0)));
+        assert_eq!(heap.get_element(a, 1), Some(&Value::Int(7)));
+        // Out-of-bounds writes are refused
+        assert_eq!(heap.set_element(a, 3, Value::Int(1)), None);
+        assert_eq!(heap.array_length(a), Some(3));
+
+        let d = heap.allocate("Dog");
+        assert!(!heap.get(d).unwrap().is_array());
+        assert_eq!(heap.array_length(d), None);
+    }
+
+    #[test]
+    fn strings_are_objects_with_contents() {
+        let mut heap = Heap::new();
+        let a = heap.allocate_string("Rex");
+        let b = heap.allocate_string("Rex");
+        assert_ne!(a, b, "every allocation is a distinct object");
+        let obj = heap.get(a).unwrap();
+        assert_eq!(obj.class_name, "String");
+        assert_eq!(obj.string_value(), Some("Rex"));
+        assert_eq!(heap.allocate("Dog"), 3);
+        assert_eq!(heap.get(3).unwrap().string_value(), None);
+    }
+
+    #[test]
+    fn static_fields_live_apart_from_instances() {
+        let mut heap = Heap::new();
+        let d = heap.allocate("Dog");
+        assert_eq!(heap.set_static("Dog", "count", Value::Int(1)), None);
+        assert_eq!(
+            heap.get_static("Dog", "count"),
+            None,
+            "Dog isn't initialized"
+        );
+
+        assert!(heap.initialize_class("Dog"));
+        assert!(!heap.initialize_class("Dog"));
+        assert!(heap.initialize_class("Main"));
+        heap.set_static("Dog", "count", Value::Int(0));
+        assert_eq!(
+            heap.set_static("Dog", "count", Value::Int(1)),
+            Some(Value::Int(0))
+        );
+        assert_eq!(heap.get_static("Dog", "count"), Some(&Value::Int(1)));
+        assert!(heap.get(d).unwrap().fields.is_empty());
+        let snapshot = heap.statics_snapshot();
+        assert_eq!(snapshot.len(), 1, "Main has no static fields to show");
+        assert_eq!(snapshot[0].class_name, "Dog");
+    }
+
+    #[test]
+    fn reachability_follows_fields_elements_and_statics() {
+        let mut heap = Heap::new();
+        let owner = heap.allocate("Person");
+        let pet = heap.allocate("Dog");
+        let stray = heap.allocate("Dog");
+        let array = heap.allocate_array("Dog", vec![Value::Ref(stray), Value::Null]);
+        let shared = heap.allocate("Dog");
+        let pooled = heap.intern("Rex");
+        heap.set_field(owner, "Person", "pet", Value::Ref(pet));
+        heap.initialize_class("Dog");
+        heap.set_static("Dog", "first", Value::Ref(shared));
+
+        assert_eq!(heap.unreachable([owner]), [stray, array]);
+        assert_eq!(heap.unreachable([array]), [owner, pet]);
+        assert!(!heap.unreachable([]).contains(&pooled));
+
+        heap.free(pet);
+        assert!(heap.get(pet).is_none());
+        assert_eq!(heap.unreachable([owner, array]), Vec::<ObjectId>::new());
+    }
+
+    #[test]
+    fn interning_reuses_the_pooled_object() {
+        let mut heap = Heap::new();
+        let pooled = heap.intern("hi");
+        assert_eq!(heap.intern("hi"), pooled);
+        assert_eq!(heap.interned("hi"), Some(pooled));
+        assert!(heap.get(pooled).unwrap().interned);
+
+        let fresh = heap.allocate_string("hi");
+        assert_ne!(fresh, pooled);
+        assert!(!heap.get(fresh).unwrap().interned);
+        assert_eq!(heap.interned("bye"), None);
+    }
+}
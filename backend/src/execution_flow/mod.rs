@@ -1,8 +1,18 @@
+mod collections;
 pub mod evaluator;
+mod exceptions;
 mod execution_analyzer;
 mod execution_graph_generator;
+pub mod frame;
+pub mod heap;
+mod scanner;
+mod strings;
 pub use evaluator::{FlowSignal, Value};
-pub use execution_analyzer::{ExecutionAction, ExecutionAnalyzer, ExecutionFlow, MethodBodyMap};
+pub use execution_analyzer::{
+    ExecutionAction, ExecutionAnalyzer, ExecutionFlow, ExecutionStep, MethodBodyMap,
+};
 pub use execution_graph_generator::{
     ExecutionGraphConfig, ExecutionGraphGenerator, ExecutionGraphStep,
 };
+pub use frame::{StackFrame, Variable};
+pub use heap::{ClassStatics, FieldSlot, Heap, HeapObject, ObjectId};
@@ -0,0 +1,189 @@
+use super::evaluator::Value;
+
+/// Token reads modeled for `java.util.Scanner`, besides `nextLine`.
+const TOKEN_METHODS: &[&str] = &[
+    "next",
+    "nextInt",
+    "nextLong",
+    "nextShort",
+    "nextByte",
+    "nextDouble",
+    "nextFloat",
+    "nextBoolean",
+];
+
+/// Exception thrown by a `Scanner` read; `message` is `None` where the
+/// JDK throws it without one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanFault {
+    pub exception_class: &'static str,
+    pub message: Option<&'static str>,
+}
+
+impl ScanFault {
+    fn no_such_element(message: Option<&'static str>) -> Self {
+        ScanFault {
+            exception_class: "NoSuchElementException",
+            message,
+        }
+    }
+}
+
+/// Standard input supplied for a trace and read through a `Scanner` on
+/// `System.in`. As with the real class, token reads skip leading whitespace
+/// and leave the line terminator after the token unread, so a `nextLine()`
+/// right after `nextInt()` returns the rest of that line.
+#[derive(Debug, Clone)]
+pub struct Stdin {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Stdin {
+    pub fn new(text: &str) -> Self {
+        Stdin {
+            chars: text.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    /// Result of the `Scanner` read `method` (one [`Stdin::is_read`]
+    /// accepts), consuming what it reads. Reads past the end of the input
+    /// throw `NoSuchElementException`, tokens that don't scan as the type
+    /// read `InputMismatchException`; both leave the input where it was.
+    pub fn read(&mut self, method: &str) -> Result<Value, ScanFault> {
+        if method == "nextLine" {
+            let rest = self
+                .chars
+                .get(self.pos..)
+                .filter(|rest| !rest.is_empty())
+                .ok_or(ScanFault::no_such_element(Some("No line found")))?;
+            let len = rest.iter().position(|&c| c == '\n').unwrap_or(rest.len());
+            let line: String = rest[..len].iter().collect();
+            self.pos += (len + 1).min(rest.len());
+            return Ok(Value::Str(line.trim_end_matches('\r').to_string()));
+        }
+        let (token, end) = self.next_token().ok_or(ScanFault::no_such_element(None))?;
+        let value = parse_token(method, &token).ok_or(ScanFault {
+            exception_class: "InputMismatchException",
+            message: None,
+        })?;
+        self.pos = end;
+        Ok(value)
+    }
+
+    /// Result of the `hasNext*` method `method`, which only looks ahead.
+    /// Returns `None` for methods that aren't modeled.
+    pub fn peek(&self, method: &str) -> Option<Value> {
+        let available = match method {
+            "hasNextLine" => self.pos < self.chars.len(),
+            "hasNext" => self.next_token().is_some(),
+            _ => {
+                let read = format!("n{}", method.strip_prefix("hasN")?);
+                if !TOKEN_METHODS.contains(&read.as_str()) {
+                    return None;
+                }
+                self.next_token()
+                    .is_some_and(|(token, _)| parse_token(&read, &token).is_some())
+            }
+        };
+        Some(Value::Bool(available))
+    }
+
+    /// Whether `method` consumes input.
+    pub fn is_read(method: &str) -> bool {
+        method == "nextLine" || TOKEN_METHODS.contains(&method)
+    }
+
+    /// The next whitespace-delimited token and the position just past it.
+    fn next_token(&self) -> Option<(String, usize)> {
+        let rest = self.chars.get(self.pos..)?;
+        let start = self.pos + rest.iter().position(|c| !c.is_whitespace())?;
+        let end = self.chars[start..]
+            .iter()
+            .position(|c| c.is_whitespace())
+            .map_or(self.chars.len(), |len| start + len);
+        Some((self.chars[start..end].iter().collect(), end))
+    }
+}
+
+/// Value of `token` as read by the token method `method`, `None` if it
+/// doesn't scan as that type.
+fn parse_token(method: &str, token: &str) -> Option<Value> {
+    match method {
+        "next" => Some(Value::Str(token.to_string())),
+        "nextInt" => token.parse::<i32>().ok().map(Value::Int),
+        "nextLong" => token.parse::<i64>().ok().map(Value::Long),
+        "nextShort" => token.parse::<i16>().ok().map(Value::Short),
+        "nextByte" => token.parse::<i8>().ok().map(Value::Byte),
+        "nextDouble" => token.parse::<f64>().ok().map(Value::Double),
+        "nextFloat" => token.parse::<f32>().ok().map(Value::Float),
+        "nextBoolean" => match token.to_ascii_lowercase().as_str() {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_skip_whitespace_across_lines() {
+        let mut stdin = Stdin::new("  3 4\n\n  hello 2.5\n");
+        assert_eq!(stdin.read("nextInt"), Ok(Value::Int(3)));
+        assert_eq!(stdin.read("nextInt"), Ok(Value::Int(4)));
+        assert_eq!(stdin.read("next"), Ok(Value::Str("hello".into())));
+        assert_eq!(stdin.read("nextDouble"), Ok(Value::Double(2.5)));
+        assert_eq!(
+            stdin.read("next"),
+            Err(ScanFault::no_such_element(None)),
+            "input is exhausted"
+        );
+    }
+
+    #[test]
+    fn next_line_after_a_token_returns_the_rest_of_its_line() {
+        let mut stdin = Stdin::new("42\nAda Lovelace\r\n");
+        assert_eq!(stdin.read("nextInt"), Ok(Value::Int(42)));
+        assert_eq!(stdin.read("nextLine"), Ok(Value::Str("".into())));
+        assert_eq!(
+            stdin.read("nextLine"),
+            Ok(Value::Str("Ada Lovelace".into()))
+        );
+        assert_eq!(
+            stdin.read("nextLine"),
+            Err(ScanFault::no_such_element(Some("No line found")))
+        );
+    }
+
+    #[test]
+    fn mismatched_reads_leave_the_input_in_place() {
+        let mut stdin = Stdin::new("abc 7");
+        let mismatch = Err(ScanFault {
+            exception_class: "InputMismatchException",
+            message: None,
+        });
+        assert_eq!(stdin.read("nextInt"), mismatch);
+        assert_eq!(stdin.read("next"), Ok(Value::Str("abc".into())));
+        assert_eq!(stdin.read("nextInt"), Ok(Value::Int(7)));
+        assert_eq!(Stdin::new("3000000000").read("nextInt"), mismatch);
+    }
+
+    #[test]
+    fn has_next_looks_ahead_without_consuming() {
+        let stdin = Stdin::new("5 x");
+        assert_eq!(stdin.peek("hasNextInt"), Some(Value::Bool(true)));
+        assert_eq!(stdin.peek("hasNextInt"), Some(Value::Bool(true)));
+        assert_eq!(stdin.peek("hasNextBoolean"), Some(Value::Bool(false)));
+        assert_eq!(stdin.peek("hasNextLine"), Some(Value::Bool(true)));
+        assert_eq!(stdin.peek("close"), None);
+
+        let empty = Stdin::new("   \n");
+        assert_eq!(empty.peek("hasNext"), Some(Value::Bool(false)));
+        assert_eq!(empty.peek("hasNextLine"), Some(Value::Bool(true)));
+    }
+}
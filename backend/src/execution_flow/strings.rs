@@ -0,0 +1,199 @@
+use super::evaluator::Value;
+
+/// Result of calling the `java.lang.String` method `method` on a string
+/// with contents `text`. The JDK source isn't analyzed, so the common
+/// methods are modeled here instead of being stepped into. Characters are
+/// indexed by Unicode scalar value, which matches Java's UTF-16 indexing
+/// outside the supplementary planes.
+///
+/// String arguments are expected as [`Value::Str`]; a `char` argument may
+/// be a [`Value::Char`] or an `int` code point, as Java accepts both.
+/// Returns `None` for methods that aren't modeled, for unknown arguments,
+/// and for calls that would throw (e.g. `charAt` out of bounds).
+pub fn call_string_method(text: &str, method: &str, args: &[Value]) -> Option<Value> {
+    let chars: Vec<char> = text.chars().collect();
+    let index = |value: &Value| usize::try_from(value.as_int()?).ok();
+
+    let result = match (method, args) {
+        ("length", []) => Value::Int(chars.len() as i32),
+        ("isEmpty", []) => Value::Bool(chars.is_empty()),
+        ("charAt", [i]) => Value::Char(*chars.get(index(i)?)? as u16),
+        ("substring", [begin]) => {
+            let begin = index(begin)?;
+            Value::Str(chars.get(begin..)?.iter().collect())
+        }
+        ("substring", [begin, end]) => {
+            let (begin, end) = (index(begin)?, index(end)?);
+            Value::Str(chars.get(begin..end)?.iter().collect())
+        }
+        ("equals", [other]) => match other {
+            Value::Str(other) => Value::Bool(text == other),
+            Value::Unknown => return None,
+            _ => Value::Bool(false),
+        },
+        ("equalsIgnoreCase", [other]) => match other {
+            Value::Str(other) => Value::Bool(text.to_lowercase() == other.to_lowercase()),
+            Value::Unknown => return None,
+            _ => Value::Bool(false),
+        },
+        ("compareTo", [Value::Str(other)]) => Value::Int(compare_to(&chars, other)),
+        ("contains", [Value::Str(part)]) => Value::Bool(text.contains(part.as_str())),
+        ("startsWith", [Value::Str(prefix)]) => Value::Bool(text.starts_with(prefix.as_str())),
+        ("endsWith", [Value::Str(suffix)]) => Value::Bool(text.ends_with(suffix.as_str())),
+        ("indexOf", [needle]) => Value::Int(index_of(&chars, &needle_chars(needle)?)),
+        ("lastIndexOf", [needle]) => Value::Int(last_index_of(&chars, &needle_chars(needle)?)),
+        ("toUpperCase", []) => Value::Str(text.to_uppercase()),
+        ("toLowerCase", []) => Value::Str(text.to_lowercase()),
+        ("trim", []) => Value::Str(text.trim_matches(|c: char| c <= ' ').to_string()),
+        ("concat", [Value::Str(other)]) => Value::Str(format!("{text}{other}")),
+        ("replace", [from, to]) => {
+            let from: String = needle_chars(from)?.into_iter().collect();
+            let to: String = needle_chars(to)?.into_iter().collect();
+            Value::Str(text.replace(&from, &to))
+        }
+        ("repeat", [count]) => Value::Str(text.repeat(index(count)?)),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Characters searched for by `indexOf`/`replace`: a string, or a single
+/// `char`, possibly given as its code point.
+fn needle_chars(value: &Value) -> Option<Vec<char>> {
+    match value {
+        Value::Str(s) => Some(s.chars().collect()),
+        Value::Char(_) | Value::Int(_) => {
+            Some(vec![char::from_u32(u32::try_from(value.as_int()?).ok()?)?])
+        }
+        _ => None,
+    }
+}
+
+fn index_of(haystack: &[char], needle: &[char]) -> i32 {
+    if needle.is_empty() {
+        return 0;
+    }
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map_or(-1, |i| i as i32)
+}
+
+fn last_index_of(haystack: &[char], needle: &[char]) -> i32 {
+    if needle.is_empty() {
+        return haystack.len() as i32;
+    }
+    haystack
+        .windows(needle.len())
+        .rposition(|window| window == needle)
+        .map_or(-1, |i| i as i32)
+}
+
+/// `String.compareTo`: the difference of the first mismatching characters,
+/// or of the lengths when one string is a prefix of the other.
+fn compare_to(chars: &[char], other: &str) -> i32 {
+    let other: Vec<char> = other.chars().collect();
+    chars
+        .iter()
+        .zip(&other)
+        .find(|(a, b)| a != b)
+        .map(|(a, b)| *a as i32 - *b as i32)
+        .unwrap_or(chars.len() as i32 - other.len() as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(text: &str, method: &str, args: &[Value]) -> Option<Value> {
+        call_string_method(text, method, args)
+    }
+
+    #[test]
+    fn length_and_char_at() {
+        assert_eq!(call("Rex", "length", &[]), Some(Value::Int(3)));
+        assert_eq!(
+            call("Rex", "charAt", &[Value::Int(1)]),
+            Some(Value::Char('e' as u16))
+        );
+        assert_eq!(call("Rex", "charAt", &[Value::Int(3)]), None);
+    }
+
+    #[test]
+    fn substring_uses_half_open_range() {
+        assert_eq!(
+            call("hello", "substring", &[Value::Int(1), Value::Int(3)]),
+            Some(Value::Str("el".into()))
+        );
+        assert_eq!(
+            call("hello", "substring", &[Value::Int(5)]),
+            Some(Value::Str("".into()))
+        );
+        assert_eq!(
+            call("hello", "substring", &[Value::Int(3), Value::Int(2)]),
+            None
+        );
+    }
+
+    #[test]
+    fn equals_compares_contents() {
+        assert_eq!(
+            call("Rex", "equals", &[Value::Str("Rex".into())]),
+            Some(Value::Bool(true))
+        );
+        assert_eq!(
+            call("Rex", "equals", &[Value::Null]),
+            Some(Value::Bool(false))
+        );
+        assert_eq!(
+            call("Rex", "equalsIgnoreCase", &[Value::Str("rEX".into())]),
+            Some(Value::Bool(true))
+        );
+        assert_eq!(call("Rex", "equals", &[Value::Unknown]), None);
+    }
+
+    #[test]
+    fn compare_to_follows_java() {
+        let cmp = |a: &str, b: &str| call(a, "compareTo", &[Value::Str(b.into())]);
+        assert_eq!(
+            cmp("apple", "apricot"),
+            Some(Value::Int('p' as i32 - 'r' as i32))
+        );
+        assert_eq!(cmp("ab", "abcd"), Some(Value::Int(-2)));
+        assert_eq!(cmp("same", "same"), Some(Value::Int(0)));
+    }
+
+    #[test]
+    fn searching_accepts_strings_and_chars() {
+        assert_eq!(
+            call("banana", "indexOf", &[Value::Str("an".into())]),
+            Some(Value::Int(1))
+        );
+        assert_eq!(
+            call("banana", "lastIndexOf", &[Value::Char('a' as u16)]),
+            Some(Value::Int(5))
+        );
+        assert_eq!(
+            call("banana", "indexOf", &[Value::Str("x".into())]),
+            Some(Value::Int(-1))
+        );
+    }
+
+    #[test]
+    fn transformations_return_new_strings() {
+        assert_eq!(
+            call("Rex", "toUpperCase", &[]),
+            Some(Value::Str("REX".into()))
+        );
+        assert_eq!(call("  hi ", "trim", &[]), Some(Value::Str("hi".into())));
+        assert_eq!(
+            call(
+                "a-b",
+                "replace",
+                &[Value::Char('-' as u16), Value::Char('+' as u16)]
+            ),
+            Some(Value::Str("a+b".into()))
+        );
+        assert_eq!(call("Rex", "hashCode", &[]), None);
+    }
+}
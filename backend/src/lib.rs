@@ -19,13 +19,14 @@ use parser::JavaParser;
 /// Generate a sequence of DOT graphs, one per execution step.
 /// Each element of the returned vector is a standalone DOT document
 /// describing the memory/call-stack state at that step.
+/// `stdin` is the input read by the program's `Scanner`s, if any.
 /// Returns an empty vector if the code fails to parse or analyze.
-pub fn execution_flow_gen(java_code: &str) -> Vec<String> {
+pub fn execution_flow_gen(java_code: &str, stdin: Option<&str>) -> Vec<String> {
     let mut visualizer = match visualizer::JavaVisualizer::new() {
         Ok(v) => v,
         Err(_) => return vec![],
     };
-    let result = match visualizer.analyze_execution_flow(java_code) {
+    let result = match visualizer.analyze_execution_flow(java_code, stdin) {
         Ok(r) => r,
         Err(_) => return vec![],
     };
@@ -61,8 +62,8 @@ pub fn no_flow_gen(java_code: &str) -> String {
 /*
 // WASM-compatible exports
 #[wasm_bindgen]
-pub fn wasm_execution_flow_gen(java_code: &str) -> String {
-    match execution_flow_gen(java_code) {
+pub fn wasm_execution_flow_gen(java_code: &str, stdin: Option<String>) -> String {
+    match execution_flow_gen(java_code, stdin.as_deref()) {
         vec => serde_json::to_string(&vec).unwrap_or_else(|e| format!("Error serializing: {}", e)),
     }
 }
@@ -92,19 +93,28 @@ fn to_c_string(s: String) -> *mut c_char {
 
 /// Emscripten-compatible wrapper around [`execution_flow_gen`].
 ///
-/// Reads a NUL-terminated C string from `ptr`, runs the analysis inside
+/// Reads a NUL-terminated C string from `ptr`, and the program's stdin
+/// from `stdin_ptr` unless it is null, runs the analysis inside
 /// [`std::panic::catch_unwind`], and returns a NUL-terminated JSON array
 /// of DOT strings. On panic or invalid UTF-8, returns `"[]"`.
 /// Ownership of the returned pointer is transferred to the caller, who
 /// must free it.
 #[unsafe(no_mangle)]
 #[allow(clippy::missing_safety_doc)]
-pub unsafe extern "C" fn wasm_execution_flow_gen(ptr: *const c_char) -> *mut c_char {
+pub unsafe extern "C" fn wasm_execution_flow_gen(
+    ptr: *const c_char,
+    stdin_ptr: *const c_char,
+) -> *mut c_char {
     let c_str = unsafe { std::ffi::CStr::from_ptr(ptr) };
     let java_code = c_str.to_str().unwrap_or("");
+    let stdin = if stdin_ptr.is_null() {
+        None
+    } else {
+        unsafe { std::ffi::CStr::from_ptr(stdin_ptr) }.to_str().ok()
+    };
 
     let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-        execution_flow_gen(java_code)
+        execution_flow_gen(java_code, stdin)
     }));
 
     let json = match result {
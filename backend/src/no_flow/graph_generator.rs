@@ -1,5 +1,5 @@
 use crate::{
-    analyzer::AnalysisResult,
+    analyzer::{AnalysisResult, EffectiveField},
     repr::{JavaClass, JavaField, JavaMethod, Relationship, RelationshipType},
 };
 
@@ -92,7 +92,7 @@ impl GraphGenerator {
         let mut body = String::new();
 
         for class in &analysis.classes {
-            body.push_str(&self.generate_class_node(class));
+            body.push_str(&self.generate_class_node(class, analysis));
             body.push('\n');
         }
 
@@ -116,7 +116,12 @@ impl GraphGenerator {
     ///   - Interfaces use `shape=diamond`.
     ///   - The HTML label arranges members with visual separation
     ///     between "interior" (private) and "border" (public) sections.
-    pub(crate) fn generate_class_node(&self, class: &JavaClass) -> String {
+    ///   - Inherited fields are listed below, grouped by declaring class.
+    pub(crate) fn generate_class_node(
+        &self,
+        class: &JavaClass,
+        analysis: &AnalysisResult,
+    ) -> String {
         let mut out = String::new();
         let safe = class.name.replace('.', "_");
 
@@ -157,6 +162,17 @@ impl GraphGenerator {
             .filter(|m| m.visibility != "private")
             .collect();
 
+        let inherited_fields: Vec<EffectiveField> = if self.config.show_fields {
+            analysis
+                .effective_fields(&class.name)
+                .into_iter()
+                .filter(|f| f.declaring_class != class.name)
+                .filter(|f| self.config.show_private_members || f.field.visibility != "private")
+                .collect()
+        } else {
+            vec![]
+        };
+
         let shape = if class.is_interface {
             "diamond"
         } else {
@@ -169,6 +185,7 @@ impl GraphGenerator {
             &public_fields,
             &private_methods,
             &public_methods,
+            &inherited_fields,
         );
 
         let peripheries = if class.fields.iter().all(|f| f.is_final) && !class.fields.is_empty() {
@@ -192,6 +209,8 @@ impl GraphGenerator {
     ///   3. Public fields   — on the "border" (separated by a line)
     ///   4. Private fields  — in the "interior"
     ///   5. Private methods — in the "interior"
+    ///   6. Inherited fields — under a header per declaring class, hidden
+    ///      ones greyed out
     fn build_html_label(
         &self,
         class: &JavaClass,
@@ -199,6 +218,7 @@ impl GraphGenerator {
         public_fields: &[&&JavaField],
         private_methods: &[&&JavaMethod],
         public_methods: &[&&JavaMethod],
+        inherited_fields: &[EffectiveField],
     ) -> String {
         let mut html = String::new();
 
@@ -248,6 +268,34 @@ impl GraphGenerator {
             ));
         }
 
+        let has_own_members = has_public || has_private;
+        let mut current_group = None;
+        for inherited in inherited_fields {
+            if current_group != Some(inherited.declaring_class) {
+                if current_group.is_none() && has_own_members {
+                    html.push_str("<HR/>");
+                }
+                current_group = Some(inherited.declaring_class);
+                html.push_str(&format!(
+                    "<TR><TD><I><FONT POINT-SIZE=\"10\">inherited from {}</FONT></I></TD></TR>",
+                    Self::escape_html(inherited.declaring_class)
+                ));
+            }
+
+            let label = self.format_field_for_diagram(inherited.field);
+            if inherited.hidden {
+                html.push_str(&format!(
+                    "<TR><TD BORDER=\"1\" BGCOLOR=\"#e0e0e0\"><FONT COLOR=\"gray40\">{} (hidden)</FONT></TD></TR>",
+                    Self::escape_html(&label)
+                ));
+            } else {
+                html.push_str(&format!(
+                    "<TR><TD BORDER=\"1\" BGCOLOR=\"lightyellow\">{}</TD></TR>",
+                    Self::escape_html(&label)
+                ));
+            }
+        }
+
         html.push_str("</TABLE>");
         html
     }
@@ -113,6 +113,44 @@ class ClassB {
 mod inheritance {
     use super::*;
 
+    #[test]
+    fn effective_fields_follow_extends_and_mark_hiding() {
+        let code = r#"
+class Animal {
+    String name;
+    int legs;
+}
+class Dog extends Animal {
+    String name;
+    boolean loyal;
+}
+class Puppy extends Dog {}
+"#;
+
+        let result = analyze_java_code(code);
+        let fields: Vec<(&str, &str, bool)> = result
+            .effective_fields("Puppy")
+            .iter()
+            .map(|f| (f.declaring_class, f.field.name.as_str(), f.hidden))
+            .collect();
+
+        assert_eq!(
+            fields,
+            vec![
+                ("Animal", "name", true),
+                ("Animal", "legs", false),
+                ("Dog", "name", false),
+                ("Dog", "loyal", false),
+            ]
+        );
+        assert_eq!(result.field_declaring_class("Puppy", "name"), Some("Dog"));
+        assert_eq!(
+            result.field_declaring_class("Puppy", "legs"),
+            Some("Animal")
+        );
+        assert_eq!(result.field_declaring_class("Animal", "loyal"), None);
+    }
+
     #[test]
     fn inheritance_relationships() {
         let code = r#"
@@ -378,6 +416,40 @@ public class Child extends Parent {}
         assert!(dot.contains("arrowhead=empty"));
         assert!(dot.contains("label=extends"));
     }
+
+    #[test]
+    fn inherited_fields_grouped_in_class_node() {
+        let code = r#"
+public class Animal {
+    protected String name;
+    protected int legs;
+}
+
+public class Dog extends Animal {
+    private String name;
+}
+"#;
+
+        let result = analyze_java_code(code);
+        let dot = generate_dot(&result);
+        let dog_node = dot
+            .lines()
+            .find(|l| l.trim_start().starts_with("\"Dog_class\" ["))
+            .expect("Dog node should be rendered");
+
+        assert!(dog_node.contains("inherited from Animal"), "{}", dog_node);
+        assert!(dog_node.contains("int legs"), "{}", dog_node);
+        assert!(
+            dog_node.contains("String name (hidden)"),
+            "Animal.name is hidden by Dog.name: {}",
+            dog_node
+        );
+        let animal_node = dot
+            .lines()
+            .find(|l| l.trim_start().starts_with("\"Animal_class\" ["))
+            .expect("Animal node should be rendered");
+        assert!(!animal_node.contains("inherited from"), "{}", animal_node);
+    }
 }
 
 #[cfg(test)]
@@ -507,8 +579,10 @@ public class TestClass {
         // Note: Whether fields are shown depends on default config
 
         // Update config to hide fields
-        let mut config = GraphConfig::default();
-        config.show_fields = false;
+        let config = GraphConfig {
+            show_fields: false,
+            ..Default::default()
+        };
         visualizer.update_config(config);
 
         let result2 = visualizer.generate_dot(code).unwrap();
@@ -533,7 +607,7 @@ public class FlowTest {
 }
 "#;
 
-        let result = execution_flow_gen(code);
+        let result = execution_flow_gen(code, None);
 
         // Verify exact structure: Vec<String> with valid DOT graphs
         assert!(!result.is_empty());
@@ -617,7 +691,7 @@ public class Calculator {
     }
 }
 "#;
-        let result = execution_flow_gen(code);
+        let result = execution_flow_gen(code, None);
 
         assert!(
             result.len() > 8,
@@ -641,7 +715,7 @@ public class Calculator {
 
         let last = result.last().unwrap();
         assert!(
-            last.contains("obj_calc"),
+            last.contains("var_calc -> obj_1"),
             "Last step should contain Calculator object in Active Objects panel"
         );
 
@@ -676,7 +750,7 @@ public class Calculator {
     }
 }
 "#;
-        let result = execution_flow_gen(code);
+        let result = execution_flow_gen(code, None);
 
         let has_value_field = result
             .iter()
@@ -689,7 +763,7 @@ public class Calculator {
         // Object ellipse should show the field's runtime value
         let has_runtime_value_on_object = result
             .iter()
-            .any(|g| g.contains("obj_calc") && g.contains("value = 0.0"));
+            .any(|g| g.contains("var_calc -> obj_1") && g.contains("value = 0.0"));
         assert!(
             has_runtime_value_on_object,
             "Some step should display the runtime value 0.0 on the calc object"
@@ -705,7 +779,7 @@ public class TestExecution {
     }
 }
 "#;
-        let result = execution_flow_gen(code);
+        let result = execution_flow_gen(code, None);
 
         for graph in &result {
             assert!(
@@ -875,7 +949,9 @@ mod property_based_tests {
 mod control_flow_tests {
     use crate::{
         analyzer::JavaAnalyzer,
-        execution_flow::{ExecutionAction, ExecutionAnalyzer, ExecutionFlow},
+        execution_flow::{
+            ExecutionAction, ExecutionAnalyzer, ExecutionFlow, ExecutionStep, ObjectId, Value,
+        },
         parser::JavaParser,
     };
 
@@ -889,6 +965,16 @@ mod control_flow_tests {
         exec.analyze_execution_flow(&root, code)
     }
 
+    fn analyze_flow_with_stdin(code: &str, stdin: &str) -> ExecutionFlow {
+        let mut parser = JavaParser::new().unwrap();
+        let tree = parser.parse(code).unwrap();
+        let root = parser.get_root_node(&tree);
+        let mut analyzer = JavaAnalyzer::new();
+        let analysis = analyzer.analyze(&root, code);
+        let mut exec = ExecutionAnalyzer::new(analysis).with_stdin(Some(stdin));
+        exec.analyze_execution_flow(&root, code)
+    }
+
     fn has_action(flow: &ExecutionFlow, pred: impl Fn(&ExecutionAction) -> bool) -> bool {
         flow.steps.iter().any(|s| pred(&s.action))
     }
@@ -897,6 +983,17 @@ mod control_flow_tests {
         flow.steps.iter().filter(|s| pred(&s.action)).count()
     }
 
+    /// Contents of the String object `value` refers to.
+    fn string_at<'a>(
+        heap: &'a [crate::execution_flow::HeapObject],
+        value: Option<&crate::execution_flow::Value>,
+    ) -> Option<&'a str> {
+        let Some(crate::execution_flow::Value::Ref(id)) = value else {
+            return None;
+        };
+        heap.iter().find(|obj| obj.id == *id)?.string_value()
+    }
+
     fn wrap_main(body: &str) -> String {
         format!(
             "public class Main {{ public static void main(String[] args) {{ {} }} }}",
@@ -1102,4 +1199,2392 @@ mod control_flow_tests {
         });
         assert_eq!(count, 4, "expected exactly 4 for-loop iterations");
     }
+
+    // ── Reference Aliasing Tests ──
+
+    #[test]
+    fn reference_assignment_copies_the_reference() {
+        let code = r#"
+public class Main {
+    public static void main(String[] args) {
+        Dog a = new Dog();
+        Dog b = a;
+        if (a == b) { int same = 1; }
+        b = null;
+    }
+}
+class Dog {}
+"#;
+        let flow = analyze_flow(code);
+        assert_eq!(
+            count_actions(&flow, |a| matches!(
+                a,
+                ExecutionAction::ObjectCreation { .. }
+            )),
+            1,
+            "Dog b = a; must not create a second object"
+        );
+        assert!(
+            has_action(&flow, |a| matches!(
+                a,
+                ExecutionAction::ReferenceAssignment { variable_name, object_id: Some(1), .. }
+                    if variable_name == "b"
+            )),
+            "expected b to be pointed at object 1"
+        );
+        assert!(
+            has_action(&flow, |a| matches!(
+                a,
+                ExecutionAction::ConditionalBranch {
+                    branch_taken: true,
+                    ..
+                }
+            )),
+            "a == b should compare identity and be true"
+        );
+        assert!(
+            has_action(&flow, |a| matches!(
+                a,
+                ExecutionAction::ReferenceAssignment { variable_name, object_id: None, .. }
+                    if variable_name == "b"
+            )),
+            "expected b = null to clear the reference"
+        );
+    }
+
+    /// Methods entered during the trace, leaving out constructor chaining.
+    fn called_methods(flow: &ExecutionFlow) -> Vec<String> {
+        flow.steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::MethodCall {
+                    method_name,
+                    target_class,
+                    ..
+                } if method_name != "<init>" => Some(format!("{}.{}", target_class, method_name)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn virtual_call_enters_override_on_runtime_class() {
+        let code = r#"
+public class Main {
+    public static void main(String[] args) {
+        Animal a = new Dog();
+        a.speak();
+    }
+}
+class Animal {
+    public void speak() { int generic = 1; }
+}
+class Dog extends Animal {
+    public void speak() { int woof = 1; }
+}
+"#;
+        let flow = analyze_flow(code);
+        assert_eq!(called_methods(&flow), vec!["Dog.speak"]);
+        assert!(
+            has_action(&flow, |a| matches!(
+                a,
+                ExecutionAction::VariableAssignment { variable_name, .. } if variable_name == "woof"
+            )),
+            "the override's body should run"
+        );
+        assert!(
+            !has_action(&flow, |a| matches!(
+                a,
+                ExecutionAction::VariableAssignment { variable_name, .. } if variable_name == "generic"
+            )),
+            "the superclass body must not run"
+        );
+    }
+
+    #[test]
+    fn inherited_method_falls_back_through_superclass_chain() {
+        let code = r#"
+public class Main {
+    public static void main(String[] args) {
+        Puppy p = new Puppy();
+        p.eat();
+    }
+}
+class Animal {
+    int meals;
+    public void eat() { meals = 1; }
+}
+class Dog extends Animal {}
+class Puppy extends Dog {}
+"#;
+        let flow = analyze_flow(code);
+        assert_eq!(called_methods(&flow), vec!["Animal.eat"]);
+        let last = flow.steps.last().unwrap();
+        assert_eq!(
+            last.frames.last().unwrap().this_object,
+            Some(1),
+            "the inherited method should still run on the Puppy"
+        );
+        assert_eq!(
+            last.heap[0].field("meals"),
+            Some(&crate::execution_flow::Value::Int(1)),
+            "the inherited field should be written on the receiver"
+        );
+    }
+
+    #[test]
+    fn super_call_runs_superclass_version_on_same_object() {
+        let code = r#"
+public class Main {
+    public static void main(String[] args) {
+        Animal a = new Dog();
+        a.speak();
+    }
+}
+class Animal {
+    public void speak() { describe(); }
+    public void describe() { int animal = 1; }
+}
+class Dog extends Animal {
+    public void speak() { super.speak(); }
+    public void describe() { int dog = 1; }
+}
+"#;
+        let flow = analyze_flow(code);
+        assert_eq!(
+            called_methods(&flow),
+            vec!["Dog.speak", "Animal.speak", "Dog.describe"],
+            "super.speak() runs Animal.speak, whose unqualified call dispatches back to Dog"
+        );
+    }
+
+    #[test]
+    fn overloads_are_matched_by_argument_count_and_type() {
+        let code = r#"
+public class Main {
+    public static void main(String[] args) {
+        Dog a = new Dog();
+        Dog b = new Dog("Rex");
+        a.feed(2);
+        a.feed(2.5);
+        a.feed("bone");
+        a.feed(b);
+        a.feed('c');
+    }
+}
+class Dog {
+    String name;
+    Dog() { name = "stray"; }
+    Dog(String n) { name = n; }
+    void feed(int n) {}
+    void feed(double d) {}
+    void feed(String s) {}
+    void feed(Object o) {}
+}
+"#;
+        let flow = analyze_flow(code);
+        let signatures: Vec<String> = flow
+            .steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::ObjectCreation { signature, .. }
+                | ExecutionAction::MethodCall { signature, .. } => signature.clone(),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            signatures,
+            vec![
+                "<init>()",
+                "<init>(String)",
+                "feed(int)",
+                "feed(double)",
+                "feed(String)",
+                "feed(Object)",
+                "feed(int)",
+            ]
+        );
+
+        let heap = &flow.steps.last().unwrap().heap;
+        let dogs: Vec<_> = heap.iter().filter(|obj| obj.class_name == "Dog").collect();
+        assert_eq!(string_at(heap, dogs[0].field("name")), Some("stray"));
+        assert_eq!(
+            string_at(heap, dogs[1].field("name")),
+            Some("Rex"),
+            "the one-argument constructor should run for new Dog(\"Rex\")"
+        );
+    }
+
+    #[test]
+    fn overloads_are_chosen_by_static_type_then_overridden_at_runtime() {
+        let code = r#"
+public class Main {
+    public static void main(String[] args) {
+        Animal a = new Dog();
+        a.eat("x");
+        Dog d = new Dog();
+        d.eat("x");
+        Animal p = new Puppy();
+        p.eat("x");
+    }
+}
+class Animal {
+    void eat(Object o) {}
+}
+class Dog extends Animal {
+    void eat(String s) {}
+}
+class Puppy extends Dog {
+    void eat(Object o) {}
+}
+"#;
+        let flow = analyze_flow(code);
+        let calls: Vec<String> = flow
+            .steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::MethodCall {
+                    target_class,
+                    signature: Some(signature),
+                    ..
+                } => Some(format!("{}.{}", target_class, signature)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            calls,
+            ["Animal.eat(Object)", "Dog.eat(String)", "Puppy.eat(Object)"],
+            "Animal has no eat(String), so Dog's overload is never a candidate through an Animal"
+        );
+    }
+
+    #[test]
+    fn static_calls_bind_to_their_class_and_defaults_come_from_interfaces() {
+        let code = r#"
+public class Main {
+    public static void main(String[] args) {
+        Dog d = new Dog();
+        String k = d.describe();
+        Greeter g = new Robot();
+        String h = g.greet();
+        Robot r = new Robot();
+        String h2 = r.greet();
+    }
+}
+class Animal {
+    static String kind() { return "animal"; }
+    String describe() { return kind(); }
+}
+class Dog extends Animal {
+    static String kind() { return "dog"; }
+}
+interface Named {}
+interface Greeter {
+    String name();
+    default String greet() { return "hello " + name(); }
+}
+class Robot implements Named, Greeter {
+    public String name() { return "robo"; }
+}
+"#;
+        let flow = analyze_flow(code);
+        assert_eq!(
+            called_methods(&flow),
+            [
+                "Animal.describe",
+                "Animal.kind",
+                "Greeter.greet",
+                "Robot.name",
+                "Greeter.greet",
+                "Robot.name"
+            ]
+        );
+        let last = flow.steps.last().unwrap();
+        let local =
+            |name: &str| string_at(&last.heap, last.frames[0].lookup(name).map(|v| &v.value));
+        assert_eq!(
+            local("k"),
+            Some("animal"),
+            "kind() is static, so Animal's runs"
+        );
+        assert_eq!(local("h"), Some("hello robo"));
+        assert_eq!(local("h2"), Some("hello robo"));
+    }
+
+    #[test]
+    fn object_initialization_follows_java_order() {
+        let code = r#"
+public class Main {
+    public static void main(String[] args) {
+        Puppy a = new Puppy("Rex");
+        Puppy b = new Puppy();
+    }
+}
+class Animal {
+    int legs = 4;
+    String name;
+    Animal(String name) { this.name = name; }
+}
+class Dog extends Animal {
+    boolean loyal;
+    { loyal = true; }
+    Dog(String name) { super(name); }
+}
+class Puppy extends Dog {
+    int age = 1;
+    Puppy() { this("pup"); }
+    Puppy(String name) { super(name); age = 0; }
+}
+"#;
+        let flow = analyze_flow(code);
+        let trace: Vec<String> = flow
+            .steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::ObjectCreation {
+                    class_name,
+                    signature,
+                    ..
+                } => Some(format!("new {} {:?}", class_name, signature)),
+                ExecutionAction::MethodCall {
+                    caller,
+                    target_class,
+                    signature,
+                    ..
+                } => Some(format!("{:?} {} {:?}", caller, target_class, signature)),
+                ExecutionAction::FieldMutation {
+                    field_name,
+                    old_value,
+                    new_value,
+                    ..
+                } => Some(format!("{} {:?} -> {}", field_name, old_value, new_value)),
+                _ => None,
+            })
+            .collect();
+
+        let first_object = [
+            r#"new Puppy Some("<init>(String)")"#,
+            r#"Some("super") Dog Some("<init>(String)")"#,
+            r#"Some("super") Animal Some("<init>(String)")"#,
+            r#"legs Some("0") -> 4"#,
+            r#"name Some("null") -> "Rex""#,
+            r#"loyal Some("false") -> true"#,
+            r#"age Some("0") -> 1"#,
+            r#"age Some("1") -> 0"#,
+        ];
+        assert_eq!(trace[..first_object.len()], first_object);
+
+        // this("pup") delegates, so the initializers still run only once
+        let second_object = &trace[first_object.len()..];
+        assert_eq!(second_object[0], r#"new Puppy Some("<init>()")"#);
+        assert_eq!(
+            second_object[1],
+            r#"Some("this") Puppy Some("<init>(String)")"#
+        );
+        assert_eq!(
+            second_object
+                .iter()
+                .filter(|t| t.starts_with("age Some(\"0\") -> 1"))
+                .count(),
+            1
+        );
+
+        let creation = flow
+            .steps
+            .iter()
+            .find(|s| matches!(s.action, ExecutionAction::ObjectCreation { .. }))
+            .unwrap();
+        let defaults: Vec<(String, String)> = creation.heap[0]
+            .fields
+            .iter()
+            .map(|slot| (slot.name.clone(), slot.value.to_string()))
+            .collect();
+        assert_eq!(
+            defaults,
+            vec![
+                ("legs".to_string(), "0".to_string()),
+                ("name".to_string(), "null".to_string()),
+                ("loyal".to_string(), "false".to_string()),
+                ("age".to_string(), "0".to_string()),
+            ],
+            "fields should hold defaults, superclass fields first, before any constructor code runs"
+        );
+    }
+
+    #[test]
+    fn object_creation_steps_show_each_initialization_phase() {
+        let code = r#"
+public class Main {
+    public static void main(String[] args) {
+        Dog d = new Dog();
+    }
+}
+class Dog {
+    int age = 1;
+    String name;
+    Dog() { name = "Rex"; }
+}
+"#;
+        let flow = analyze_flow(code);
+        let phases: Vec<String> = flow
+            .steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::ObjectCreation { .. } => Some("allocate".to_string()),
+                ExecutionAction::FieldDefaults { .. } => Some(s.description.clone()),
+                ExecutionAction::FieldMutation { field_name, .. } => {
+                    Some(format!("write {}", field_name))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            phases,
+            [
+                "allocate",
+                "Fields of the new Dog start at their default values: age = 0, name = null",
+                "write age",
+                "write name",
+            ]
+        );
+    }
+
+    #[test]
+    fn field_initializers_make_their_calls() {
+        let code = r#"
+public class Main {
+    public static void main(String[] args) {
+        Ticket a = new Ticket();
+        Ticket b = new Ticket();
+    }
+}
+class Ticket {
+    static int counter;
+    int id = next();
+    static int next() { counter = counter + 1; return counter * 10; }
+}
+"#;
+        let flow = analyze_flow(code);
+        let last = flow.steps.last().unwrap();
+        let ids: Vec<Option<&Value>> = last
+            .heap
+            .iter()
+            .map(|obj| obj.field_in("Ticket", "id"))
+            .collect();
+        assert_eq!(ids, [Some(&Value::Int(10)), Some(&Value::Int(20))]);
+        assert_eq!(last.statics[0].fields[0].value, Value::Int(2));
+    }
+
+    #[test]
+    fn initializers_do_not_run_after_super_throws() {
+        let code = r#"
+public class Main {
+    public static void main(String[] args) {
+        C c = new C();
+    }
+}
+class B {
+    B(int d) { int x = 10 / d; }
+}
+class C extends B {
+    int z = 5;
+    C() { super(0); }
+}
+"#;
+        let flow = analyze_flow(code);
+        assert!(!flow.steps.iter().any(|s| matches!(
+            &s.action,
+            ExecutionAction::FieldMutation { field_name, .. } if field_name == "z"
+        )));
+        let unwound: Vec<&str> = flow
+            .steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::FrameUnwound { method_name, .. } => Some(method_name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(unwound[..2], ["B.<init>", "C.<init>"]);
+    }
+
+    #[test]
+    fn hidden_fields_resolve_by_static_type() {
+        let code = r#"
+public class Main {
+    public static void main(String[] args) {
+        Dog d = new Dog();
+        Animal a = d;
+        a.name = "via animal";
+        d.name = "via dog";
+        d.rename();
+    }
+}
+class Animal {
+    String name;
+    void rename() { name = "renamed"; }
+}
+class Dog extends Animal {
+    String name;
+}
+"#;
+        let flow = analyze_flow(code);
+        let heap = &flow.steps.last().unwrap().heap;
+        let dog = &heap[0];
+        assert_eq!(
+            string_at(heap, dog.field_in("Animal", "name")),
+            Some("renamed"),
+            "a.name and the bare name in Animal code both mean Animal.name"
+        );
+        assert_eq!(
+            string_at(heap, dog.field_in("Dog", "name")),
+            Some("via dog")
+        );
+    }
+
+    // ── Array Tests ──
+
+    #[test]
+    fn arrays_support_indexing_length_and_element_assignment() {
+        let code = wrap_main(
+            "int[] a = {3, 4, 5}; int sum = 0; \
+             for (int i = 0; i < a.length; i++) { sum += a[i]; } \
+             a[1] = 10; a[2] += 1; int[] b = new int[4];",
+        );
+        let flow = analyze_flow(&code);
+        assert!(
+            has_action(
+                &flow,
+                |a| matches!(a, ExecutionAction::VariableAssignment { variable_name, value, .. } if variable_name == "sum" && value == "12")
+            ),
+            "loop over a.length should sum every element"
+        );
+        assert!(has_action(
+            &flow,
+            |a| matches!(a, ExecutionAction::ArrayElementAssignment { array_name, index: 2, old_value: Some(old), new_value, .. } if array_name == "a" && old == "5" && new_value == "6")
+        ));
+        assert!(has_action(
+            &flow,
+            |a| matches!(a, ExecutionAction::ArrayCreation { variable_name, element_type, length: 4, .. } if variable_name == "b" && element_type == "int")
+        ));
+
+        let heap = &flow.steps.last().unwrap().heap;
+        assert_eq!(
+            heap[0].elements,
+            Some(vec![Value::Int(3), Value::Int(10), Value::Int(6)])
+        );
+        assert_eq!(heap[1].elements, Some(vec![Value::Int(0); 4]));
+    }
+
+    #[test]
+    fn reference_arrays_hold_object_references() {
+        let code = r#"
+public class Main {
+    public static void main(String[] args) {
+        Dog[] dogs = new Dog[2];
+        dogs[0] = new Dog();
+        dogs[1] = dogs[0];
+    }
+}
+class Dog {}
+"#;
+        let flow = analyze_flow(code);
+        assert!(has_action(
+            &flow,
+            |a| matches!(a, ExecutionAction::ObjectCreation { variable_name, .. } if variable_name == "dogs[0]")
+        ));
+        let heap = &flow.steps.last().unwrap().heap;
+        assert_eq!(heap[0].class_name, "Dog[]");
+        assert_eq!(heap[0].elements, Some(vec![Value::Ref(2), Value::Ref(2)]));
+        assert_eq!(
+            heap.len(),
+            2,
+            "copying an element must not create an object"
+        );
+    }
+
+    #[test]
+    fn two_dimensional_arrays_are_arrays_of_row_arrays() {
+        let code = wrap_main(
+            "int[][] grid = new int[2][3]; grid[1][2] = 7; int x = grid[1][2] + grid.length; \
+             int[][] jagged = {{1}, {2, 3}}; jagged[0] = new int[4]; int[][] lazy = new int[2][];",
+        );
+        let flow = analyze_flow(&code);
+        assert!(has_action(
+            &flow,
+            |a| matches!(a, ExecutionAction::VariableAssignment { variable_name, value, .. } if variable_name == "x" && value == "9")
+        ));
+        assert!(
+            flow.steps
+                .iter()
+                .any(|s| s.description == "Create new int[2][3] array: grid"),
+            "one step should create the whole grid"
+        );
+
+        let heap = &flow.steps.last().unwrap().heap;
+        let grid = &heap[0];
+        assert_eq!(grid.class_name, "int[][]");
+        assert_eq!(grid.elements, Some(vec![Value::Ref(2), Value::Ref(3)]));
+        assert_eq!(heap[1].class_name, "int[]");
+        assert_eq!(
+            heap[2].elements,
+            Some(vec![Value::Int(0), Value::Int(0), Value::Int(7)]),
+            "rows are separate objects; only row 1 changed"
+        );
+
+        // jagged: outer @4 with rows @5 {1} and @6 {2, 3}, then row 0 replaced
+        // by @7, which leaves @5 unreachable
+        assert_eq!(heap[3].elements, Some(vec![Value::Ref(7), Value::Ref(6)]));
+        assert_eq!(heap[4].elements, Some(vec![Value::Int(2), Value::Int(3)]));
+        assert_eq!(heap[5].elements, Some(vec![Value::Int(0); 4]));
+        assert!(has_action(
+            &flow,
+            |a| matches!(a, ExecutionAction::EligibleForGc { object_ids } if object_ids == &[5])
+        ));
+
+        // new int[2][] leaves the rows to be created later
+        assert_eq!(heap[6].elements, Some(vec![Value::Null, Value::Null]));
+        assert_eq!(heap.len(), 7);
+    }
+
+    // ── String Tests ──
+
+    #[test]
+    fn string_methods_drive_branches_and_values() {
+        let code = wrap_main(
+            "String name = \"Rex\"; \
+             if (name.equals(\"Rex\")) { int y = 1; } else { int y = 2; } \
+             int n = name.length(); String up = name.toUpperCase(); \
+             boolean starts = up.substring(0, 2).equals(\"RE\");",
+        );
+        let flow = analyze_flow(&code);
+        assert!(
+            has_action(&flow, |a| matches!(
+                a,
+                ExecutionAction::ConditionalBranch {
+                    branch_taken: true,
+                    ..
+                }
+            )) && has_action(
+                &flow,
+                |a| matches!(a, ExecutionAction::VariableAssignment { variable_name, value, .. } if variable_name == "y" && value == "1")
+            ),
+            "equals should pick the true branch"
+        );
+        assert!(has_action(
+            &flow,
+            |a| matches!(a, ExecutionAction::VariableAssignment { variable_name, value, .. } if variable_name == "n" && value == "3")
+        ));
+        assert!(has_action(
+            &flow,
+            |a| matches!(a, ExecutionAction::VariableAssignment { variable_name, value, .. } if variable_name == "starts" && value == "true")
+        ));
+
+        let last = flow.steps.last().unwrap();
+        let up = last.frames[0].lookup("up").unwrap();
+        assert_eq!(string_at(&last.heap, Some(&up.value)), Some("REX"));
+    }
+
+    #[test]
+    fn strings_are_immutable_heap_objects() {
+        let code = wrap_main("String a = \"ab\"; String b = a; a = a + \"c\"; a += 1;");
+        let flow = analyze_flow(&code);
+        let last = flow.steps.last().unwrap();
+        let frame = &last.frames[0];
+        assert_eq!(
+            string_at(&last.heap, Some(&frame.lookup("b").unwrap().value)),
+            Some("ab"),
+            "concatenation must not change the object b still refers to"
+        );
+        assert_eq!(
+            string_at(&last.heap, Some(&frame.lookup("a").unwrap().value)),
+            Some("abc1")
+        );
+        assert_eq!(
+            last.heap.len(),
+            3,
+            "every concatenation result is a new String object"
+        );
+        assert_eq!(
+            count_actions(
+                &flow,
+                |a| matches!(a, ExecutionAction::ObjectCreation { class_name, .. } if class_name == "String")
+            ),
+            3
+        );
+    }
+
+    #[test]
+    fn string_pool_distinguishes_identity_from_contents() {
+        let code = wrap_main(
+            "String a = \"hi\"; String b = \"hi\"; String c = new String(\"hi\"); \
+             if (a == b) { int x = 1; } \
+             if (a == c) { int y = 1; } else { int y = 2; } \
+             if (a.equals(c)) { int z = 1; } \
+             if (a == \"h\" + \"i\") { int w = 1; }",
+        );
+        let flow = analyze_flow(&code);
+        let branches: Vec<(bool, &str)> = flow
+            .steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::ConditionalBranch { branch_taken, .. } => {
+                    Some((*branch_taken, s.description.as_str()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            branches.iter().map(|(taken, _)| *taken).collect::<Vec<_>>(),
+            vec![true, false, true, true],
+            "literals share the pooled object, new String does not, equals compares contents"
+        );
+        assert!(
+            branches[1].1.contains("== compares references")
+                && branches[1].1.contains("a is String@1 (pooled)")
+                && branches[1].1.contains("c is String@2"),
+            "got: {}",
+            branches[1].1
+        );
+        assert!(
+            branches[2]
+                .1
+                .contains("equals compares contents: \"hi\" and \"hi\""),
+            "got: {}",
+            branches[2].1
+        );
+        assert!(
+            flow.steps
+                .iter()
+                .any(|s| s.description.starts_with("Reuse pooled String"))
+        );
+
+        let heap = &flow.steps.last().unwrap().heap;
+        assert_eq!(heap.len(), 2, "one pooled \"hi\" and one from new String");
+        assert!(heap[0].interned && !heap[1].interned);
+    }
+
+    // ── Console Output Tests ──
+
+    #[test]
+    fn system_out_writes_to_the_console() {
+        let code = r#"
+class Dog {
+    String name;
+    Dog(String name) { this.name = name; }
+    public String toString() { return "Dog " + name; }
+}
+class Box {}
+public class Main {
+    public static void main(String[] args) {
+        Dog d = new Dog("Rex");
+        Box b = new Box();
+        int n = 2;
+        System.out.print("n=" + n);
+        System.out.println();
+        System.out.println("Hi " + d);
+        System.out.println(b);
+        System.out.println(1 + 2 + "x");
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        let last = flow.steps.last().unwrap();
+        let box_id = match last.frames[0].lookup("b").unwrap().value {
+            Value::Ref(id) => id,
+            ref other => panic!("b should be a reference, got {:?}", other),
+        };
+        assert_eq!(
+            last.console,
+            format!("n=2\nHi Dog Rex\nBox@{:x}\n3x\n", box_id)
+        );
+
+        let prints: Vec<&str> = flow
+            .steps
+            .iter()
+            .filter(|s| {
+                matches!(&s.action, ExecutionAction::MethodCall { target_class, .. } if target_class == "System.out")
+            })
+            .map(|s| s.console.as_str())
+            .collect();
+        assert_eq!(prints.len(), 5);
+        assert_eq!(prints[0], "n=2", "print adds no newline");
+        assert_eq!(prints[1], "n=2\n");
+        assert!(
+            flow.steps[0].console.is_empty(),
+            "nothing is printed before the first call"
+        );
+        let calls: Vec<&str> = flow
+            .steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::MethodCall { method_name, .. } => Some(method_name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            calls[calls.len() - 4..],
+            ["toString", "println", "println", "println"],
+            "toString runs as a call before println prints its result"
+        );
+    }
+
+    #[test]
+    fn printing_calls_to_string_with_any_body() {
+        let code = r#"
+class Point {
+    int x;
+    Point(int x) { this.x = x; }
+    public String toString() {
+        String s = "P(" + x;
+        return s + ")";
+    }
+}
+class Line {
+    Point start = new Point(1);
+    public String toString() { return "Line " + start; }
+}
+public class Main {
+    public static void main(String[] args) {
+        Point p = new Point(3);
+        System.out.println(p);
+        Line line = new Line();
+        System.out.println(line);
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        let last = flow.steps.last().unwrap();
+        assert_eq!(last.console, "P(3)\nLine P(1)\n");
+        assert_eq!(last.frames.len(), 1, "toString frames are popped");
+    }
+
+    // ── Standard Input Tests ──
+
+    const SCANNER_PROGRAM: &str = r#"
+import java.util.Scanner;
+public class Main {
+    public static void main(String[] args) {
+        Scanner sc = new Scanner(System.in);
+        int n = sc.nextInt();
+        sc.nextLine();
+        String name = sc.nextLine();
+        int sum = 0;
+        while (sc.hasNextInt()) {
+            sum += sc.nextInt();
+        }
+        if (n > 2) {
+            System.out.println("big " + name);
+        } else {
+            System.out.println("small");
+        }
+    }
+}
+"#;
+
+    #[test]
+    fn scanner_reads_follow_the_stdin_script() {
+        let flow = analyze_flow_with_stdin(SCANNER_PROGRAM, "3\nAda\n4 5\n6\n");
+        let last = flow.steps.last().unwrap();
+        let frame = &last.frames[0];
+        assert_eq!(frame.lookup("n").unwrap().value, Value::Int(3));
+        assert_eq!(frame.lookup("sum").unwrap().value, Value::Int(15));
+        assert_eq!(
+            string_at(&last.heap, Some(&frame.lookup("name").unwrap().value)),
+            Some("Ada")
+        );
+        assert_eq!(
+            count_actions(&flow, |a| matches!(
+                a,
+                ExecutionAction::LoopIteration { .. }
+            )),
+            3,
+            "hasNextInt stays true for exactly the three remaining ints"
+        );
+        assert_eq!(last.console, "big Ada\n");
+        assert!(
+            flow.steps
+                .iter()
+                .any(|s| s.description == "Call method: nextInt (reads 3 from input)")
+        );
+    }
+
+    #[test]
+    fn scanner_reads_are_unknown_without_stdin() {
+        let flow = analyze_flow(SCANNER_PROGRAM);
+        let frame = &flow.steps.last().unwrap().frames[0];
+        assert_eq!(frame.lookup("n").unwrap().value, Value::Unknown);
+    }
+
+    #[test]
+    fn reads_inside_compound_assignments_get_an_input_step() {
+        let flow = analyze_flow_with_stdin(SCANNER_PROGRAM, "3\nAda\n4 5\n6\n");
+        for value in [4, 5, 6] {
+            let description = format!("Call method: nextInt (reads {} from input)", value);
+            let step = flow
+                .steps
+                .iter()
+                .find(|s| s.description == description)
+                .unwrap_or_else(|| panic!("no step reading {}", value));
+            assert_eq!(step.line_number, 11);
+            assert!(matches!(step.action, ExecutionAction::MethodCall { .. }));
+        }
+    }
+
+    #[test]
+    fn reading_past_the_end_of_stdin_throws() {
+        let code = wrap_main(
+            r#"java.util.Scanner sc = new java.util.Scanner(System.in);
+        int total = 0;
+        total += sc.nextInt();
+        total += sc.nextInt();
+        int after = 1;"#,
+        );
+        let flow = analyze_flow_with_stdin(&code, "7");
+        let step = raised(&flow).expect("the second read throws");
+        assert_eq!(step.description, "NoSuchElementException");
+        assert!(matches!(
+            &step.action,
+            ExecutionAction::ExceptionThrown { exception_class, message: None, .. }
+                if exception_class == "NoSuchElementException"
+        ));
+        let frame = &step.frames[0];
+        assert_eq!(frame.lookup("total").unwrap().value, Value::Int(7));
+        assert!(frame.lookup("after").is_none());
+
+        let flow = analyze_flow_with_stdin(SCANNER_PROGRAM, "3\n");
+        assert_eq!(
+            raised(&flow).unwrap().description,
+            "NoSuchElementException: No line found"
+        );
+    }
+
+    // ── Exception Tests ──
+
+    #[test]
+    fn exception_unwinds_to_matching_catch_then_finally_runs() {
+        let code = r#"
+class Account {
+    int balance;
+    void withdraw(int amount) {
+        if (amount > balance) {
+            throw new IllegalArgumentException("insufficient funds");
+        }
+        balance = balance - amount;
+    }
+}
+public class Main {
+    public static void main(String[] args) {
+        Account a = new Account();
+        int status = 0;
+        try {
+            a.withdraw(50);
+            status = 1;
+        } catch (IllegalStateException e) {
+            status = 2;
+        } catch (RuntimeException e) {
+            status = 3;
+            System.out.println(e.getMessage());
+        } finally {
+            System.out.println("done");
+        }
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        let kinds: Vec<&str> = flow
+            .steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::ExceptionThrown { .. } => Some("throw"),
+                ExecutionAction::FrameUnwound { .. } => Some("unwind"),
+                ExecutionAction::ExceptionCaught { .. } => Some("catch"),
+                ExecutionAction::FinallyBlock { .. } => Some("finally"),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(kinds, ["throw", "unwind", "catch", "finally"]);
+
+        let unwind = flow
+            .steps
+            .iter()
+            .find(|s| matches!(s.action, ExecutionAction::FrameUnwound { .. }))
+            .unwrap();
+        assert_eq!(
+            unwind.description,
+            "Unwind Account.withdraw: IllegalArgumentException propagates to Main.main"
+        );
+        assert_eq!(unwind.frames.len(), 1, "the unwound frame is gone");
+
+        let caught = flow
+            .steps
+            .iter()
+            .find(|s| matches!(s.action, ExecutionAction::ExceptionCaught { .. }))
+            .unwrap();
+        assert!(
+            matches!(caught.frames[0].lookup("e").unwrap().value, Value::Ref(_)),
+            "the catch parameter refers to the exception object"
+        );
+        let last = flow.steps.last().unwrap();
+        let frame = &last.frames[0];
+        assert_eq!(frame.lookup("status").unwrap().value, Value::Int(3));
+        assert!(frame.lookup("e").is_none(), "e ends with its catch block");
+        assert_eq!(last.console, "insufficient funds\ndone\n");
+        assert_eq!(
+            count_actions(&flow, |a| matches!(
+                a,
+                ExecutionAction::FieldMutation { .. }
+            )),
+            0,
+            "the rest of withdraw is skipped"
+        );
+    }
+
+    #[test]
+    fn uncaught_exception_ends_the_trace_with_a_stack_trace() {
+        let code = r#"
+class BadInput extends Exception {
+    BadInput(String message) { super(message); }
+}
+class Parser {
+    int parse(int x) throws BadInput {
+        if (x < 0) {
+            throw new BadInput("negative: " + x);
+        }
+        return x;
+    }
+}
+public class Main {
+    public static void main(String[] args) throws BadInput {
+        Parser p = new Parser();
+        try {
+            p.parse(-1);
+        } finally {
+            System.out.println("cleanup");
+        }
+        System.out.println("unreachable");
+    }
+}
+"#;
+        let line_of = |text: &str| code.lines().position(|l| l.contains(text)).unwrap() + 1;
+        let flow = analyze_flow(code);
+        let last = flow.steps.last().unwrap();
+        let ExecutionAction::UncaughtException { stack_trace, .. } = &last.action else {
+            panic!(
+                "trace should end with the uncaught exception, got {:?}",
+                last.action
+            );
+        };
+        assert_eq!(
+            stack_trace,
+            &[
+                "Exception in thread \"main\" BadInput: negative: -1".to_string(),
+                format!("\tat Parser.parse(Main.java:{})", line_of("throw new")),
+                format!("\tat Main.main(Main.java:{})", line_of("p.parse(-1)")),
+            ]
+        );
+        assert_eq!(last.description, stack_trace.join("\n"));
+        assert!(last.frames.is_empty());
+        assert_eq!(last.console, "cleanup\n");
+        assert!(
+            flow.steps
+                .iter()
+                .any(|s| { s.description == "Run finally block, then rethrow BadInput" })
+        );
+    }
+
+    #[test]
+    fn exception_thrown_in_a_loop_ends_it() {
+        let code = wrap_main(
+            "int i = 0; \
+             try { while (i < 5) { i = i + 1; if (i == 3) { throw new IllegalStateException(); } } } \
+             catch (Exception e) { i = i * 10; }",
+        );
+        let flow = analyze_flow(&code);
+        let frame = &flow.steps.last().unwrap().frames[0];
+        assert_eq!(frame.lookup("i").unwrap().value, Value::Int(30));
+        assert_eq!(
+            count_actions(&flow, |a| matches!(
+                a,
+                ExecutionAction::LoopIteration { .. }
+            )),
+            3
+        );
+    }
+
+    #[test]
+    fn try_with_resources_closes_in_reverse_order() {
+        let code = r#"
+class Res implements AutoCloseable {
+    String name;
+    Res(String name) { this.name = name; }
+    public void close() { System.out.println("close " + name); }
+}
+public class Main {
+    public static void main(String[] args) {
+        try (Res a = new Res("a"); Res b = new Res("b")) {
+            System.out.println("body");
+        }
+        try (Res c = new Res("c"); Res none = null) {
+            throw new IllegalStateException("boom");
+        } catch (IllegalStateException e) {
+            System.out.println("caught " + e.getMessage());
+        }
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        let last = flow.steps.last().unwrap();
+        assert_eq!(
+            last.console, "body\nclose b\nclose a\nclose c\ncaught boom\n",
+            "resources close before the catch block runs, and null ones are skipped"
+        );
+        assert_eq!(
+            called_methods(&flow)
+                .iter()
+                .filter(|m| m.as_str() == "Res.close")
+                .count(),
+            3
+        );
+    }
+
+    // ── Runtime Error Tests ──
+
+    fn raised(flow: &ExecutionFlow) -> Option<&ExecutionStep> {
+        flow.steps
+            .iter()
+            .find(|s| matches!(s.action, ExecutionAction::ExceptionThrown { .. }))
+    }
+
+    #[test]
+    fn null_receiver_raises_npe_explaining_where_null_came_from() {
+        let code = r#"
+class Dog {
+    void bark() {}
+}
+public class Main {
+    public static void main(String[] args) {
+        Dog d = new Dog();
+        d = null;
+        d.bark();
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        let step = raised(&flow).expect("calling bark on null should throw");
+        assert_eq!(step.line_number, 9);
+        assert_eq!(
+            step.description,
+            "NullPointerException: Cannot invoke \"Dog.bark()\" because \"d\" is null \
+             (d was set to null on line 8)"
+        );
+        assert!(matches!(
+            flow.steps.last().unwrap().action,
+            ExecutionAction::UncaughtException { .. }
+        ));
+    }
+
+    #[test]
+    fn null_field_read_names_the_field_and_its_default() {
+        let code = r#"
+class Dog { String name; }
+class Person { Dog pet; }
+public class Main {
+    public static void main(String[] args) {
+        Person p = new Person();
+        String n = p.pet.name;
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        assert_eq!(
+            raised(&flow).unwrap().description,
+            "NullPointerException: Cannot read field \"name\" because \"p.pet\" is null \
+             (p.pet was never assigned, so it still holds the default value null)"
+        );
+        let last = flow.steps.last().unwrap();
+        assert!(
+            last.frames.is_empty(),
+            "n is never declared; main ends with the exception"
+        );
+    }
+
+    #[test]
+    fn integer_division_by_zero_raises_arithmetic_exception() {
+        let code = wrap_main(
+            "int a = 5; int b = 0; double q = 1.0 / 0; \
+             try { int c = a / b; } catch (ArithmeticException e) { System.out.println(e.getMessage()); }",
+        );
+        let flow = analyze_flow(&code);
+        assert_eq!(
+            raised(&flow).unwrap().description,
+            "ArithmeticException: / by zero"
+        );
+        let last = flow.steps.last().unwrap();
+        assert_eq!(last.console, "/ by zero\n");
+        assert!(last.frames[0].lookup("c").is_none());
+    }
+
+    #[test]
+    fn out_of_range_indexes_raise_index_exceptions() {
+        let code = wrap_main(
+            "int[] arr = new int[3]; \
+             for (int i = 0; i <= 3; i++) { arr[i] = i; }",
+        );
+        let flow = analyze_flow(&code);
+        assert_eq!(
+            raised(&flow).unwrap().description,
+            "ArrayIndexOutOfBoundsException: Index 3 out of bounds for length 3"
+        );
+        assert_eq!(
+            count_actions(&flow, |a| matches!(
+                a,
+                ExecutionAction::ArrayElementAssignment { .. }
+            )),
+            3
+        );
+
+        let flow = analyze_flow(&wrap_main("String s = \"abc\"; char c = s.charAt(3);"));
+        assert_eq!(
+            raised(&flow).unwrap().description,
+            "StringIndexOutOfBoundsException: Index 3 out of bounds for length 3"
+        );
+    }
+
+    #[test]
+    fn array_lengths_that_are_unknown_or_negative() {
+        let code = r#"
+import java.util.Scanner;
+public class Main {
+    public static void main(String[] args) {
+        Scanner sc = new Scanner(System.in);
+        int n = sc.nextInt();
+        int[] a = new int[n];
+        a[0] = 1;
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        assert!(
+            raised(&flow).is_none(),
+            "an array of unknown length can't be indexed out of bounds"
+        );
+        assert!(!has_action(&flow, |a| matches!(
+            a,
+            ExecutionAction::ArrayCreation { .. }
+        )));
+        let frame = &flow.steps.last().unwrap().frames[0];
+        assert_eq!(frame.lookup("a").unwrap().value, Value::Unknown);
+
+        let flow = analyze_flow(&wrap_main("int n = -2; int[][] grid = new int[3][n];"));
+        assert_eq!(
+            raised(&flow).unwrap().description,
+            "NegativeArraySizeException: -2"
+        );
+        assert!(!has_action(&flow, |a| matches!(
+            a,
+            ExecutionAction::ArrayCreation { .. }
+        )));
+    }
+
+    #[test]
+    fn short_circuit_guards_are_respected() {
+        let code = r#"
+class Dog { String name; }
+public class Main {
+    public static void main(String[] args) {
+        Dog d = null;
+        int x = 0;
+        if (d != null && d.name.equals("Rex")) { x = 1; }
+        int y = d == null ? 0 : d.name.length();
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        assert!(raised(&flow).is_none());
+        let frame = &flow.steps.last().unwrap().frames[0];
+        assert_eq!(frame.lookup("y").unwrap().value, Value::Int(0));
+    }
+
+    // ── Switch and Ternary Tests ──
+
+    fn switch_labels(flow: &ExecutionFlow) -> Vec<Option<String>> {
+        flow.steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::SwitchBranch { matched_label, .. } => Some(matched_label.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn classic_switch_falls_through_until_break() {
+        let code = wrap_main(
+            "int day = 2; int hits = 0; \
+             switch (day) { \
+               case 1: hits = hits + 1; \
+               case 2: hits = hits + 10; \
+               case 3: hits = hits + 100; break; \
+               default: hits = -1; \
+             }",
+        );
+        let flow = analyze_flow(&code);
+        assert_eq!(switch_labels(&flow), [Some("case 2".to_string())]);
+        let frame = &flow.steps.last().unwrap().frames[0];
+        assert_eq!(frame.lookup("hits").unwrap().value, Value::Int(110));
+        assert!(
+            flow.steps
+                .iter()
+                .any(|s| s.description == "Switch on day = 2: take case 2")
+        );
+    }
+
+    #[test]
+    fn switch_takes_default_and_arrow_rules_do_not_fall_through() {
+        let code = wrap_main(
+            "String cmd = \"stop\"; int a = 0; int b = 0; \
+             switch (cmd) { case \"go\", \"run\" -> a = 1; default -> a = 2; } \
+             switch (cmd) { case \"stop\" -> { b = 5; } case \"go\" -> b = 6; }",
+        );
+        let flow = analyze_flow(&code);
+        assert_eq!(
+            switch_labels(&flow),
+            [
+                Some("default".to_string()),
+                Some("case \"stop\"".to_string())
+            ]
+        );
+        let frame = &flow.steps.last().unwrap().frames[0];
+        assert_eq!(frame.lookup("a").unwrap().value, Value::Int(2));
+        assert_eq!(frame.lookup("b").unwrap().value, Value::Int(5));
+    }
+
+    #[test]
+    fn switch_expressions_yield_values() {
+        let code = wrap_main(
+            "int n = 3; \
+             int size = switch (n) { case 1, 2 -> 10; case 3 -> { int t = n * 2; yield t + 1; } default -> 0; }; \
+             String kind = switch (size) { case 7: yield \"seven\"; default: yield \"other\"; }; \
+             int none = 0; \
+             none = switch (n) { case 9 -> 1; default -> 2; };",
+        );
+        let flow = analyze_flow(&code);
+        assert_eq!(
+            switch_labels(&flow),
+            [
+                Some("case 3".to_string()),
+                Some("case 7".to_string()),
+                Some("default".to_string())
+            ]
+        );
+        let last = flow.steps.last().unwrap();
+        let frame = &last.frames[0];
+        assert_eq!(frame.lookup("size").unwrap().value, Value::Int(7));
+        assert_eq!(
+            string_at(&last.heap, Some(&frame.lookup("kind").unwrap().value)),
+            Some("seven")
+        );
+        assert_eq!(frame.lookup("none").unwrap().value, Value::Int(2));
+    }
+
+    #[test]
+    fn break_in_a_switch_does_not_end_the_enclosing_loop() {
+        let code = wrap_main(
+            "int total = 0; \
+             for (int i = 0; i < 3; i++) { switch (i) { case 1: total = total + 5; break; default: total = total + 1; } }",
+        );
+        let flow = analyze_flow(&code);
+        assert_eq!(switch_labels(&flow).len(), 3);
+        let frame = &flow.steps.last().unwrap().frames[0];
+        assert_eq!(frame.lookup("total").unwrap().value, Value::Int(7));
+    }
+
+    #[test]
+    fn ternary_records_the_chosen_operand() {
+        let code = wrap_main("int x = -4; int abs = x < 0 ? -x : x;");
+        let flow = analyze_flow(&code);
+        let frame = &flow.steps.last().unwrap().frames[0];
+        assert_eq!(frame.lookup("abs").unwrap().value, Value::Int(4));
+        let decision = flow
+            .steps
+            .iter()
+            .find(|s| matches!(s.action, ExecutionAction::ConditionalBranch { .. }))
+            .unwrap();
+        assert_eq!(
+            decision.action,
+            ExecutionAction::ConditionalBranch {
+                condition: "x < 0".to_string(),
+                branch_taken: true
+            }
+        );
+        assert_eq!(
+            decision.description,
+            "Evaluate condition: x < 0 = true (choose -x)"
+        );
+    }
+
+    // ── Numeric Type Tests ──
+
+    #[test]
+    fn primitives_follow_java_numeric_semantics() {
+        let code = wrap_main(
+            "int big = 2147483647; big = big + 1; \
+             long wide = 2147483647L + 1; \
+             char c = 'a'; int code = c + 1; char next = (char) code; c += 2; \
+             int truncated = (int) 3.9; byte small = (byte) 300; \
+             int a = 7; int b = 2; double quotient = a / b; double exact = (double) a / b; \
+             double d = 7; d = d / 2; \
+             int total = 10; total += 2.7; \
+             float f = 0.1f; \
+             System.out.println(c + \" \" + (0.1 + 0.2) + \" \" + 1e7);",
+        );
+        let flow = analyze_flow(&code);
+        let last = flow.steps.last().unwrap();
+        let value = |name: &str| last.frames[0].lookup(name).unwrap().value.clone();
+        assert_eq!(value("big"), Value::Int(i32::MIN));
+        assert_eq!(value("wide"), Value::Long(2147483648));
+        assert_eq!(value("code"), Value::Int(98));
+        assert_eq!(value("next"), Value::Char('b' as u16));
+        assert_eq!(value("c"), Value::Char('c' as u16));
+        assert_eq!(value("truncated"), Value::Int(3));
+        assert_eq!(value("small"), Value::Byte(44));
+        assert_eq!(value("quotient"), Value::Double(3.0));
+        assert_eq!(value("exact"), Value::Double(3.5));
+        assert_eq!(value("d"), Value::Double(3.5));
+        assert_eq!(value("total"), Value::Int(12));
+        assert_eq!(value("f"), Value::Float(0.1));
+        assert_eq!(last.console, "c 0.30000000000000004 1.0E7\n");
+        assert!(flow.steps.iter().any(|s| matches!(
+            &s.action,
+            ExecutionAction::VariableAssignment { variable_name, value, .. }
+                if variable_name == "quotient" && value == "3.0"
+        )));
+    }
+
+    #[test]
+    fn wrapper_bounds_wrap_around() {
+        let code = wrap_main(
+            "int over = Integer.MAX_VALUE + 1; \
+             int under = Integer.MIN_VALUE - 1; \
+             long wide = Long.MAX_VALUE + 1; \
+             long widened = Integer.MAX_VALUE + 1L; \
+             short s = Short.MAX_VALUE; s++; \
+             byte b = Byte.MIN_VALUE; \
+             char top = Character.MAX_VALUE; \
+             System.out.println(Integer.MAX_VALUE + 1);",
+        );
+        let flow = analyze_flow(&code);
+        let last = flow.steps.last().unwrap();
+        let value = |name: &str| last.frames[0].lookup(name).unwrap().value.clone();
+        assert_eq!(value("over"), Value::Int(i32::MIN));
+        assert_eq!(value("under"), Value::Int(i32::MAX));
+        assert_eq!(value("wide"), Value::Long(i64::MIN));
+        assert_eq!(value("widened"), Value::Long(2147483648));
+        assert_eq!(value("s"), Value::Short(i16::MIN));
+        assert_eq!(value("b"), Value::Byte(-128));
+        assert_eq!(value("top"), Value::Char(0xFFFF));
+        assert_eq!(last.console, "-2147483648\n");
+    }
+
+    #[test]
+    fn bitwise_and_shift_operators_including_compound_forms() {
+        let code = wrap_main(
+            "int flags = 0b1010; flags |= 0b0101; flags &= ~0b0010; flags ^= 0x100; \
+             int bits = 1; bits <<= 4; bits >>= 1; \
+             int sign = -16; sign >>>= 28; \
+             long mask = 1L << 40; \
+             byte b = 0x70; b <<= 1; \
+             boolean both = (flags & 1) == 1 & bits > 0;",
+        );
+        let flow = analyze_flow(&code);
+        let last = flow.steps.last().unwrap();
+        let value = |name: &str| last.frames[0].lookup(name).unwrap().value.clone();
+        assert_eq!(value("flags"), Value::Int(0b1_0000_1101));
+        assert_eq!(value("bits"), Value::Int(8));
+        assert_eq!(value("sign"), Value::Int(15));
+        assert_eq!(value("mask"), Value::Long(1 << 40));
+        assert_eq!(value("b"), Value::Byte(-32));
+        assert_eq!(value("both"), Value::Bool(true));
+    }
+
+    // ── Return Value Tests ──
+
+    #[test]
+    fn return_values_flow_into_caller_expressions() {
+        let code = r#"
+class Calculator {
+    int add(int a, int b) { return a + b; }
+}
+class Dog {
+    int food = 0;
+    boolean isHungry() { return food < 3; }
+    int getFood() { return food; }
+    void eat() { food = food + 1; }
+}
+public class Main {
+    static int max(int a, int b) {
+        if (a > b) {
+            return a;
+        }
+        return b;
+    }
+    public static void main(String[] args) {
+        Calculator calc = new Calculator();
+        int total = calc.add(2, 3) + 1;
+        Dog dog = new Dog();
+        if (dog.isHungry()) {
+            dog.eat();
+        }
+        while (dog.getFood() < 3) {
+            dog.eat();
+        }
+        int bigger = max(7, 4) * 2;
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        let last = flow.steps.last().unwrap();
+        let value = |name: &str| last.frames[0].lookup(name).unwrap().value.clone();
+        assert_eq!(value("total"), Value::Int(6));
+        assert_eq!(value("bigger"), Value::Int(14));
+        let conditions: Vec<(String, bool)> = flow
+            .steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::ConditionalBranch {
+                    condition,
+                    branch_taken,
+                } => Some((condition.clone(), *branch_taken)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(conditions[0], ("(dog.isHungry())".to_string(), true));
+        let iterations = flow
+            .steps
+            .iter()
+            .filter(|s| matches!(s.action, ExecutionAction::LoopIteration { .. }))
+            .count();
+        assert_eq!(iterations, 2, "the loop runs until the getter reports 3");
+        assert_eq!(
+            conditions.last().unwrap(),
+            &("(a > b)".to_string(), true),
+            "max returns early from inside the if"
+        );
+    }
+
+    #[test]
+    fn method_returns_record_the_evaluated_value() {
+        let code = r#"
+public class Main {
+    static String greet(String name) { return "Hi " + name; }
+    static int twice(int n) { return n * 2; }
+    public static void main(String[] args) {
+        String g = greet("Ada");
+        int t = twice(21);
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        let returned: Vec<Option<String>> = flow
+            .steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::MethodReturn { return_value, .. } => Some(return_value.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            returned,
+            [Some("\"Hi Ada\"".to_string()), Some("42".to_string())]
+        );
+    }
+
+    #[test]
+    fn recursive_calls_keep_a_result_per_frame() {
+        let code = r#"
+public class Main {
+    static int fact(int n) {
+        if (n <= 1) {
+            return 1;
+        }
+        return n * fact(n - 1);
+    }
+    public static void main(String[] args) {
+        int f = fact(4);
+        System.out.println(f);
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        let last = flow.steps.last().unwrap();
+        assert_eq!(last.frames[0].lookup("f").unwrap().value, Value::Int(24));
+        assert_eq!(last.console, "24\n");
+        let max_depth = flow.steps.iter().map(|s| s.frames.len()).max().unwrap();
+        assert_eq!(max_depth, 5, "main plus four calls to fact");
+    }
+
+    #[test]
+    fn returned_references_bind_as_references() {
+        let code = r#"
+class Dog {}
+public class Main {
+    static Dog make(boolean real) {
+        if (real) {
+            return new Dog();
+        }
+        return null;
+    }
+    public static void main(String[] args) {
+        Dog x = make(true);
+        Dog y = make(false);
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        let bindings: Vec<(&str, Option<ObjectId>)> = flow
+            .steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::ReferenceAssignment {
+                    variable_name,
+                    object_id,
+                    ..
+                } => Some((variable_name.as_str(), *object_id)),
+                _ => None,
+            })
+            .collect();
+        let x = match flow.steps.last().unwrap().frames[0]
+            .lookup("x")
+            .unwrap()
+            .value
+        {
+            Value::Ref(id) => id,
+            ref other => panic!("x should be a reference, got {:?}", other),
+        };
+        assert!(bindings.contains(&("x", Some(x))));
+        assert!(bindings.contains(&("y", None)));
+    }
+
+    #[test]
+    fn objects_created_inside_expressions_are_allocated() {
+        let code = r#"
+class Dog {
+    int age;
+    Dog(int age) { this.age = age; }
+    int getAge() { return age; }
+}
+public class Main {
+    static int ageOf(Dog d) { return d.getAge(); }
+    static int find(int[] values, int target) {
+        for (int i = 0; i < values.length; i++) {
+            if (values[i] == target) {
+                return i;
+            }
+        }
+        return -1;
+    }
+    public static void main(String[] args) {
+        int a = ageOf(new Dog(5));
+        int b = new Dog(6).getAge();
+        int c = find(new int[]{4, 5, 6}, 5);
+        int d = find(new int[]{4, 5, 6}, 7);
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        let last = flow.steps.last().unwrap();
+        let value = |name: &str| last.frames[0].lookup(name).unwrap().value.clone();
+        assert_eq!(value("a"), Value::Int(5));
+        assert_eq!(value("b"), Value::Int(6));
+        assert_eq!(value("c"), Value::Int(1));
+        assert_eq!(value("d"), Value::Int(-1));
+        let creations = flow
+            .steps
+            .iter()
+            .filter(|s| matches!(s.action, ExecutionAction::ObjectCreation { .. }))
+            .count();
+        assert_eq!(creations, 2, "each new Dog is allocated once");
+    }
+
+    #[test]
+    fn nested_increments_and_assignments_write_their_target() {
+        let code = r#"
+class Counter {
+    static int count = 10;
+    static int inc() { return ++count; }
+}
+public class Main {
+    static int bump(int n) { return ++n; }
+    public static void main(String[] args) {
+        int x = 0;
+        int y = ++x;
+        int z = x++;
+        int[] arr = new int[3];
+        int i = 0;
+        arr[i++] = 5;
+        int b = bump(4);
+        int w;
+        int v = (w = 7) + 1;
+        int sum = Counter.inc() + Counter.inc();
+        System.out.println(x + " " + y + " " + z + " " + i);
+        System.out.println(sum);
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        let last = flow.steps.last().unwrap();
+        let value = |name: &str| last.frames[0].lookup(name).unwrap().value.clone();
+        assert_eq!(value("y"), Value::Int(1));
+        assert_eq!(value("z"), Value::Int(1));
+        assert_eq!(value("x"), Value::Int(2));
+        assert_eq!(value("i"), Value::Int(1));
+        assert_eq!(value("b"), Value::Int(5));
+        assert_eq!(value("w"), Value::Int(7));
+        assert_eq!(value("v"), Value::Int(8));
+        let Value::Ref(arr) = value("arr") else {
+            panic!("arr holds the array");
+        };
+        let arr = last.heap.iter().find(|obj| obj.id == arr).unwrap();
+        assert_eq!(
+            arr.elements,
+            Some(vec![Value::Int(5), Value::Int(0), Value::Int(0)])
+        );
+        assert_eq!(last.console, "2 1 1 1\n23\n");
+    }
+
+    #[test]
+    fn parameters_bind_for_methods_declared_after_a_nested_class() {
+        let code = r#"
+public class Main {
+    static class Dog {}
+    static int fact(int n) {
+        if (n <= 1) {
+            return 1;
+        }
+        return n * fact(n - 1);
+    }
+    public static void main(String[] args) {
+        int f = fact(3);
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        let last = flow.steps.last().unwrap();
+        assert_eq!(last.frames[0].lookup("f").unwrap().value, Value::Int(6));
+    }
+
+    #[test]
+    fn nested_calls_run_left_to_right() {
+        let code = r#"
+class Counter {
+    int count = 0;
+    int next() { count = count + 1; return count; }
+}
+public class Main {
+    static int pair(int first, int second) { return first * 10 + second; }
+    public static void main(String[] args) {
+        Counter c = new Counter();
+        int p = pair(c.next(), c.next());
+        String s = "n" + c.next();
+        System.out.println(s);
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        let last = flow.steps.last().unwrap();
+        assert_eq!(last.frames[0].lookup("p").unwrap().value, Value::Int(12));
+        let calls: Vec<&str> = flow
+            .steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::MethodCall { method_name, .. } => Some(method_name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(calls, ["next", "next", "pair", "next", "println"]);
+        assert_eq!(last.console, "n3\n");
+    }
+
+    #[test]
+    fn operands_keep_the_value_they_had_when_evaluated() {
+        let code = r#"
+class Counter {
+    int count = 0;
+    int next() { count = count + 1; return count; }
+}
+public class Main {
+    public static void main(String[] args) {
+        Counter c = new Counter();
+        int before = c.count + c.next();
+        int after = c.next() + c.count;
+        int[] slots = new int[3];
+        slots[c.count] = c.next();
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        let last = flow.steps.last().unwrap();
+        let value = |name: &str| last.frames[0].lookup(name).unwrap().value.clone();
+        assert_eq!(
+            value("before"),
+            Value::Int(1),
+            "count is read before next runs"
+        );
+        assert_eq!(value("after"), Value::Int(4));
+        let Value::Ref(slots) = value("slots") else {
+            panic!("slots holds the array");
+        };
+        let slots = last.heap.iter().find(|obj| obj.id == slots).unwrap();
+        assert_eq!(
+            slots.elements,
+            Some(vec![Value::Int(0), Value::Int(0), Value::Int(3)]),
+            "the index is evaluated before the right side runs"
+        );
+    }
+
+    // ── Static Field Tests ──
+
+    #[test]
+    fn static_fields_are_shared_and_initialized_once() {
+        let code = r#"
+class Dog {
+    static int count;
+    static String kind = "Canine";
+    static final int LIMIT;
+    static {
+        LIMIT = 3;
+    }
+    String name;
+    Dog(String name) {
+        this.name = name;
+        count++;
+    }
+}
+public class Main {
+    public static void main(String[] args) {
+        Dog a = new Dog("Rex");
+        Dog b = new Dog("Fido");
+        Dog.count += 10;
+        int seen = Dog.count;
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        let initializations: Vec<&ExecutionStep> = flow
+            .steps
+            .iter()
+            .filter(|s| matches!(s.action, ExecutionAction::ClassInitialization { .. }))
+            .collect();
+        assert_eq!(initializations.len(), 1, "Dog is initialized once");
+        assert_eq!(initializations[0].call_stack, ["main", "Dog.<clinit>"]);
+
+        let last = flow.steps.last().unwrap();
+        assert_eq!(last.frames[0].lookup("seen").unwrap().value, Value::Int(12));
+        let dog = &last.statics[0];
+        assert_eq!(dog.class_name, "Dog");
+        let names: Vec<&str> = dog.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["count", "kind", "LIMIT"]);
+        assert_eq!(dog.fields[2].value, Value::Int(3));
+        assert!(
+            last.heap
+                .iter()
+                .filter(|obj| obj.class_name == "Dog")
+                .all(|obj| obj.fields.iter().all(|f| f.name == "name")),
+            "instances only hold their instance fields"
+        );
+        let counts: Vec<&str> = flow
+            .steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::StaticFieldMutation {
+                    field_name,
+                    new_value,
+                    ..
+                } if field_name == "count" => Some(new_value.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(counts, ["1", "2", "12"]);
+    }
+
+    #[test]
+    fn static_initialization_runs_superclass_first_on_first_use() {
+        let code = r#"
+class Animal {
+    static int animals = 100;
+}
+class Dog extends Animal {
+    static int dogs = animals + 1;
+    static int next() { dogs++; return dogs; }
+}
+public class Main {
+    static int calls = 0;
+    public static void main(String[] args) {
+        calls++;
+        int first = Dog.next();
+        int second = Dog.next();
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        let initialized: Vec<&str> = flow
+            .steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::ClassInitialization { class_name } => Some(class_name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(initialized, ["Main", "Animal", "Dog"]);
+        let last = flow.steps.last().unwrap();
+        assert_eq!(
+            last.frames[0].lookup("first").unwrap().value,
+            Value::Int(102)
+        );
+        assert_eq!(
+            last.frames[0].lookup("second").unwrap().value,
+            Value::Int(103)
+        );
+        assert_eq!(last.statics[0].fields[0].value, Value::Int(1));
+    }
+
+    // ── Garbage Collection Tests ──
+
+    fn collected(flow: &ExecutionFlow) -> Vec<(usize, Vec<ObjectId>)> {
+        flow.steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::EligibleForGc { object_ids } => {
+                    Some((s.line_number, object_ids.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn objects_become_eligible_for_gc_when_their_last_reference_goes() {
+        let code = r#"
+class Dog {}
+public class Main {
+    static Dog kept;
+    static Dog make() {
+        Dog local = new Dog();
+        return local;
+    }
+    public static void main(String[] args) {
+        Dog a = new Dog();
+        Dog b = a;
+        a = new Dog();
+        b = null;
+        kept = make();
+        make();
+        String s = "x";
+        s = s + 1;
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        // Dog@1 goes with b, the result of the second make() right away;
+        // the literal "x" stays in the String pool
+        assert_eq!(collected(&flow), [(13, vec![1]), (15, vec![4])]);
+        let step = flow
+            .steps
+            .iter()
+            .find(|s| matches!(s.action, ExecutionAction::EligibleForGc { .. }))
+            .unwrap();
+        assert_eq!(
+            step.description,
+            "Dog@1 is no longer reachable: eligible for garbage collection"
+        );
+        assert!(
+            step.heap.iter().any(|obj| obj.id == 1),
+            "shown one last time"
+        );
+        let last = flow.steps.last().unwrap();
+        assert!(last.heap.iter().all(|obj| obj.id != 1 && obj.id != 4));
+        assert!(last.heap.iter().any(|obj| obj.id == 3), "held by Main.kept");
+    }
+
+    #[test]
+    fn a_pending_exception_survives_its_finally_block() {
+        let code = wrap_main(
+            "try { try { throw new IllegalStateException(\"boom\"); } \
+             finally { int[] scratch = new int[1]; scratch = null; } } \
+             catch (IllegalStateException e) { System.out.println(e.getMessage()); }",
+        );
+        let flow = analyze_flow(&code);
+        let last = flow.steps.last().unwrap();
+        assert_eq!(last.console, "boom\n");
+        assert_eq!(
+            collected(&flow).len(),
+            2,
+            "the scratch array, then the exception once its catch block ends"
+        );
+    }
+
+    #[test]
+    fn arrays_being_filled_are_not_collected() {
+        let code = r#"
+class Dog {
+    int age;
+    Dog() { age = 1; }
+}
+class Kennel {
+    Dog[] dogs = { new Dog(), new Dog() };
+}
+public class Main {
+    static Dog[] litter() {
+        return new Dog[] { new Dog() };
+    }
+    public static void main(String[] args) {
+        Kennel k = new Kennel();
+        Dog[] pups = litter();
+        Dog[][] grid = { { new Dog() }, { new Dog() } };
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        assert_eq!(collected(&flow), []);
+        let last = flow.steps.last().unwrap();
+        let live = |value: &Value| match value {
+            Value::Ref(id) => last.heap.iter().any(|obj| obj.id == *id),
+            _ => false,
+        };
+        for name in ["k", "pups", "grid"] {
+            let value = &last.frames[0].lookup(name).unwrap().value;
+            assert!(live(value), "{} should refer to a live object", name);
+        }
+        for array in last.heap.iter().filter_map(|obj| obj.elements.as_ref()) {
+            assert!(array.iter().all(live), "every element should be live");
+        }
+    }
+
+    fn out_of_scope(flow: &ExecutionFlow) -> Vec<&str> {
+        flow.steps
+            .iter()
+            .filter(|s| matches!(s.action, ExecutionAction::VariablesOutOfScope { .. }))
+            .map(|s| s.description.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn block_locals_go_out_of_scope_when_their_block_ends() {
+        let code = wrap_main(
+            "int total = 0;\n\
+             for (int i = 0; i < 2; i++) {\n\
+                 int square = i * i;\n\
+                 total += square;\n\
+             }\n\
+             if (total > 0) {\n\
+                 int a = 1, b = 2;\n\
+                 total += a + b;\n\
+             }",
+        );
+        let flow = analyze_flow(&code);
+        assert_eq!(
+            out_of_scope(&flow),
+            [
+                "End of block: square goes out of scope",
+                "End of block: square goes out of scope",
+                "End of block: i goes out of scope",
+                "End of block: a, b go out of scope",
+            ]
+        );
+        let frame = &flow.steps.last().unwrap().frames[0];
+        let locals: Vec<&str> = frame.locals.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(locals, ["total"]);
+        assert_eq!(frame.lookup("total").unwrap().value, Value::Int(4));
+    }
+
+    #[test]
+    fn sibling_blocks_can_declare_the_same_name() {
+        let code = wrap_main(
+            "int x = 10;\n\
+             { int y = 1; x += y; }\n\
+             { String y = \"two\"; x += y.length(); }\n\
+             switch (x) { case 14: int y = 3; x += y; break; default: break; }",
+        );
+        let flow = analyze_flow(&code);
+        let frame = &flow.steps.last().unwrap().frames[0];
+        assert_eq!(frame.lookup("x").unwrap().value, Value::Int(17));
+        assert!(frame.lookup("y").is_none());
+        assert_eq!(out_of_scope(&flow).len(), 3);
+    }
+
+    #[test]
+    fn an_object_held_only_by_a_block_local_is_collected_when_the_block_ends() {
+        let code = r#"
+class Dog {}
+public class Main {
+    public static void main(String[] args) {
+        int n = 0;
+        while (n < 1) {
+            Dog d = new Dog();
+            n++;
+        }
+        n = 5;
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        let kinds: Vec<&str> = flow
+            .steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::VariablesOutOfScope { .. } => Some("scope"),
+                ExecutionAction::EligibleForGc { .. } => Some("gc"),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(kinds, ["scope", "gc"]);
+        assert_eq!(collected(&flow), [(9, vec![1])]);
+    }
+
+    fn loop_iterations(flow: &ExecutionFlow) -> Vec<&str> {
+        flow.steps
+            .iter()
+            .filter(|s| matches!(s.action, ExecutionAction::LoopIteration { .. }))
+            .map(|s| s.description.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn enhanced_for_binds_each_array_element() {
+        let code = r#"
+class Dog {
+    int age;
+    Dog(int age) { this.age = age; }
+}
+public class Main {
+    public static void main(String[] args) {
+        Dog[] dogs = { new Dog(3), new Dog(5) };
+        int total = 0;
+        for (Dog d : dogs) {
+            total += d.age;
+        }
+        double sum = 0;
+        for (double x : new int[] {1, 2, 3}) {
+            if (x > 2) break;
+            sum += x;
+        }
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        assert_eq!(
+            loop_iterations(&flow),
+            [
+                "Loop iteration 1: d = Dog@2",
+                "Loop iteration 2: d = Dog@3",
+                "Loop iteration 1: x = 1.0",
+                "Loop iteration 2: x = 2.0",
+                "Loop iteration 3: x = 3.0",
+            ]
+        );
+        let frame = &flow.steps.last().unwrap().frames[0];
+        assert_eq!(frame.lookup("total").unwrap().value, Value::Int(8));
+        assert_eq!(frame.lookup("sum").unwrap().value, Value::Double(3.0));
+        assert!(frame.lookup("d").is_none() && frame.lookup("x").is_none());
+    }
+
+    #[test]
+    fn enhanced_for_over_an_iterable_calls_its_iterator() {
+        let code = r#"
+class Countdown implements Iterable<Integer> {
+    int from;
+    Countdown(int from) { this.from = from; }
+    public Iterator<Integer> iterator() {
+        return new CountdownIterator(from);
+    }
+}
+class CountdownIterator implements Iterator<Integer> {
+    int next;
+    CountdownIterator(int next) { this.next = next; }
+    public boolean hasNext() { return next > 0; }
+    public Integer next() {
+        next--;
+        return next + 1;
+    }
+}
+public class Main {
+    public static void main(String[] args) {
+        int product = 1;
+        for (int n : new Countdown(3)) {
+            product *= n;
+        }
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        assert_eq!(
+            loop_iterations(&flow),
+            [
+                "Loop iteration 1: n = 3",
+                "Loop iteration 2: n = 2",
+                "Loop iteration 3: n = 1",
+            ]
+        );
+        let calls: Vec<&str> = flow
+            .steps
+            .iter()
+            .filter_map(|s| match &s.action {
+                ExecutionAction::MethodCall { method_name, .. } if method_name != "<init>" => {
+                    Some(method_name.as_str())
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            calls,
+            [
+                "iterator", "hasNext", "next", "hasNext", "next", "hasNext", "next", "hasNext"
+            ]
+        );
+        let frame = &flow.steps.last().unwrap().frames[0];
+        assert_eq!(frame.lookup("product").unwrap().value, Value::Int(6));
+        let ids: Vec<Vec<ObjectId>> = collected(&flow).into_iter().map(|(_, ids)| ids).collect();
+        assert_eq!(
+            ids,
+            [vec![1], vec![2]],
+            "the Countdown once iterator() has returned, its iterator after the loop"
+        );
+    }
+
+    #[test]
+    fn enhanced_for_over_a_null_array_throws() {
+        let code = wrap_main("int[] values = null;\nfor (int v : values) {\n}");
+        let flow = analyze_flow(&code);
+        assert_eq!(
+            raised(&flow).unwrap().description,
+            "NullPointerException: Cannot read the array length because \"values\" is null \
+             (values was set to null on line 1)"
+        );
+        assert!(loop_iterations(&flow).is_empty());
+    }
+
+    #[test]
+    fn array_lists_keep_their_elements_on_the_heap() {
+        let code = r#"
+import java.util.ArrayList;
+import java.util.List;
+class Dog {
+    String name;
+    Dog(String name) { this.name = name; }
+}
+public class Main {
+    public static void main(String[] args) {
+        List<Dog> dogs = new ArrayList<>();
+        dogs.add(new Dog("Rex"));
+        dogs.add(new Dog("Fido"));
+        dogs.add(0, new Dog("Ace"));
+        Dog removed = dogs.remove(1);
+        dogs.set(0, removed);
+        int size = dogs.size();
+        String names = "";
+        for (Dog d : dogs) {
+            names += d.name;
+        }
+        System.out.println(dogs.get(1).name + " " + dogs.size());
+    }
+}
+"#;
+        let flow = analyze_flow(code);
+        let last = flow.steps.last().unwrap();
+        let frame = &last.frames[0];
+        assert_eq!(frame.lookup("size").unwrap().value, Value::Int(2));
+        assert_eq!(last.console, "Fido 2\n");
+        assert_eq!(
+            loop_iterations(&flow),
+            ["Loop iteration 1: d = Dog@3", "Loop iteration 2: d = Dog@5",]
+        );
+
+        let Value::Ref(list) = frame.lookup("dogs").unwrap().value else {
+            panic!("dogs refers to the list");
+        };
+        let object = |id: ObjectId| last.heap.iter().find(|obj| obj.id == id).unwrap();
+        let list = object(list);
+        assert_eq!(list.class_name, "ArrayList");
+        assert_eq!(list.field("size"), Some(&Value::Int(2)));
+        let Some(&Value::Ref(array)) = list.field("elementData") else {
+            panic!("the elements live in an array");
+        };
+        assert_eq!(object(array).class_name, "Object[]");
+        assert_eq!(
+            object(array).elements.as_deref(),
+            Some([Value::Ref(3), Value::Ref(5)].as_slice())
+        );
+        assert!(
+            last.heap.iter().all(|obj| obj.id != 7),
+            "Ace was replaced and collected"
+        );
+
+        let calls: Vec<&str> = flow
+            .steps
+            .iter()
+            .filter(|s| matches!(s.action, ExecutionAction::MethodCall { .. }))
+            .map(|s| s.description.as_str())
+            .filter(|d| d.contains("returns"))
+            .collect();
+        assert_eq!(
+            calls,
+            [
+                "Call method: add (returns true)",
+                "Call method: add (returns true)",
+                "Call method: remove (returns Dog@3)",
+                "Call method: set (returns Dog@7)",
+                "Call method: size (returns 2)",
+                "Call method: get (returns Dog@5)",
+                "Call method: size (returns 2)",
+            ]
+        );
+    }
+
+    #[test]
+    fn hash_maps_and_sets_match_keys_by_equality() {
+        let code = wrap_main(
+            "HashMap<String, Integer> ages = new HashMap<>();\n\
+             ages.put(\"Rex\", 3);\n\
+             ages.put(\"Fido\", 5);\n\
+             Integer old = ages.put(\"Rex\", 4);\n\
+             boolean known = ages.containsKey(\"Fido\");\n\
+             int age = ages.get(\"Rex\");\n\
+             Object missing = ages.get(\"Ace\");\n\
+             HashSet<String> seen = new HashSet<>();\n\
+             seen.add(\"a\");\n\
+             String copy = new String(\"a\");\n\
+             boolean again = seen.add(copy);\n\
+             LinkedList<Integer> queue = new LinkedList<>();\n\
+             queue.add(1);\n\
+             queue.addFirst(0);\n\
+             System.out.println(ages + \" \" + seen + \" \" + queue);",
+        );
+        let flow = analyze_flow(&code);
+        let last = flow.steps.last().unwrap();
+        let frame = &last.frames[0];
+        assert_eq!(frame.lookup("old").unwrap().value, Value::Int(3));
+        assert_eq!(frame.lookup("known").unwrap().value, Value::Bool(true));
+        assert_eq!(frame.lookup("age").unwrap().value, Value::Int(4));
+        assert_eq!(frame.lookup("missing").unwrap().value, Value::Null);
+        assert_eq!(frame.lookup("again").unwrap().value, Value::Bool(false));
+        assert_eq!(last.console, "{Rex=4, Fido=5} [a] [0, 1]\n");
+    }
+
+    #[test]
+    fn a_bad_list_index_throws_index_out_of_bounds() {
+        let code = wrap_main(
+            "ArrayList<Integer> values = new ArrayList<>();\n\
+             values.add(7);\n\
+             int x = values.get(2);",
+        );
+        let flow = analyze_flow(&code);
+        assert_eq!(
+            raised(&flow).unwrap().description,
+            "IndexOutOfBoundsException: Index 2 out of bounds for length 1"
+        );
+        assert!(!flow.steps.iter().any(|s| matches!(
+            &s.action,
+            ExecutionAction::VariableAssignment { variable_name, .. } if variable_name == "x"
+        )));
+    }
 }
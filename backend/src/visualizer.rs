@@ -111,10 +111,12 @@ impl JavaVisualizer {
             .map(|_| true)
     }
 
-    /// Analyze execution flow starting from main method
+    /// Analyze execution flow starting from main method, with `stdin` as the
+    /// input its `Scanner` reads consume
     pub fn analyze_execution_flow(
         &mut self,
         java_code: &str,
+        stdin: Option<&str>,
     ) -> Result<ExecutionVisualizationResult> {
         // First do static analysis
         let tree = self
@@ -125,7 +127,8 @@ impl JavaVisualizer {
         let static_analysis = self.analyzer.analyze(&root_node, java_code);
 
         // Then do execution flow analysis
-        let mut execution_analyzer = ExecutionAnalyzer::new(static_analysis.clone());
+        let mut execution_analyzer =
+            ExecutionAnalyzer::new(static_analysis.clone()).with_stdin(stdin);
         let execution_flow = execution_analyzer.analyze_execution_flow(&root_node, java_code);
 
         // Generate step-by-step execution graphs
@@ -143,6 +146,7 @@ impl JavaVisualizer {
     pub fn analyze_execution_flow_with_config(
         &mut self,
         java_code: &str,
+        stdin: Option<&str>,
         execution_config: ExecutionGraphConfig,
     ) -> Result<ExecutionVisualizationResult> {
         // First do static analysis
@@ -154,7 +158,8 @@ impl JavaVisualizer {
         let static_analysis = self.analyzer.analyze(&root_node, java_code);
 
         // Then do execution flow analysis
-        let mut execution_analyzer = ExecutionAnalyzer::new(static_analysis.clone());
+        let mut execution_analyzer =
+            ExecutionAnalyzer::new(static_analysis.clone()).with_stdin(stdin);
         let execution_flow = execution_analyzer.analyze_execution_flow(&root_node, java_code);
 
         // Generate step-by-step execution graphs with custom config
@@ -169,7 +174,11 @@ impl JavaVisualizer {
     }
 
     /// Generate only execution flow without graphs (for performance)
-    pub fn get_execution_flow_only(&mut self, java_code: &str) -> Result<ExecutionFlow> {
+    pub fn get_execution_flow_only(
+        &mut self,
+        java_code: &str,
+        stdin: Option<&str>,
+    ) -> Result<ExecutionFlow> {
         let tree = self
             .parser
             .parse(java_code)
@@ -177,7 +186,7 @@ impl JavaVisualizer {
         let root_node = self.parser.get_root_node(&tree);
         let static_analysis = self.analyzer.analyze(&root_node, java_code);
 
-        let mut execution_analyzer = ExecutionAnalyzer::new(static_analysis);
+        let mut execution_analyzer = ExecutionAnalyzer::new(static_analysis).with_stdin(stdin);
         Ok(execution_analyzer.analyze_execution_flow(&root_node, java_code))
     }
 }
@@ -8,7 +8,7 @@ import panzoom from 'https://cdn.jsdelivr.net/npm/panzoom@9.4.3/+esm'
     const wasmExecFlowGen = mod.cwrap(
         'wasm_execution_flow_gen',
         'string',
-        ['string']
+        ['string', 'string']
     );
 
     const wasmNoFlowGen = mod.cwrap(
@@ -38,7 +38,7 @@ import panzoom from 'https://cdn.jsdelivr.net/npm/panzoom@9.4.3/+esm'
     var execDotArray;
     async function update() {
         var dotCode = wasmVisualizeJavaCode(getEditorContent());
-        var execDotCode = wasmExecFlowGen(getEditorContent());
+        var execDotCode = wasmExecFlowGen(getEditorContent(), null);
         execDotArray = JSON.parse(execDotCode);
         execDotArrayLen = execDotArray.length;
 